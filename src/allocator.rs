@@ -0,0 +1,363 @@
+
+use std::cmp;
+use std::collections::HashMap;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use libc::{self, c_void};
+use vks::*;
+
+/// See vulkan specification, section 10.1 Host Memory
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SystemAllocationScope {
+    Command = 0,
+    Object = 1,
+    Cache = 2,
+    Device = 3,
+    Instance = 4,
+}
+
+impl From<VkSystemAllocationScope> for SystemAllocationScope {
+    fn from(vk: VkSystemAllocationScope) -> SystemAllocationScope {
+        unsafe {
+            mem::transmute(vk.as_raw())
+        }
+    }
+}
+
+/// See vulkan specification, section 10.1 Host Memory
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum InternalAllocationType {
+    Executable = 0,
+}
+
+impl From<VkInternalAllocationType> for InternalAllocationType {
+    fn from(vk: VkInternalAllocationType) -> InternalAllocationType {
+        unsafe {
+            mem::transmute(vk.as_raw())
+        }
+    }
+}
+
+/// Host memory allocation functions for the Vulkan implementation to use in
+/// place of its own.  See vulkan specification, section 10.1 Host Memory for
+/// the requirements each function must meet.  These may be called from any
+/// thread.  A panic is caught before it reaches the driver, and fails the
+/// allocation.
+pub trait HostAllocator: Send + Sync {
+    /// Allocate `size` bytes aligned to `alignment`, or return null on failure
+    fn allocate(&self, size: usize, alignment: usize, scope: SystemAllocationScope)
+                -> *mut c_void;
+
+    /// Resize an allocation made by this allocator, preserving its contents
+    /// and alignment.  `original` may be null (allocate) and `size` may be zero
+    /// (free, returning null).
+    fn reallocate(&self, original: *mut c_void, size: usize, alignment: usize,
+                  scope: SystemAllocationScope) -> *mut c_void;
+
+    /// Free an allocation made by this allocator.  `memory` may be null.
+    fn free(&self, memory: *mut c_void);
+
+    /// Notification that the implementation allocated memory on its own
+    fn internal_allocation(&self, _size: usize, _allocation_type: InternalAllocationType,
+                           _scope: SystemAllocationScope)
+    {
+    }
+
+    /// Notification that the implementation freed memory on its own
+    fn internal_free(&self, _size: usize, _allocation_type: InternalAllocationType,
+                     _scope: SystemAllocationScope)
+    {
+    }
+}
+
+struct AllocatorInner {
+    host: Box<HostAllocator>,
+    // pUserData points back at this AllocatorInner, which does not move since
+    // it lives inside the Arc.
+    callbacks: VkAllocationCallbacks,
+}
+
+// The raw pointer in callbacks only refers to ourself, and the host allocator
+// is itself Send + Sync.
+unsafe impl Send for AllocatorInner {}
+unsafe impl Sync for AllocatorInner {}
+
+/// A `HostAllocator` bridged to `VkAllocationCallbacks`.  Objects created with
+/// an allocator keep a clone of it, so that they are destroyed with the same
+/// allocator they were created with.
+#[derive(Clone)]
+pub struct Allocator(Arc<AllocatorInner>);
+
+impl Allocator {
+    pub fn new<A: HostAllocator + 'static>(host: A) -> Allocator
+    {
+        let mut inner = Arc::new(AllocatorInner {
+            host: Box::new(host),
+            callbacks: VkAllocationCallbacks {
+                pUserData: ptr::null_mut(),
+                pfnAllocation: allocation,
+                pfnReallocation: reallocation,
+                pfnFree: free,
+                pfnInternalAllocation: internal_allocation,
+                pfnInternalFree: internal_free,
+            },
+        });
+        let p_user_data = &*inner as *const AllocatorInner as *mut c_void;
+        Arc::get_mut(&mut inner).unwrap().callbacks.pUserData = p_user_data;
+        Allocator(inner)
+    }
+
+    pub fn inner(&self) -> *const VkAllocationCallbacks
+    {
+        &self.0.callbacks
+    }
+}
+
+/// The `pAllocator` argument for an optional allocator
+pub fn vk_allocator(allocator: &Option<Allocator>) -> *const VkAllocationCallbacks
+{
+    match *allocator {
+        Some(ref a) => a.inner(),
+        None => ptr::null(),
+    }
+}
+
+unsafe fn host<'a>(p_user_data: *mut c_void) -> &'a HostAllocator
+{
+    &*(*(p_user_data as *const AllocatorInner)).host
+}
+
+// The callbacks below must not unwind into the driver, so a panic in the
+// host allocator is caught: allocations then fail, and frees and
+// notifications are ignored.
+
+unsafe extern "system" fn allocation(p_user_data: *mut c_void, size: usize, alignment: usize,
+                                     allocation_scope: VkSystemAllocationScope)
+                                     -> *mut c_void
+{
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        host(p_user_data).allocate(size, alignment, From::from(allocation_scope))
+    })).unwrap_or(ptr::null_mut())
+}
+
+unsafe extern "system" fn reallocation(p_user_data: *mut c_void, p_original: *mut c_void,
+                                       size: usize, alignment: usize,
+                                       allocation_scope: VkSystemAllocationScope)
+                                       -> *mut c_void
+{
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        host(p_user_data).reallocate(p_original, size, alignment, From::from(allocation_scope))
+    })).unwrap_or(ptr::null_mut())
+}
+
+unsafe extern "system" fn free(p_user_data: *mut c_void, p_memory: *mut c_void)
+{
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        host(p_user_data).free(p_memory)
+    }));
+}
+
+unsafe extern "system" fn internal_allocation(p_user_data: *mut c_void, size: usize,
+                                              allocation_type: VkInternalAllocationType,
+                                              allocation_scope: VkSystemAllocationScope)
+{
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        host(p_user_data).internal_allocation(size, From::from(allocation_type),
+                                              From::from(allocation_scope))
+    }));
+}
+
+unsafe extern "system" fn internal_free(p_user_data: *mut c_void, size: usize,
+                                        allocation_type: VkInternalAllocationType,
+                                        allocation_scope: VkSystemAllocationScope)
+{
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        host(p_user_data).internal_free(size, From::from(allocation_type),
+                                        From::from(allocation_scope))
+    }));
+}
+
+/// A `HostAllocator` using the C library malloc and free.  Each allocation is
+/// preceded by a small header recording the size and the pointer returned by
+/// malloc, so that any alignment can be honoured.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemAllocator;
+
+const HEADER_SIZE: usize = 2 * mem::size_of::<usize>();
+
+impl SystemAllocator {
+    // The size that was requested for an allocation we made
+    unsafe fn size_of(memory: *mut c_void) -> usize
+    {
+        *((memory as usize - HEADER_SIZE) as *const usize)
+    }
+}
+
+impl HostAllocator for SystemAllocator {
+    fn allocate(&self, size: usize, alignment: usize, _scope: SystemAllocationScope)
+                -> *mut c_void
+    {
+        if !alignment.is_power_of_two() {
+            return ptr::null_mut();
+        }
+        let alignment = cmp::max(alignment, mem::align_of::<usize>());
+        let total = match size.checked_add(HEADER_SIZE).and_then(|n| n.checked_add(alignment)) {
+            Some(total) => total,
+            None => return ptr::null_mut(),
+        };
+        unsafe {
+            let raw = libc::malloc(total) as usize;
+            if raw == 0 {
+                return ptr::null_mut();
+            }
+            let aligned = (raw + HEADER_SIZE + alignment - 1) & !(alignment - 1);
+            *((aligned - HEADER_SIZE) as *mut usize) = size;
+            *((aligned - mem::size_of::<usize>()) as *mut usize) = raw;
+            aligned as *mut c_void
+        }
+    }
+
+    fn reallocate(&self, original: *mut c_void, size: usize, alignment: usize,
+                  scope: SystemAllocationScope) -> *mut c_void
+    {
+        if original.is_null() {
+            return self.allocate(size, alignment, scope);
+        }
+        if size == 0 {
+            self.free(original);
+            return ptr::null_mut();
+        }
+        let memory = self.allocate(size, alignment, scope);
+        if !memory.is_null() {
+            unsafe {
+                let copy_size = cmp::min(size, SystemAllocator::size_of(original));
+                ptr::copy_nonoverlapping(original as *const u8, memory as *mut u8, copy_size);
+            }
+            self.free(original);
+        }
+        memory
+    }
+
+    fn free(&self, memory: *mut c_void)
+    {
+        if memory.is_null() {
+            return;
+        }
+        unsafe {
+            let raw = *((memory as usize - mem::size_of::<usize>()) as *const usize);
+            libc::free(raw as *mut c_void);
+        }
+    }
+}
+
+#[derive(Default)]
+struct TrackingState {
+    allocations: HashMap<usize, (usize, SystemAllocationScope)>,
+    internal: HashMap<SystemAllocationScope, usize>,
+}
+
+/// A `HostAllocator` which allocates with the `SystemAllocator` and keeps
+/// track of how many bytes are live in each allocation scope.  Clones share
+/// the same statistics, so keep a clone to inspect after handing one to
+/// `Allocator::new()`.
+#[derive(Clone, Default)]
+pub struct TrackingAllocator {
+    state: Arc<Mutex<TrackingState>>,
+}
+
+impl TrackingAllocator {
+    pub fn new() -> TrackingAllocator
+    {
+        Default::default()
+    }
+
+    // The allocation callbacks must not panic, and the statistics are still
+    // usable if a thread panicked while holding the lock
+    fn state(&self) -> MutexGuard<TrackingState>
+    {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Bytes currently allocated through us in the given scope
+    pub fn live_bytes(&self, scope: SystemAllocationScope) -> usize
+    {
+        let state = self.state();
+        state.allocations.values()
+            .filter(|&&(_, s)| s == scope)
+            .map(|&(size, _)| size)
+            .sum()
+    }
+
+    /// Bytes currently allocated through us, in all scopes
+    pub fn total_live_bytes(&self) -> usize
+    {
+        let state = self.state();
+        state.allocations.values().map(|&(size, _)| size).sum()
+    }
+
+    /// Number of allocations currently live, in all scopes
+    pub fn live_allocations(&self) -> usize
+    {
+        self.state().allocations.len()
+    }
+
+    /// Bytes the implementation reports having allocated internally in the
+    /// given scope
+    pub fn internal_bytes(&self, scope: SystemAllocationScope) -> usize
+    {
+        *self.state().internal.get(&scope).unwrap_or(&0)
+    }
+}
+
+impl HostAllocator for TrackingAllocator {
+    fn allocate(&self, size: usize, alignment: usize, scope: SystemAllocationScope)
+                -> *mut c_void
+    {
+        let memory = SystemAllocator.allocate(size, alignment, scope);
+        if !memory.is_null() {
+            let mut state = self.state();
+            state.allocations.insert(memory as usize, (size, scope));
+        }
+        memory
+    }
+
+    fn reallocate(&self, original: *mut c_void, size: usize, alignment: usize,
+                  scope: SystemAllocationScope) -> *mut c_void
+    {
+        let memory = SystemAllocator.reallocate(original, size, alignment, scope);
+        if !memory.is_null() || size == 0 {
+            let mut state = self.state();
+            state.allocations.remove(&(original as usize));
+            if !memory.is_null() {
+                state.allocations.insert(memory as usize, (size, scope));
+            }
+        }
+        memory
+    }
+
+    fn free(&self, memory: *mut c_void)
+    {
+        self.state().allocations.remove(&(memory as usize));
+        SystemAllocator.free(memory)
+    }
+
+    fn internal_allocation(&self, size: usize, _allocation_type: InternalAllocationType,
+                           scope: SystemAllocationScope)
+    {
+        let mut state = self.state();
+        let bytes = state.internal.entry(scope).or_insert(0);
+        *bytes = bytes.saturating_add(size);
+    }
+
+    fn internal_free(&self, size: usize, _allocation_type: InternalAllocationType,
+                     scope: SystemAllocationScope)
+    {
+        let mut state = self.state();
+        let bytes = state.internal.entry(scope).or_insert(0);
+        *bytes = bytes.saturating_sub(size);
+    }
+}
@@ -0,0 +1,55 @@
+//! Print a report of the Vulkan implementation and every physical device, for
+//! attaching to bug reports.
+//!
+//! Usage: sarek-info [--json] [--output FILE]
+
+extern crate sarek;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, Write};
+use std::process;
+use sarek::InstanceLoader;
+use sarek::info::Report;
+
+const USAGE: &'static str = "Usage: sarek-info [--json] [--output FILE]";
+
+fn main() {
+    let mut json = false;
+    let mut output: Option<String> = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match &*arg {
+            "--json" => json = true,
+            "--output" | "-o" => match args.next() {
+                Some(path) => output = Some(path),
+                None => fail(USAGE),
+            },
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return;
+            },
+            _ => fail(USAGE),
+        }
+    }
+
+    let report = match InstanceLoader::load_system().and_then(Report::collect) {
+        Ok(report) => report,
+        Err(e) => fail(&format!("sarek-info: {}", e)),
+    };
+    let text = if json { report.to_json() } else { report.to_text() };
+
+    let written = match output {
+        Some(ref path) => File::create(path).and_then(|mut f| f.write_all(text.as_bytes())),
+        None => io::stdout().write_all(text.as_bytes()),
+    };
+    if let Err(e) = written {
+        fail(&format!("sarek-info: {}", e));
+    }
+}
+
+fn fail(message: &str) -> ! {
+    let _ = writeln!(io::stderr(), "{}", message);
+    process::exit(1);
+}
@@ -5,14 +5,278 @@ use std::fmt;
 
 use std::ffi::NulError;
 use std::str::Utf8Error;
-use vks::VkResult;
+use vks::*;
+use instance::physical_device::DeviceRejection;
+
+/// The kind of name which was requested but found not to be present
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameKind {
+    Layer,
+    InstanceExtension,
+    DeviceExtension,
+    DeviceFeature,
+}
+
+impl fmt::Display for NameKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NameKind::Layer => write!(f, "layer"),
+            NameKind::InstanceExtension => write!(f, "instance extension"),
+            NameKind::DeviceExtension => write!(f, "device extension"),
+            NameKind::DeviceFeature => write!(f, "device feature"),
+        }
+    }
+}
+
+/// A requested layer or extension which is not available, along with the
+/// available names which look similar to it (most similar first)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingName {
+    pub kind: NameKind,
+    pub name: String,
+    pub similar: Vec<String>,
+}
+
+impl MissingName {
+    /// Check each requested name against the available names, returning
+    /// those which are missing.
+    pub fn find(kind: NameKind, requested: &[String], available: &[String])
+                -> Vec<MissingName>
+    {
+        requested.iter()
+            .filter(|name| !available.contains(name))
+            .map(|name| MissingName {
+                kind: kind,
+                name: name.clone(),
+                similar: similar_names(name, available),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for MissingName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.name)?;
+        if !self.similar.is_empty() {
+            write!(f, " (did you mean {}?)", self.similar.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+// Available names that are close to `name`, either by containing one another
+// (ignoring case) or by a small edit distance.
+fn similar_names(name: &str, available: &[String]) -> Vec<String>
+{
+    let name = name.to_lowercase();
+    let max_distance = ::std::cmp::max(2, name.len() / 4);
+
+    let mut candidates: Vec<(usize, &String)> = available.iter()
+        .filter_map(|candidate| {
+            let lower = candidate.to_lowercase();
+            let distance = edit_distance(&name, &lower);
+            if distance <= max_distance || lower.contains(&name) || name.contains(&lower) {
+                Some((distance, candidate))
+            } else {
+                None
+            }
+        })
+        .collect();
+    candidates.sort();
+    candidates.into_iter().take(3).map(|(_, candidate)| candidate.clone()).collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize
+{
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..b.len() + 1).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for j in 0..b.len() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == b[j] {
+                diagonal
+            } else {
+                1 + ::std::cmp::min(diagonal, ::std::cmp::min(above, row[j]))
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+// Raw values of result codes from extensions, which vks only defines when the
+// extension feature is enabled
+const ERROR_SURFACE_LOST_KHR: i32 = -1000000000;
+const SUBOPTIMAL_KHR: i32 = 1000001003;
+const ERROR_OUT_OF_DATE_KHR: i32 = -1000001004;
+
+/// The success codes a Vulkan command may return.  See vulkan specification,
+/// section 2.6.2 Return Codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    NotReady,
+    Timeout,
+    EventSet,
+    EventReset,
+    Incomplete,
+    SuboptimalKHR,
+}
+
+impl Status {
+    /// The status for a success code, or None for an error code
+    pub fn from_vk(vk: VkResult) -> Option<Status>
+    {
+        if vk == VK_SUCCESS {
+            Some(Status::Success)
+        } else if vk == VK_NOT_READY {
+            Some(Status::NotReady)
+        } else if vk == VK_TIMEOUT {
+            Some(Status::Timeout)
+        } else if vk == VK_EVENT_SET {
+            Some(Status::EventSet)
+        } else if vk == VK_EVENT_RESET {
+            Some(Status::EventReset)
+        } else if vk == VK_INCOMPLETE {
+            Some(Status::Incomplete)
+        } else if vk.as_raw() == SUBOPTIMAL_KHR {
+            Some(Status::SuboptimalKHR)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_incomplete(&self) -> bool
+    {
+        *self == Status::Incomplete
+    }
+
+    pub fn is_suboptimal(&self) -> bool
+    {
+        *self == Status::SuboptimalKHR
+    }
+}
+
+/// A failed Vulkan command: the result it returned, the entry point which
+/// returned it, and the sarek method which called it (if known)
+#[derive(Debug, Clone)]
+pub struct VulkanError {
+    pub result: VkResult,
+    pub entry_point: &'static str,
+    pub method: Option<&'static str>,
+}
+
+impl VulkanError {
+    pub fn new(result: VkResult, entry_point: &'static str, method: Option<&'static str>)
+               -> VulkanError
+    {
+        VulkanError {
+            result: result,
+            entry_point: entry_point,
+            method: method,
+        }
+    }
+
+    pub fn is_device_lost(&self) -> bool
+    {
+        self.result == VK_ERROR_DEVICE_LOST
+    }
+
+    pub fn is_out_of_date(&self) -> bool
+    {
+        self.result.as_raw() == ERROR_OUT_OF_DATE_KHR
+    }
+
+    /// Out of host or device memory
+    pub fn is_out_of_memory(&self) -> bool
+    {
+        self.result == VK_ERROR_OUT_OF_HOST_MEMORY || self.result == VK_ERROR_OUT_OF_DEVICE_MEMORY
+    }
+
+    pub fn is_surface_lost(&self) -> bool
+    {
+        self.result.as_raw() == ERROR_SURFACE_LOST_KHR
+    }
+
+    /// Whether the result was really a success code (such as VK_INCOMPLETE)
+    /// returned where only VK_SUCCESS was expected
+    pub fn is_success_code(&self) -> bool
+    {
+        Status::from_vk(self.result).is_some()
+    }
+}
+
+impl fmt::Display for VulkanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.entry_point.is_empty() {
+            write!(f, "{:?}", self.result)?;
+        } else {
+            write!(f, "{} returned {:?}", self.entry_point, self.result)?;
+        }
+        if let Some(method) = self.method {
+            write!(f, " (in {})", method)?;
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug)]
 pub enum Error {
     General(String),
     Nul(NulError),
-    Vulkan(VkResult),
+    Vulkan(VulkanError),
     StrUtf8(Utf8Error),
+    NotPresent(Vec<MissingName>),
+    VulkanNotAvailable(String),
+    NoSuitablePhysicalDevice(Vec<DeviceRejection>),
+    /// A function of an extension (named here) which was not enabled when
+    /// the instance or device was created
+    ExtensionNotEnabled(String),
+}
+
+impl Error {
+    /// The failing Vulkan result, if this is a Vulkan error
+    pub fn vk_result(&self) -> Option<VkResult>
+    {
+        match *self {
+            Error::Vulkan(ref e) => Some(e.result),
+            _ => None,
+        }
+    }
+
+    pub fn is_device_lost(&self) -> bool
+    {
+        match *self {
+            Error::Vulkan(ref e) => e.is_device_lost(),
+            _ => false,
+        }
+    }
+
+    pub fn is_out_of_date(&self) -> bool
+    {
+        match *self {
+            Error::Vulkan(ref e) => e.is_out_of_date(),
+            _ => false,
+        }
+    }
+
+    pub fn is_out_of_memory(&self) -> bool
+    {
+        match *self {
+            Error::Vulkan(ref e) => e.is_out_of_memory(),
+            _ => false,
+        }
+    }
+
+    pub fn is_surface_lost(&self) -> bool
+    {
+        match *self {
+            Error::Vulkan(ref e) => e.is_surface_lost(),
+            _ => false,
+        }
+    }
 }
 
 impl StdError for Error {
@@ -23,6 +287,10 @@ impl StdError for Error {
             Error::Nul(_) => "Nul Error",
             Error::Vulkan(_) => "Vulkan Error",
             Error::StrUtf8(_) => "UTF-8 Error",
+            Error::NotPresent(_) => "Layers, Extensions or Features Not Present",
+            Error::VulkanNotAvailable(_) => "Vulkan Not Available",
+            Error::NoSuitablePhysicalDevice(_) => "No Suitable Physical Device",
+            Error::ExtensionNotEnabled(_) => "Extension Not Enabled",
         }
     }
 
@@ -40,8 +308,30 @@ impl fmt::Display for Error {
         match *self {
             Error::General(ref s) => write!(f, "{}", s),
             Error::Nul(ref e) => write!(f, "{}: {}", self.description(), e),
-            Error::Vulkan(ref e) => write!(f, "{}: {:?}", self.description(), e),
+            Error::Vulkan(ref e) => write!(f, "{}: {}", self.description(), e),
             Error::StrUtf8(ref e) => write!(f, "{}: {}", self.description(), e),
+            Error::VulkanNotAvailable(ref s) => write!(f, "{}: {}", self.description(), s),
+            Error::ExtensionNotEnabled(ref s) => write!(f, "{}: {}", self.description(), s),
+            Error::NotPresent(ref missing) => {
+                write!(f, "{}: ", self.description())?;
+                for (i, m) in missing.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", m)?;
+                }
+                Ok(())
+            },
+            Error::NoSuitablePhysicalDevice(ref rejections) => {
+                write!(f, "{}", self.description())?;
+                if rejections.is_empty() {
+                    write!(f, ": no physical devices found")?;
+                }
+                for rejection in rejections {
+                    write!(f, "; {}", rejection)?;
+                }
+                Ok(())
+            },
         }
     }
 }
@@ -66,7 +356,13 @@ impl From<NulError> for Error {
 
 impl From<VkResult> for Error {
     fn from(vk: VkResult) -> Error {
-        Error::Vulkan(vk)
+        Error::Vulkan(VulkanError::new(vk, "", None))
+    }
+}
+
+impl From<VulkanError> for Error {
+    fn from(e: VulkanError) -> Error {
+        Error::Vulkan(e)
     }
 }
 
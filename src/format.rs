@@ -1,6 +1,9 @@
 
 use std::mem;
 use vks::{VkFormat, VkFormatFeatureFlags, VkFormatProperties};
+use {Extent3D};
+use image::{ImageTiling, ImageAspectFlags, IMAGE_ASPECT_COLOR_BIT, IMAGE_ASPECT_DEPTH_BIT,
+            IMAGE_ASPECT_STENCIL_BIT};
 
 #[repr(u32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -224,6 +227,457 @@ impl Into<VkFormat> for Format {
     }
 }
 
+impl Format {
+    /// Every format except `Undefined`, in order of value
+    pub fn all() -> Vec<Format>
+    {
+        let mut formats: Vec<Format> = (Format::R4g4UnormPack8 as u32
+                                        ..Format::Astc12x12SrgbBlock as u32 + 1)
+            .map(|raw| unsafe { mem::transmute(raw) })
+            .collect();
+        #[cfg(feature = "img_format_pvrtc")]
+        {
+            formats.extend((Format::Pvrtc12bppUnormBlockImg as u32
+                            ..Format::Pvrtc24bppSrgbBlockImg as u32 + 1)
+                           .map(|raw| unsafe { mem::transmute::<u32, Format>(raw) }));
+        }
+        formats
+    }
+
+    /// Size in bytes of one texel block (one texel, unless the format is
+    /// compressed).  Zero for `Undefined`.  See vulkan specification, section
+    /// 32.1 Format Definition
+    pub fn block_size(&self) -> u32
+    {
+        format_info(*self).map_or(0, |info| info.block_size)
+    }
+
+    /// Texels covered by one block: 1x1x1 unless the format is compressed
+    pub fn block_extent(&self) -> Extent3D
+    {
+        let (width, height) = format_info(*self).map_or((1, 1), |info| info.block_extent);
+        Extent3D { width: width, height: height, depth: 1 }
+    }
+
+    /// The components in memory order, with their size in bits.  Compressed
+    /// formats give zero bits, and the shared exponent of
+    /// `E5b9g9r9UfloatPack32` and the padding of `X8D24UnormPack32` are not
+    /// included.
+    pub fn components(&self) -> Vec<(Component, u32)>
+    {
+        format_info(*self).map_or(vec![], |info| info.components.to_vec())
+    }
+
+    pub fn component_count(&self) -> u32
+    {
+        format_info(*self).map_or(0, |info| info.components.len() as u32)
+    }
+
+    /// Size in bits of a component, or None if the format does not have it
+    pub fn component_bits(&self, component: Component) -> Option<u32>
+    {
+        format_info(*self).and_then(|info| {
+            info.components.iter().find(|&&(c, _)| c == component).map(|&(_, bits)| bits)
+        })
+    }
+
+    /// How the components are interpreted.  For combined depth/stencil
+    /// formats this is the type of the depth component; stencil is always
+    /// `Uint`.
+    pub fn numeric_type(&self) -> Option<NumericType>
+    {
+        format_info(*self).map(|info| info.numeric_type)
+    }
+
+    pub fn is_depth(&self) -> bool
+    {
+        self.component_bits(Component::D).is_some()
+    }
+
+    pub fn is_stencil(&self) -> bool
+    {
+        self.component_bits(Component::S).is_some()
+    }
+
+    pub fn is_compressed(&self) -> bool
+    {
+        format_info(*self).map_or(false, |info| info.block_extent != (1, 1))
+    }
+
+    pub fn is_srgb(&self) -> bool
+    {
+        self.numeric_type() == Some(NumericType::Srgb)
+    }
+
+    /// The aspects an image of this format has.  See vulkan specification,
+    /// section 11.5 Image Views
+    pub fn aspect_mask(&self) -> ImageAspectFlags
+    {
+        let mut aspects = ImageAspectFlags::empty();
+        if self.is_depth() {
+            aspects |= IMAGE_ASPECT_DEPTH_BIT;
+        }
+        if self.is_stencil() {
+            aspects |= IMAGE_ASPECT_STENCIL_BIT;
+        }
+        if aspects.is_empty() && *self != Format::Undefined {
+            aspects = IMAGE_ASPECT_COLOR_BIT;
+        }
+        aspects
+    }
+
+    /// The `Srgb` format with the same layout as this `Unorm` one, if there
+    /// is one
+    pub fn to_srgb(&self) -> Option<Format>
+    {
+        match *self {
+            Format::R8Unorm => Some(Format::R8Srgb),
+            Format::R8g8Unorm => Some(Format::R8g8Srgb),
+            Format::R8g8b8Unorm => Some(Format::R8g8b8Srgb),
+            Format::B8g8r8Unorm => Some(Format::B8g8r8Srgb),
+            Format::R8g8b8a8Unorm => Some(Format::R8g8b8a8Srgb),
+            Format::B8g8r8a8Unorm => Some(Format::B8g8r8a8Srgb),
+            Format::A8b8g8r8UnormPack32 => Some(Format::A8b8g8r8SrgbPack32),
+            Format::Bc1RgbUnormBlock => Some(Format::Bc1RgbSrgbBlock),
+            Format::Bc1RgbaUnormBlock => Some(Format::Bc1RgbaSrgbBlock),
+            Format::Bc2UnormBlock => Some(Format::Bc2SrgbBlock),
+            Format::Bc3UnormBlock => Some(Format::Bc3SrgbBlock),
+            Format::Bc7UnormBlock => Some(Format::Bc7SrgbBlock),
+            Format::Etc2R8g8b8UnormBlock => Some(Format::Etc2R8g8b8SrgbBlock),
+            Format::Etc2R8g8b8a1UnormBlock => Some(Format::Etc2R8g8b8a1SrgbBlock),
+            Format::Etc2R8g8b8a8UnormBlock => Some(Format::Etc2R8g8b8a8SrgbBlock),
+            Format::Astc4x4UnormBlock => Some(Format::Astc4x4SrgbBlock),
+            Format::Astc5x4UnormBlock => Some(Format::Astc5x4SrgbBlock),
+            Format::Astc5x5UnormBlock => Some(Format::Astc5x5SrgbBlock),
+            Format::Astc6x5UnormBlock => Some(Format::Astc6x5SrgbBlock),
+            Format::Astc6x6UnormBlock => Some(Format::Astc6x6SrgbBlock),
+            Format::Astc8x5UnormBlock => Some(Format::Astc8x5SrgbBlock),
+            Format::Astc8x6UnormBlock => Some(Format::Astc8x6SrgbBlock),
+            Format::Astc8x8UnormBlock => Some(Format::Astc8x8SrgbBlock),
+            Format::Astc10x5UnormBlock => Some(Format::Astc10x5SrgbBlock),
+            Format::Astc10x6UnormBlock => Some(Format::Astc10x6SrgbBlock),
+            Format::Astc10x8UnormBlock => Some(Format::Astc10x8SrgbBlock),
+            Format::Astc10x10UnormBlock => Some(Format::Astc10x10SrgbBlock),
+            Format::Astc12x10UnormBlock => Some(Format::Astc12x10SrgbBlock),
+            Format::Astc12x12UnormBlock => Some(Format::Astc12x12SrgbBlock),
+            #[cfg(feature = "img_format_pvrtc")]
+            Format::Pvrtc12bppUnormBlockImg => Some(Format::Pvrtc12bppSrgbBlockImg),
+            #[cfg(feature = "img_format_pvrtc")]
+            Format::Pvrtc14bppUnormBlockImg => Some(Format::Pvrtc14bppSrgbBlockImg),
+            #[cfg(feature = "img_format_pvrtc")]
+            Format::Pvrtc22bppUnormBlockImg => Some(Format::Pvrtc22bppSrgbBlockImg),
+            #[cfg(feature = "img_format_pvrtc")]
+            Format::Pvrtc24bppUnormBlockImg => Some(Format::Pvrtc24bppSrgbBlockImg),
+            _ => None,
+        }
+    }
+
+    /// The `Unorm` format with the same layout as this `Srgb` one
+    pub fn to_unorm(&self) -> Option<Format>
+    {
+        match *self {
+            Format::R8Srgb => Some(Format::R8Unorm),
+            Format::R8g8Srgb => Some(Format::R8g8Unorm),
+            Format::R8g8b8Srgb => Some(Format::R8g8b8Unorm),
+            Format::B8g8r8Srgb => Some(Format::B8g8r8Unorm),
+            Format::R8g8b8a8Srgb => Some(Format::R8g8b8a8Unorm),
+            Format::B8g8r8a8Srgb => Some(Format::B8g8r8a8Unorm),
+            Format::A8b8g8r8SrgbPack32 => Some(Format::A8b8g8r8UnormPack32),
+            Format::Bc1RgbSrgbBlock => Some(Format::Bc1RgbUnormBlock),
+            Format::Bc1RgbaSrgbBlock => Some(Format::Bc1RgbaUnormBlock),
+            Format::Bc2SrgbBlock => Some(Format::Bc2UnormBlock),
+            Format::Bc3SrgbBlock => Some(Format::Bc3UnormBlock),
+            Format::Bc7SrgbBlock => Some(Format::Bc7UnormBlock),
+            Format::Etc2R8g8b8SrgbBlock => Some(Format::Etc2R8g8b8UnormBlock),
+            Format::Etc2R8g8b8a1SrgbBlock => Some(Format::Etc2R8g8b8a1UnormBlock),
+            Format::Etc2R8g8b8a8SrgbBlock => Some(Format::Etc2R8g8b8a8UnormBlock),
+            Format::Astc4x4SrgbBlock => Some(Format::Astc4x4UnormBlock),
+            Format::Astc5x4SrgbBlock => Some(Format::Astc5x4UnormBlock),
+            Format::Astc5x5SrgbBlock => Some(Format::Astc5x5UnormBlock),
+            Format::Astc6x5SrgbBlock => Some(Format::Astc6x5UnormBlock),
+            Format::Astc6x6SrgbBlock => Some(Format::Astc6x6UnormBlock),
+            Format::Astc8x5SrgbBlock => Some(Format::Astc8x5UnormBlock),
+            Format::Astc8x6SrgbBlock => Some(Format::Astc8x6UnormBlock),
+            Format::Astc8x8SrgbBlock => Some(Format::Astc8x8UnormBlock),
+            Format::Astc10x5SrgbBlock => Some(Format::Astc10x5UnormBlock),
+            Format::Astc10x6SrgbBlock => Some(Format::Astc10x6UnormBlock),
+            Format::Astc10x8SrgbBlock => Some(Format::Astc10x8UnormBlock),
+            Format::Astc10x10SrgbBlock => Some(Format::Astc10x10UnormBlock),
+            Format::Astc12x10SrgbBlock => Some(Format::Astc12x10UnormBlock),
+            Format::Astc12x12SrgbBlock => Some(Format::Astc12x12UnormBlock),
+            #[cfg(feature = "img_format_pvrtc")]
+            Format::Pvrtc12bppSrgbBlockImg => Some(Format::Pvrtc12bppUnormBlockImg),
+            #[cfg(feature = "img_format_pvrtc")]
+            Format::Pvrtc14bppSrgbBlockImg => Some(Format::Pvrtc14bppUnormBlockImg),
+            #[cfg(feature = "img_format_pvrtc")]
+            Format::Pvrtc22bppSrgbBlockImg => Some(Format::Pvrtc22bppUnormBlockImg),
+            #[cfg(feature = "img_format_pvrtc")]
+            Format::Pvrtc24bppSrgbBlockImg => Some(Format::Pvrtc24bppUnormBlockImg),
+            _ => None,
+        }
+    }
+}
+
+/// How the components of a format are interpreted.  See vulkan specification,
+/// section 32.1 Format Definition
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NumericType {
+    Unorm,
+    Snorm,
+    Uscaled,
+    Sscaled,
+    Uint,
+    Sint,
+    Ufloat,
+    Sfloat,
+    Srgb,
+}
+
+/// A component of a format: red, green, blue, alpha, depth or stencil
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Component {
+    R,
+    G,
+    B,
+    A,
+    D,
+    S,
+}
+
+struct FormatInfo {
+    block_size: u32,
+    block_extent: (u32, u32),
+    numeric_type: NumericType,
+    components: &'static [(Component, u32)],
+}
+
+macro_rules! format_table {
+    ($( $(#[$attr:meta])*
+        $format:ident => ($size:expr, $width:expr, $height:expr, $numeric:ident,
+                          [$($component:ident $bits:expr),*]), )*) => {
+        fn format_info(format: Format) -> Option<FormatInfo>
+        {
+            match format {
+                Format::Undefined => None,
+                $(
+                    $(#[$attr])*
+                    Format::$format => Some(FormatInfo {
+                        block_size: $size,
+                        block_extent: ($width, $height),
+                        numeric_type: NumericType::$numeric,
+                        components: &[$((Component::$component, $bits)),*],
+                    }),
+                )*
+            }
+        }
+    }
+}
+
+// Block size in bytes, block width and height, numeric type, and components
+// with their bits
+format_table! {
+    R4g4UnormPack8 => (1, 1, 1, Unorm, [R 4, G 4]),
+    R4g4b4a4UnormPack16 => (2, 1, 1, Unorm, [R 4, G 4, B 4, A 4]),
+    B4g4r4a4UnormPack16 => (2, 1, 1, Unorm, [B 4, G 4, R 4, A 4]),
+    R5g6b5UnormPack16 => (2, 1, 1, Unorm, [R 5, G 6, B 5]),
+    B5g6r5UnormPack16 => (2, 1, 1, Unorm, [B 5, G 6, R 5]),
+    R5g5b5a1UnormPack16 => (2, 1, 1, Unorm, [R 5, G 5, B 5, A 1]),
+    B5g5r5a1UnormPack16 => (2, 1, 1, Unorm, [B 5, G 5, R 5, A 1]),
+    A1r5g5b5UnormPack16 => (2, 1, 1, Unorm, [A 1, R 5, G 5, B 5]),
+    R8Unorm => (1, 1, 1, Unorm, [R 8]),
+    R8Snorm => (1, 1, 1, Snorm, [R 8]),
+    R8Uscaled => (1, 1, 1, Uscaled, [R 8]),
+    R8Sscaled => (1, 1, 1, Sscaled, [R 8]),
+    R8Uint => (1, 1, 1, Uint, [R 8]),
+    R8Sint => (1, 1, 1, Sint, [R 8]),
+    R8Srgb => (1, 1, 1, Srgb, [R 8]),
+    R8g8Unorm => (2, 1, 1, Unorm, [R 8, G 8]),
+    R8g8Snorm => (2, 1, 1, Snorm, [R 8, G 8]),
+    R8g8Uscaled => (2, 1, 1, Uscaled, [R 8, G 8]),
+    R8g8Sscaled => (2, 1, 1, Sscaled, [R 8, G 8]),
+    R8g8Uint => (2, 1, 1, Uint, [R 8, G 8]),
+    R8g8Sint => (2, 1, 1, Sint, [R 8, G 8]),
+    R8g8Srgb => (2, 1, 1, Srgb, [R 8, G 8]),
+    R8g8b8Unorm => (3, 1, 1, Unorm, [R 8, G 8, B 8]),
+    R8g8b8Snorm => (3, 1, 1, Snorm, [R 8, G 8, B 8]),
+    R8g8b8Uscaled => (3, 1, 1, Uscaled, [R 8, G 8, B 8]),
+    R8g8b8Sscaled => (3, 1, 1, Sscaled, [R 8, G 8, B 8]),
+    R8g8b8Uint => (3, 1, 1, Uint, [R 8, G 8, B 8]),
+    R8g8b8Sint => (3, 1, 1, Sint, [R 8, G 8, B 8]),
+    R8g8b8Srgb => (3, 1, 1, Srgb, [R 8, G 8, B 8]),
+    B8g8r8Unorm => (3, 1, 1, Unorm, [B 8, G 8, R 8]),
+    B8g8r8Snorm => (3, 1, 1, Snorm, [B 8, G 8, R 8]),
+    B8g8r8Uscaled => (3, 1, 1, Uscaled, [B 8, G 8, R 8]),
+    B8g8r8Sscaled => (3, 1, 1, Sscaled, [B 8, G 8, R 8]),
+    B8g8r8Uint => (3, 1, 1, Uint, [B 8, G 8, R 8]),
+    B8g8r8Sint => (3, 1, 1, Sint, [B 8, G 8, R 8]),
+    B8g8r8Srgb => (3, 1, 1, Srgb, [B 8, G 8, R 8]),
+    R8g8b8a8Unorm => (4, 1, 1, Unorm, [R 8, G 8, B 8, A 8]),
+    R8g8b8a8Snorm => (4, 1, 1, Snorm, [R 8, G 8, B 8, A 8]),
+    R8g8b8a8Uscaled => (4, 1, 1, Uscaled, [R 8, G 8, B 8, A 8]),
+    R8g8b8a8Sscaled => (4, 1, 1, Sscaled, [R 8, G 8, B 8, A 8]),
+    R8g8b8a8Uint => (4, 1, 1, Uint, [R 8, G 8, B 8, A 8]),
+    R8g8b8a8Sint => (4, 1, 1, Sint, [R 8, G 8, B 8, A 8]),
+    R8g8b8a8Srgb => (4, 1, 1, Srgb, [R 8, G 8, B 8, A 8]),
+    B8g8r8a8Unorm => (4, 1, 1, Unorm, [B 8, G 8, R 8, A 8]),
+    B8g8r8a8Snorm => (4, 1, 1, Snorm, [B 8, G 8, R 8, A 8]),
+    B8g8r8a8Uscaled => (4, 1, 1, Uscaled, [B 8, G 8, R 8, A 8]),
+    B8g8r8a8Sscaled => (4, 1, 1, Sscaled, [B 8, G 8, R 8, A 8]),
+    B8g8r8a8Uint => (4, 1, 1, Uint, [B 8, G 8, R 8, A 8]),
+    B8g8r8a8Sint => (4, 1, 1, Sint, [B 8, G 8, R 8, A 8]),
+    B8g8r8a8Srgb => (4, 1, 1, Srgb, [B 8, G 8, R 8, A 8]),
+    A8b8g8r8UnormPack32 => (4, 1, 1, Unorm, [A 8, B 8, G 8, R 8]),
+    A8b8g8r8SnormPack32 => (4, 1, 1, Snorm, [A 8, B 8, G 8, R 8]),
+    A8b8g8r8UscaledPack32 => (4, 1, 1, Uscaled, [A 8, B 8, G 8, R 8]),
+    A8b8g8r8SscaledPack32 => (4, 1, 1, Sscaled, [A 8, B 8, G 8, R 8]),
+    A8b8g8r8UintPack32 => (4, 1, 1, Uint, [A 8, B 8, G 8, R 8]),
+    A8b8g8r8SintPack32 => (4, 1, 1, Sint, [A 8, B 8, G 8, R 8]),
+    A8b8g8r8SrgbPack32 => (4, 1, 1, Srgb, [A 8, B 8, G 8, R 8]),
+    A2r10g10b10UnormPack32 => (4, 1, 1, Unorm, [A 2, R 10, G 10, B 10]),
+    A2r10g10b10SnormPack32 => (4, 1, 1, Snorm, [A 2, R 10, G 10, B 10]),
+    A2r10g10b10UscaledPack32 => (4, 1, 1, Uscaled, [A 2, R 10, G 10, B 10]),
+    A2r10g10b10SscaledPack32 => (4, 1, 1, Sscaled, [A 2, R 10, G 10, B 10]),
+    A2r10g10b10UintPack32 => (4, 1, 1, Uint, [A 2, R 10, G 10, B 10]),
+    A2r10g10b10SintPack32 => (4, 1, 1, Sint, [A 2, R 10, G 10, B 10]),
+    A2b10g10r10UnormPack32 => (4, 1, 1, Unorm, [A 2, B 10, G 10, R 10]),
+    A2b10g10r10SnormPack32 => (4, 1, 1, Snorm, [A 2, B 10, G 10, R 10]),
+    A2b10g10r10UscaledPack32 => (4, 1, 1, Uscaled, [A 2, B 10, G 10, R 10]),
+    A2b10g10r10SscaledPack32 => (4, 1, 1, Sscaled, [A 2, B 10, G 10, R 10]),
+    A2b10g10r10UintPack32 => (4, 1, 1, Uint, [A 2, B 10, G 10, R 10]),
+    A2b10g10r10SintPack32 => (4, 1, 1, Sint, [A 2, B 10, G 10, R 10]),
+    R16Unorm => (2, 1, 1, Unorm, [R 16]),
+    R16Snorm => (2, 1, 1, Snorm, [R 16]),
+    R16Uscaled => (2, 1, 1, Uscaled, [R 16]),
+    R16Sscaled => (2, 1, 1, Sscaled, [R 16]),
+    R16Uint => (2, 1, 1, Uint, [R 16]),
+    R16Sint => (2, 1, 1, Sint, [R 16]),
+    R16Sfloat => (2, 1, 1, Sfloat, [R 16]),
+    R16g16Unorm => (4, 1, 1, Unorm, [R 16, G 16]),
+    R16g16Snorm => (4, 1, 1, Snorm, [R 16, G 16]),
+    R16g16Uscaled => (4, 1, 1, Uscaled, [R 16, G 16]),
+    R16g16Sscaled => (4, 1, 1, Sscaled, [R 16, G 16]),
+    R16g16Uint => (4, 1, 1, Uint, [R 16, G 16]),
+    R16g16Sint => (4, 1, 1, Sint, [R 16, G 16]),
+    R16g16Sfloat => (4, 1, 1, Sfloat, [R 16, G 16]),
+    R16g16b16Unorm => (6, 1, 1, Unorm, [R 16, G 16, B 16]),
+    R16g16b16Snorm => (6, 1, 1, Snorm, [R 16, G 16, B 16]),
+    R16g16b16Uscaled => (6, 1, 1, Uscaled, [R 16, G 16, B 16]),
+    R16g16b16Sscaled => (6, 1, 1, Sscaled, [R 16, G 16, B 16]),
+    R16g16b16Uint => (6, 1, 1, Uint, [R 16, G 16, B 16]),
+    R16g16b16Sint => (6, 1, 1, Sint, [R 16, G 16, B 16]),
+    R16g16b16Sfloat => (6, 1, 1, Sfloat, [R 16, G 16, B 16]),
+    R16g16b16a16Unorm => (8, 1, 1, Unorm, [R 16, G 16, B 16, A 16]),
+    R16g16b16a16Snorm => (8, 1, 1, Snorm, [R 16, G 16, B 16, A 16]),
+    R16g16b16a16Uscaled => (8, 1, 1, Uscaled, [R 16, G 16, B 16, A 16]),
+    R16g16b16a16Sscaled => (8, 1, 1, Sscaled, [R 16, G 16, B 16, A 16]),
+    R16g16b16a16Uint => (8, 1, 1, Uint, [R 16, G 16, B 16, A 16]),
+    R16g16b16a16Sint => (8, 1, 1, Sint, [R 16, G 16, B 16, A 16]),
+    R16g16b16a16Sfloat => (8, 1, 1, Sfloat, [R 16, G 16, B 16, A 16]),
+    R32Uint => (4, 1, 1, Uint, [R 32]),
+    R32Sint => (4, 1, 1, Sint, [R 32]),
+    R32Sfloat => (4, 1, 1, Sfloat, [R 32]),
+    R32g32Uint => (8, 1, 1, Uint, [R 32, G 32]),
+    R32g32Sint => (8, 1, 1, Sint, [R 32, G 32]),
+    R32g32Sfloat => (8, 1, 1, Sfloat, [R 32, G 32]),
+    R32g32b32Uint => (12, 1, 1, Uint, [R 32, G 32, B 32]),
+    R32g32b32Sint => (12, 1, 1, Sint, [R 32, G 32, B 32]),
+    R32g32b32Sfloat => (12, 1, 1, Sfloat, [R 32, G 32, B 32]),
+    R32g32b32a32Uint => (16, 1, 1, Uint, [R 32, G 32, B 32, A 32]),
+    R32g32b32a32Sint => (16, 1, 1, Sint, [R 32, G 32, B 32, A 32]),
+    R32g32b32a32Sfloat => (16, 1, 1, Sfloat, [R 32, G 32, B 32, A 32]),
+    R64Uint => (8, 1, 1, Uint, [R 64]),
+    R64Sint => (8, 1, 1, Sint, [R 64]),
+    R64Sfloat => (8, 1, 1, Sfloat, [R 64]),
+    R64g64Uint => (16, 1, 1, Uint, [R 64, G 64]),
+    R64g64Sint => (16, 1, 1, Sint, [R 64, G 64]),
+    R64g64Sfloat => (16, 1, 1, Sfloat, [R 64, G 64]),
+    R64g64b64Uint => (24, 1, 1, Uint, [R 64, G 64, B 64]),
+    R64g64b64Sint => (24, 1, 1, Sint, [R 64, G 64, B 64]),
+    R64g64b64Sfloat => (24, 1, 1, Sfloat, [R 64, G 64, B 64]),
+    R64g64b64a64Uint => (32, 1, 1, Uint, [R 64, G 64, B 64, A 64]),
+    R64g64b64a64Sint => (32, 1, 1, Sint, [R 64, G 64, B 64, A 64]),
+    R64g64b64a64Sfloat => (32, 1, 1, Sfloat, [R 64, G 64, B 64, A 64]),
+    B10g11r11UfloatPack32 => (4, 1, 1, Ufloat, [B 10, G 11, R 11]),
+    E5b9g9r9UfloatPack32 => (4, 1, 1, Ufloat, [B 9, G 9, R 9]),
+    D16Unorm => (2, 1, 1, Unorm, [D 16]),
+    X8D24UnormPack32 => (4, 1, 1, Unorm, [D 24]),
+    D32Sfloat => (4, 1, 1, Sfloat, [D 32]),
+    S8Uint => (1, 1, 1, Uint, [S 8]),
+    D16UnormS8Uint => (3, 1, 1, Unorm, [D 16, S 8]),
+    D24UnormS8Uint => (4, 1, 1, Unorm, [D 24, S 8]),
+    D32SfloatS8Uint => (5, 1, 1, Sfloat, [D 32, S 8]),
+    Bc1RgbUnormBlock => (8, 4, 4, Unorm, [R 0, G 0, B 0]),
+    Bc1RgbSrgbBlock => (8, 4, 4, Srgb, [R 0, G 0, B 0]),
+    Bc1RgbaUnormBlock => (8, 4, 4, Unorm, [R 0, G 0, B 0, A 0]),
+    Bc1RgbaSrgbBlock => (8, 4, 4, Srgb, [R 0, G 0, B 0, A 0]),
+    Bc2UnormBlock => (16, 4, 4, Unorm, [R 0, G 0, B 0, A 0]),
+    Bc2SrgbBlock => (16, 4, 4, Srgb, [R 0, G 0, B 0, A 0]),
+    Bc3UnormBlock => (16, 4, 4, Unorm, [R 0, G 0, B 0, A 0]),
+    Bc3SrgbBlock => (16, 4, 4, Srgb, [R 0, G 0, B 0, A 0]),
+    Bc4UnormBlock => (8, 4, 4, Unorm, [R 0]),
+    Bc4SnormBlock => (8, 4, 4, Snorm, [R 0]),
+    Bc5UnormBlock => (16, 4, 4, Unorm, [R 0, G 0]),
+    Bc5SnormBlock => (16, 4, 4, Snorm, [R 0, G 0]),
+    Bc6hUfloatBlock => (16, 4, 4, Ufloat, [R 0, G 0, B 0]),
+    Bc6hSfloatBlock => (16, 4, 4, Sfloat, [R 0, G 0, B 0]),
+    Bc7UnormBlock => (16, 4, 4, Unorm, [R 0, G 0, B 0, A 0]),
+    Bc7SrgbBlock => (16, 4, 4, Srgb, [R 0, G 0, B 0, A 0]),
+    Etc2R8g8b8UnormBlock => (8, 4, 4, Unorm, [R 0, G 0, B 0]),
+    Etc2R8g8b8SrgbBlock => (8, 4, 4, Srgb, [R 0, G 0, B 0]),
+    Etc2R8g8b8a1UnormBlock => (8, 4, 4, Unorm, [R 0, G 0, B 0, A 0]),
+    Etc2R8g8b8a1SrgbBlock => (8, 4, 4, Srgb, [R 0, G 0, B 0, A 0]),
+    Etc2R8g8b8a8UnormBlock => (16, 4, 4, Unorm, [R 0, G 0, B 0, A 0]),
+    Etc2R8g8b8a8SrgbBlock => (16, 4, 4, Srgb, [R 0, G 0, B 0, A 0]),
+    EacR11UnormBlock => (8, 4, 4, Unorm, [R 0]),
+    EacR11SnormBlock => (8, 4, 4, Snorm, [R 0]),
+    EacR11g11UnormBlock => (16, 4, 4, Unorm, [R 0, G 0]),
+    EacR11g11SnormBlock => (16, 4, 4, Snorm, [R 0, G 0]),
+    Astc4x4UnormBlock => (16, 4, 4, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc4x4SrgbBlock => (16, 4, 4, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc5x4UnormBlock => (16, 5, 4, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc5x4SrgbBlock => (16, 5, 4, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc5x5UnormBlock => (16, 5, 5, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc5x5SrgbBlock => (16, 5, 5, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc6x5UnormBlock => (16, 6, 5, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc6x5SrgbBlock => (16, 6, 5, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc6x6UnormBlock => (16, 6, 6, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc6x6SrgbBlock => (16, 6, 6, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc8x5UnormBlock => (16, 8, 5, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc8x5SrgbBlock => (16, 8, 5, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc8x6UnormBlock => (16, 8, 6, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc8x6SrgbBlock => (16, 8, 6, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc8x8UnormBlock => (16, 8, 8, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc8x8SrgbBlock => (16, 8, 8, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc10x5UnormBlock => (16, 10, 5, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc10x5SrgbBlock => (16, 10, 5, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc10x6UnormBlock => (16, 10, 6, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc10x6SrgbBlock => (16, 10, 6, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc10x8UnormBlock => (16, 10, 8, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc10x8SrgbBlock => (16, 10, 8, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc10x10UnormBlock => (16, 10, 10, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc10x10SrgbBlock => (16, 10, 10, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc12x10UnormBlock => (16, 12, 10, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc12x10SrgbBlock => (16, 12, 10, Srgb, [R 0, G 0, B 0, A 0]),
+    Astc12x12UnormBlock => (16, 12, 12, Unorm, [R 0, G 0, B 0, A 0]),
+    Astc12x12SrgbBlock => (16, 12, 12, Srgb, [R 0, G 0, B 0, A 0]),
+    #[cfg(feature = "img_format_pvrtc")]
+    Pvrtc12bppUnormBlockImg => (8, 8, 4, Unorm, [R 0, G 0, B 0, A 0]),
+    #[cfg(feature = "img_format_pvrtc")]
+    Pvrtc14bppUnormBlockImg => (8, 4, 4, Unorm, [R 0, G 0, B 0, A 0]),
+    #[cfg(feature = "img_format_pvrtc")]
+    Pvrtc22bppUnormBlockImg => (8, 8, 4, Unorm, [R 0, G 0, B 0, A 0]),
+    #[cfg(feature = "img_format_pvrtc")]
+    Pvrtc24bppUnormBlockImg => (8, 4, 4, Unorm, [R 0, G 0, B 0, A 0]),
+    #[cfg(feature = "img_format_pvrtc")]
+    Pvrtc12bppSrgbBlockImg => (8, 8, 4, Srgb, [R 0, G 0, B 0, A 0]),
+    #[cfg(feature = "img_format_pvrtc")]
+    Pvrtc14bppSrgbBlockImg => (8, 4, 4, Srgb, [R 0, G 0, B 0, A 0]),
+    #[cfg(feature = "img_format_pvrtc")]
+    Pvrtc22bppSrgbBlockImg => (8, 8, 4, Srgb, [R 0, G 0, B 0, A 0]),
+    #[cfg(feature = "img_format_pvrtc")]
+    Pvrtc24bppSrgbBlockImg => (8, 4, 4, Srgb, [R 0, G 0, B 0, A 0]),
+}
+
 impl Default for Format {
     fn default() -> Format {
         Format::Undefined
@@ -269,13 +723,24 @@ impl Into<VkFormatFeatureFlags> for FormatFeatureFlags {
 }
 
 #[repr(C)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct FormatProperties {
     pub linear_tiling_features: FormatFeatureFlags,
     pub optimal_tiling_features: FormatFeatureFlags,
     pub buffer_features: FormatFeatureFlags,
 }
 
+impl FormatProperties {
+    /// The features supported for images with `tiling`
+    pub fn tiling_features(&self, tiling: ImageTiling) -> FormatFeatureFlags
+    {
+        match tiling {
+            ImageTiling::Optimal => self.optimal_tiling_features,
+            ImageTiling::Linear => self.linear_tiling_features,
+        }
+    }
+}
+
 impl From<VkFormatProperties> for FormatProperties {
     fn from(vk: VkFormatProperties) -> FormatProperties {
         unsafe {
@@ -1,5 +1,9 @@
 
-use vks::VkImageUsageFlags;
+use std::mem;
+use vks::{VkImageUsageFlags, VkImageCreateFlags, VkImageAspectFlags, VkImageType, VkImageTiling,
+          VkImageFormatProperties, VkSparseImageFormatFlags, VkSparseImageFormatProperties};
+use {Extent3D, SampleCountFlags};
+use instance::physical_device::DeviceSize;
 
 bitflags! {
     #[repr(C)]
@@ -27,3 +31,159 @@ impl Into<VkImageUsageFlags> for ImageUsageFlags {
         VkImageUsageFlags::from_bits(self.bits()).unwrap()
     }
 }
+
+bitflags! {
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct ImageCreateFlags: u32 {
+        const IMAGE_CREATE_SPARSE_BINDING_BIT = 0x00000001;
+        const IMAGE_CREATE_SPARSE_RESIDENCY_BIT = 0x00000002;
+        const IMAGE_CREATE_SPARSE_ALIASED_BIT = 0x00000004;
+        const IMAGE_CREATE_MUTABLE_FORMAT_BIT = 0x00000008;
+        const IMAGE_CREATE_CUBE_COMPATIBLE_BIT = 0x00000010;
+        #[cfg(feature = "khx_device_group")]
+        const IMAGE_CREATE_BIND_SFR_BIT_KHX = 0x00000040;
+        #[cfg(feature = "khr_maintenance1")]
+        const IMAGE_CREATE_2D_ARRAY_COMPATIBLE_BIT_KHR = 0x00000020;
+    }
+}
+
+impl From<VkImageCreateFlags> for ImageCreateFlags {
+    fn from(vk: VkImageCreateFlags) -> ImageCreateFlags {
+        ImageCreateFlags::from_bits(vk.bits()).unwrap()
+    }
+}
+
+impl Into<VkImageCreateFlags> for ImageCreateFlags {
+    fn into(self) -> VkImageCreateFlags {
+        VkImageCreateFlags::from_bits(self.bits()).unwrap()
+    }
+}
+
+bitflags! {
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct ImageAspectFlags: u32 {
+        const IMAGE_ASPECT_COLOR_BIT = 0x00000001;
+        const IMAGE_ASPECT_DEPTH_BIT = 0x00000002;
+        const IMAGE_ASPECT_STENCIL_BIT = 0x00000004;
+        const IMAGE_ASPECT_METADATA_BIT = 0x00000008;
+    }
+}
+
+impl From<VkImageAspectFlags> for ImageAspectFlags {
+    fn from(vk: VkImageAspectFlags) -> ImageAspectFlags {
+        ImageAspectFlags::from_bits(vk.bits()).unwrap()
+    }
+}
+
+impl Into<VkImageAspectFlags> for ImageAspectFlags {
+    fn into(self) -> VkImageAspectFlags {
+        VkImageAspectFlags::from_bits(self.bits()).unwrap()
+    }
+}
+
+bitflags! {
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct SparseImageFormatFlags: u32 {
+        const SPARSE_IMAGE_FORMAT_SINGLE_MIPTAIL_BIT = 0x00000001;
+        const SPARSE_IMAGE_FORMAT_ALIGNED_MIP_SIZE_BIT = 0x00000002;
+        const SPARSE_IMAGE_FORMAT_NONSTANDARD_BLOCK_SIZE_BIT = 0x00000004;
+    }
+}
+
+impl From<VkSparseImageFormatFlags> for SparseImageFormatFlags {
+    fn from(vk: VkSparseImageFormatFlags) -> SparseImageFormatFlags {
+        SparseImageFormatFlags::from_bits(vk.bits()).unwrap()
+    }
+}
+
+/// See vulkan specification, section 11.3 Images
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ImageType {
+    Type1D = 0,
+    Type2D = 1,
+    Type3D = 2,
+}
+
+impl From<VkImageType> for ImageType {
+    fn from(vk: VkImageType) -> ImageType {
+        unsafe {
+            mem::transmute(vk.as_raw())
+        }
+    }
+}
+
+impl Into<VkImageType> for ImageType {
+    fn into(self) -> VkImageType {
+        VkImageType::from_raw(unsafe {
+            mem::transmute(self)
+        })
+    }
+}
+
+/// See vulkan specification, section 11.3 Images
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ImageTiling {
+    Optimal = 0,
+    Linear = 1,
+}
+
+impl From<VkImageTiling> for ImageTiling {
+    fn from(vk: VkImageTiling) -> ImageTiling {
+        unsafe {
+            mem::transmute(vk.as_raw())
+        }
+    }
+}
+
+impl Into<VkImageTiling> for ImageTiling {
+    fn into(self) -> VkImageTiling {
+        VkImageTiling::from_raw(unsafe {
+            mem::transmute(self)
+        })
+    }
+}
+
+/// See vulkan specification, section 31.4 Additional Image Capabilities
+#[derive(Debug, Clone)]
+pub struct ImageFormatProperties {
+    pub max_extent: Extent3D,
+    pub max_mip_levels: u32,
+    pub max_array_layers: u32,
+    pub sample_counts: SampleCountFlags,
+    pub max_resource_size: DeviceSize,
+}
+
+impl From<VkImageFormatProperties> for ImageFormatProperties {
+    fn from(vk: VkImageFormatProperties) -> ImageFormatProperties {
+        ImageFormatProperties {
+            max_extent: vk.maxExtent,
+            max_mip_levels: vk.maxMipLevels,
+            max_array_layers: vk.maxArrayLayers,
+            sample_counts: vk.sampleCounts,
+            max_resource_size: vk.maxResourceSize,
+        }
+    }
+}
+
+/// See vulkan specification, section 28.7.3 Sparse Image Format Properties
+#[derive(Debug, Clone)]
+pub struct SparseImageFormatProperties {
+    pub aspect_mask: ImageAspectFlags,
+    pub image_granularity: Extent3D,
+    pub flags: SparseImageFormatFlags,
+}
+
+impl From<VkSparseImageFormatProperties> for SparseImageFormatProperties {
+    fn from(vk: VkSparseImageFormatProperties) -> SparseImageFormatProperties {
+        SparseImageFormatProperties {
+            aspect_mask: From::from(vk.aspectMask),
+            image_granularity: vk.imageGranularity,
+            flags: From::from(vk.flags),
+        }
+    }
+}
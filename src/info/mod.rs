@@ -0,0 +1,270 @@
+//! A report of everything the Vulkan implementation offers, in the manner of
+//! `vulkaninfo`: instance layers and extensions, and for each physical device
+//! its properties, limits, features, queue families, memory, extensions and
+//! format support.  The `sarek-info` binary prints this report.
+//!
+//! ```ignore
+//! let report = Report::collect(InstanceLoader::load_system()?)?;
+//! print!("{}", report.to_text());
+//! ```
+
+mod value;
+pub use self::value::ReportValue;
+
+use {Error, Version, HEADER_VERSION, InstanceLoader, Instance, Format, FormatProperties,
+     ExtensionProperties, LayerProperties};
+use {enumerate_instance_extension_properties, enumerate_instance_layer_properties};
+use instance::{ApplicationInfo, InstanceCreateInfo};
+use instance::physical_device::{self, PhysicalDevice, PhysicalDeviceProperties,
+                                PhysicalDeviceFeatures, PhysicalDeviceMemoryProperties,
+                                QueueFamilyProperties, LimitValue};
+
+/// Everything reported about one physical device
+#[derive(Debug, Clone)]
+pub struct DeviceReport {
+    pub properties: PhysicalDeviceProperties,
+    pub features: PhysicalDeviceFeatures,
+    pub queue_families: Vec<QueueFamilyProperties>,
+    pub memory_properties: PhysicalDeviceMemoryProperties,
+    pub extensions: Vec<physical_device::ExtensionProperties>,
+    /// Every format supporting at least one feature
+    pub formats: Vec<(Format, FormatProperties)>,
+}
+
+/// See the module documentation
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub instance_version: Version,
+    pub layers: Vec<LayerProperties>,
+    /// Extensions of each layer, in the same order as `layers`
+    pub layer_extensions: Vec<Vec<ExtensionProperties>>,
+    pub extensions: Vec<ExtensionProperties>,
+    pub devices: Vec<DeviceReport>,
+}
+
+impl Report {
+    /// Create an instance with no layers or extensions, and report on it
+    pub fn collect(loader: InstanceLoader) -> Result<Report, Error>
+    {
+        let (instance, loader) = Instance::new(loader, InstanceCreateInfo {
+            application_info: ApplicationInfo {
+                application_name: "sarek-info".to_owned(),
+                application_version: Version(0,1,0),
+                engine_name: "sarek".to_owned(),
+                engine_version: Version(0,1,0),
+                api_version: HEADER_VERSION,
+            },
+            enabled_layer_count: 0,
+            enabled_layer_names: vec![],
+            enabled_extension_names: vec![],
+            optional_extension_names: vec![],
+            allocator: None,
+            disabled_validation_checks: vec![],
+        })?;
+        Report::from_instance(&instance, &loader)
+    }
+
+    /// Report on the implementation behind an existing instance
+    pub fn from_instance(instance: &Instance, loader: &InstanceLoader) -> Result<Report, Error>
+    {
+        let layers = enumerate_instance_layer_properties(loader)?;
+        let mut layer_extensions = Vec::with_capacity(layers.len());
+        for layer in &layers {
+            layer_extensions.push(
+                enumerate_instance_extension_properties(loader, Some(&layer.layer_name))?);
+        }
+
+        let mut devices = Vec::new();
+        for physical_device in instance.enumerate_physical_devices(loader)? {
+            devices.push(DeviceReport::collect(loader, &physical_device)?);
+        }
+
+        Ok(Report {
+            instance_version: loader.enumerate_instance_version()?,
+            layers: layers,
+            layer_extensions: layer_extensions,
+            extensions: enumerate_instance_extension_properties(loader, None)?,
+            devices: devices,
+        })
+    }
+
+    /// The report as a tree, from which the text and JSON forms are written
+    pub fn to_value(&self) -> ReportValue
+    {
+        let layers = self.layers.iter().zip(self.layer_extensions.iter())
+            .map(|(layer, extensions)| ReportValue::Object(vec![
+                ("layer_name".to_owned(), ReportValue::str(&*layer.layer_name)),
+                ("spec_version".to_owned(),
+                 ReportValue::str(Version::from_vk(layer.spec_version).to_string())),
+                ("implementation_version".to_owned(),
+                 ReportValue::Int(layer.implementation_version as i64)),
+                ("description".to_owned(), ReportValue::str(&*layer.description)),
+                ("extensions".to_owned(), extensions_value(
+                    extensions.iter().map(|e| (&*e.extension_name, e.spec_version)))),
+            ]))
+            .collect();
+
+        ReportValue::Object(vec![
+            ("instance_version".to_owned(), ReportValue::str(self.instance_version.to_string())),
+            ("layers".to_owned(), ReportValue::List(layers)),
+            ("extensions".to_owned(), extensions_value(
+                self.extensions.iter().map(|e| (&*e.extension_name, e.spec_version)))),
+            ("devices".to_owned(),
+             ReportValue::List(self.devices.iter().map(|d| d.to_value()).collect())),
+        ])
+    }
+
+    /// Human-readable, indented text
+    pub fn to_text(&self) -> String
+    {
+        self.to_value().to_text()
+    }
+
+    /// Machine-readable JSON
+    pub fn to_json(&self) -> String
+    {
+        self.to_value().to_json()
+    }
+}
+
+impl DeviceReport {
+    pub fn collect(loader: &InstanceLoader, physical_device: &PhysicalDevice)
+                   -> Result<DeviceReport, Error>
+    {
+        Ok(DeviceReport {
+            properties: physical_device.get_properties(loader)?,
+            features: physical_device.get_features(loader)?,
+            queue_families: physical_device.get_queue_family_properties(loader)?,
+            memory_properties: physical_device.get_memory_properties(loader)?,
+            extensions: physical_device.get_extension_properties(loader, None)?,
+            formats: physical_device.get_format_table(loader)?.supported_formats(),
+        })
+    }
+
+    pub fn to_value(&self) -> ReportValue
+    {
+        let p = &self.properties;
+        let sparse = &p.sparse_properties;
+        let properties = ReportValue::Object(vec![
+            ("device_name".to_owned(), ReportValue::str(&*p.device_name)),
+            ("device_type".to_owned(), ReportValue::str(format!("{:?}", p.device_type))),
+            ("api_version".to_owned(), ReportValue::str(p.api_version.to_string())),
+            ("driver_version".to_owned(), ReportValue::Int(p.driver_version as i64)),
+            ("vendor_id".to_owned(), ReportValue::str(format!("0x{:04x}", p.vendor_id))),
+            ("device_id".to_owned(), ReportValue::str(format!("0x{:04x}", p.device_id))),
+            ("pipeline_cache_uuid".to_owned(), ReportValue::str(
+                p.pipeline_cache_uuid.iter().map(|b| format!("{:02x}", b))
+                    .collect::<Vec<String>>().concat())),
+        ]);
+
+        let limits = ReportValue::Object(
+            p.limits.fields().into_iter()
+                .map(|(name, value)| (name.to_owned(), limit_value(value)))
+                .collect());
+
+        let sparse_properties = ReportValue::Object(vec![
+            ("residency_standard_2d_block_shape".to_owned(),
+             ReportValue::Bool(sparse.residency_standard_2d_block_shape != 0)),
+            ("residency_standard_2d_multisample_block_shape".to_owned(),
+             ReportValue::Bool(sparse.residency_standard_2d_multisample_block_shape != 0)),
+            ("residency_standard_3d_block_shape".to_owned(),
+             ReportValue::Bool(sparse.residency_standard_3d_block_shape != 0)),
+            ("residency_aligned_mip_size".to_owned(),
+             ReportValue::Bool(sparse.residency_aligned_mip_size != 0)),
+            ("residency_non_resident_strict".to_owned(),
+             ReportValue::Bool(sparse.residency_non_resident_strict != 0)),
+        ]);
+
+        let features = ReportValue::Object(
+            self.features.fields().into_iter()
+                .map(|(name, enabled)| (name.to_owned(), ReportValue::Bool(enabled)))
+                .collect());
+
+        let queue_families = self.queue_families.iter()
+            .map(|qf| ReportValue::Object(vec![
+                ("queue_flags".to_owned(), ReportValue::str(format!("{:?}", qf.queue_flags))),
+                ("queue_count".to_owned(), ReportValue::Int(qf.queue_count as i64)),
+                ("timestamp_valid_bits".to_owned(),
+                 ReportValue::Int(qf.timestamp_valid_bits as i64)),
+                ("min_image_transfer_granularity".to_owned(), ReportValue::List(vec![
+                    ReportValue::Int(qf.min_image_transfer_granularity.width as i64),
+                    ReportValue::Int(qf.min_image_transfer_granularity.height as i64),
+                    ReportValue::Int(qf.min_image_transfer_granularity.depth as i64),
+                ])),
+            ]))
+            .collect();
+
+        let memory = &self.memory_properties;
+        let memory_properties = ReportValue::Object(vec![
+            ("memory_heaps".to_owned(), ReportValue::List(
+                memory.memory_heaps.iter()
+                    .map(|heap| ReportValue::Object(vec![
+                        ("size".to_owned(), ReportValue::UInt(heap.size)),
+                        ("flags".to_owned(), ReportValue::str(format!("{:?}", heap.flags))),
+                    ]))
+                    .collect())),
+            ("memory_types".to_owned(), ReportValue::List(
+                memory.memory_types.iter()
+                    .map(|memory_type| ReportValue::Object(vec![
+                        ("heap_index".to_owned(), ReportValue::Int(memory_type.heap_index as i64)),
+                        ("property_flags".to_owned(),
+                         ReportValue::str(format!("{:?}", memory_type.property_flags))),
+                    ]))
+                    .collect())),
+        ]);
+
+        let formats = ReportValue::Object(
+            self.formats.iter()
+                .map(|&(format, ref properties)| (format!("{:?}", format), ReportValue::Object(vec![
+                    ("linear_tiling_features".to_owned(),
+                     ReportValue::str(format!("{:?}", properties.linear_tiling_features))),
+                    ("optimal_tiling_features".to_owned(),
+                     ReportValue::str(format!("{:?}", properties.optimal_tiling_features))),
+                    ("buffer_features".to_owned(),
+                     ReportValue::str(format!("{:?}", properties.buffer_features))),
+                ])))
+                .collect());
+
+        ReportValue::Object(vec![
+            ("properties".to_owned(), properties),
+            ("limits".to_owned(), limits),
+            ("sparse_properties".to_owned(), sparse_properties),
+            ("features".to_owned(), features),
+            ("queue_families".to_owned(), ReportValue::List(queue_families)),
+            ("memory_properties".to_owned(), memory_properties),
+            ("extensions".to_owned(), extensions_value(
+                self.extensions.iter().map(|e| (&*e.extension_name, e.spec_version)))),
+            ("formats".to_owned(), formats),
+        ])
+    }
+}
+
+fn extensions_value<'a, I>(extensions: I) -> ReportValue
+    where I: Iterator<Item = (&'a str, u32)>
+{
+    ReportValue::Object(
+        extensions.map(|(name, spec_version)| (name.to_owned(),
+                                               ReportValue::Int(spec_version as i64)))
+            .collect())
+}
+
+// Floats go through their shortest decimal form, so that an f32 limit like 0.1
+// is not reported as 0.10000000149011612
+fn float_value(f: f32) -> ReportValue
+{
+    ReportValue::Float(f.to_string().parse().unwrap_or(f as f64))
+}
+
+fn limit_value(value: LimitValue) -> ReportValue
+{
+    match value {
+        LimitValue::U32(v) => ReportValue::Int(v as i64),
+        LimitValue::I32(v) => ReportValue::Int(v as i64),
+        LimitValue::U64(v) => ReportValue::UInt(v),
+        LimitValue::F32(v) => float_value(v),
+        LimitValue::U32Array(v) =>
+            ReportValue::List(v.into_iter().map(|v| ReportValue::Int(v as i64)).collect()),
+        LimitValue::F32Array(v) =>
+            ReportValue::List(v.into_iter().map(float_value).collect()),
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt::Write;
+
+/// A tree of report data, which can be written as indented text or as JSON
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportValue {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(String),
+    List(Vec<ReportValue>),
+    Object(Vec<(String, ReportValue)>),
+}
+
+impl ReportValue {
+    pub fn str<S: Into<String>>(s: S) -> ReportValue
+    {
+        ReportValue::Str(s.into())
+    }
+
+    pub fn to_json(&self) -> String
+    {
+        let mut out = String::new();
+        self.write_json(&mut out, 0);
+        out.push('\n');
+        out
+    }
+
+    pub fn to_text(&self) -> String
+    {
+        let mut out = String::new();
+        match *self {
+            ReportValue::Object(ref fields) => write_text_fields(&mut out, fields, 0),
+            ref other => {
+                out.push_str(&other.scalar_text());
+                out.push('\n');
+            },
+        }
+        out
+    }
+
+    fn is_scalar(&self) -> bool
+    {
+        match *self {
+            ReportValue::List(ref items) => items.iter().all(|i| i.is_scalar()),
+            ReportValue::Object(_) => false,
+            _ => true,
+        }
+    }
+
+    // Text for a value which fits on one line
+    fn scalar_text(&self) -> String
+    {
+        match *self {
+            ReportValue::Bool(b) => format!("{}", b),
+            ReportValue::Int(i) => format!("{}", i),
+            ReportValue::UInt(u) => format!("{}", u),
+            ReportValue::Float(f) => format!("{}", f),
+            ReportValue::Str(ref s) => s.clone(),
+            ReportValue::List(ref items) => {
+                let items: Vec<String> = items.iter().map(|i| i.scalar_text()).collect();
+                format!("[{}]", items.join(", "))
+            },
+            ReportValue::Object(_) => String::new(),
+        }
+    }
+
+    fn write_json(&self, out: &mut String, indent: usize)
+    {
+        match *self {
+            ReportValue::Bool(b) => write!(out, "{}", b).unwrap(),
+            ReportValue::Int(i) => write!(out, "{}", i).unwrap(),
+            ReportValue::UInt(u) => write!(out, "{}", u).unwrap(),
+            // JSON has no representation of infinities or NaN
+            ReportValue::Float(f) if !f.is_finite() => out.push_str("null"),
+            ReportValue::Float(f) => write!(out, "{:?}", f).unwrap(),
+            ReportValue::Str(ref s) => write_json_string(out, s),
+            ReportValue::List(ref items) => {
+                if items.is_empty() {
+                    out.push_str("[]");
+                    return;
+                }
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    newline(out, indent + 1);
+                    item.write_json(out, indent + 1);
+                }
+                newline(out, indent);
+                out.push(']');
+            },
+            ReportValue::Object(ref fields) => {
+                if fields.is_empty() {
+                    out.push_str("{}");
+                    return;
+                }
+                out.push('{');
+                for (i, &(ref key, ref value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    newline(out, indent + 1);
+                    write_json_string(out, key);
+                    out.push_str(": ");
+                    value.write_json(out, indent + 1);
+                }
+                newline(out, indent);
+                out.push('}');
+            },
+        }
+    }
+}
+
+fn newline(out: &mut String, indent: usize)
+{
+    out.push('\n');
+    for _ in 0..indent {
+        out.push_str("  ");
+    }
+}
+
+fn write_json_string(out: &mut String, s: &str)
+{
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_text_fields(out: &mut String, fields: &[(String, ReportValue)], indent: usize)
+{
+    for &(ref key, ref value) in fields {
+        write_text_field(out, key, value, indent);
+    }
+}
+
+fn write_text_field(out: &mut String, key: &str, value: &ReportValue, indent: usize)
+{
+    for _ in 0..indent {
+        out.push_str("    ");
+    }
+    if value.is_scalar() {
+        writeln!(out, "{} = {}", key, value.scalar_text()).unwrap();
+        return;
+    }
+    writeln!(out, "{}:", key).unwrap();
+    match *value {
+        ReportValue::Object(ref fields) => write_text_fields(out, fields, indent + 1),
+        ReportValue::List(ref items) => {
+            for (i, item) in items.iter().enumerate() {
+                write_text_field(out, &format!("[{}]", i), item, indent + 1);
+            }
+        },
+        _ => unreachable!(),
+    }
+}
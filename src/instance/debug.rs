@@ -1,73 +1,296 @@
-
-use std::ptr;
 use std::ffi::CStr;
 use std::default::Default;
+use std::fmt;
 use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use libc::{c_char, c_void};
 use vks::*;
 
 use error::Error;
+use allocator::vk_allocator;
 use super::loader::InstanceLoader;
+use super::Instance;
+
+bitflags! {
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct DebugReportFlags: u32 {
+        const DEBUG_REPORT_INFORMATION_BIT = 0x00000001;
+        const DEBUG_REPORT_WARNING_BIT = 0x00000002;
+        const DEBUG_REPORT_PERFORMANCE_WARNING_BIT = 0x00000004;
+        const DEBUG_REPORT_ERROR_BIT = 0x00000008;
+        const DEBUG_REPORT_DEBUG_BIT = 0x00000010;
+    }
+}
+
+impl From<VkDebugReportFlagsEXT> for DebugReportFlags {
+    fn from(vk: VkDebugReportFlagsEXT) -> DebugReportFlags {
+        DebugReportFlags::from_bits_truncate(vk.bits())
+    }
+}
+
+impl Into<VkDebugReportFlagsEXT> for DebugReportFlags {
+    fn into(self) -> VkDebugReportFlagsEXT {
+        VkDebugReportFlagsEXT::from_bits(self.bits()).unwrap()
+    }
+}
+
+/// See vulkan specification, section 33.2 Debug Report Callbacks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum DebugReportObjectType {
+    Unknown = 0,
+    Instance = 1,
+    PhysicalDevice = 2,
+    Device = 3,
+    Queue = 4,
+    Semaphore = 5,
+    CommandBuffer = 6,
+    Fence = 7,
+    DeviceMemory = 8,
+    Buffer = 9,
+    Image = 10,
+    Event = 11,
+    QueryPool = 12,
+    BufferView = 13,
+    ImageView = 14,
+    ShaderModule = 15,
+    PipelineCache = 16,
+    PipelineLayout = 17,
+    RenderPass = 18,
+    Pipeline = 19,
+    DescriptorSetLayout = 20,
+    Sampler = 21,
+    DescriptorPool = 22,
+    DescriptorSet = 23,
+    Framebuffer = 24,
+    CommandPool = 25,
+    SurfaceKHR = 26,
+    SwapchainKHR = 27,
+    DebugReportCallbackEXT = 28,
+    DisplayKHR = 29,
+    DisplayModeKHR = 30,
+}
+
+impl From<VkDebugReportObjectTypeEXT> for DebugReportObjectType {
+    fn from(vk: VkDebugReportObjectTypeEXT) -> DebugReportObjectType {
+        // Newer extensions add object types we do not know about; report those
+        // as unknown rather than transmuting an invalid discriminant.
+        let raw = vk.as_raw() as u32;
+        if raw <= DebugReportObjectType::DisplayModeKHR as u32 {
+            unsafe { mem::transmute(raw) }
+        } else {
+            DebugReportObjectType::Unknown
+        }
+    }
+}
+
+impl Into<VkDebugReportObjectTypeEXT> for DebugReportObjectType {
+    fn into(self) -> VkDebugReportObjectTypeEXT {
+        VkDebugReportObjectTypeEXT::from_raw(self as _)
+    }
+}
+
+/// A message reported by a layer or the implementation.  See vulkan
+/// specification, section 33.2 Debug Report Callbacks
+#[derive(Debug, Clone)]
+pub struct DebugMessage {
+    pub flags: DebugReportFlags,
+    pub object_type: DebugReportObjectType,
+    /// The handle of the object the message is about, or 0 if there is none
+    pub object: u64,
+    /// A layer-defined value identifying where the message was reported
+    pub location: usize,
+    /// A layer-defined code identifying the test which triggered the message
+    pub message_code: i32,
+    /// The abbreviated name of the layer (or implementation) reporting it
+    pub layer_prefix: String,
+    pub message: String,
+}
+
+impl DebugMessage {
+    pub fn is_error(&self) -> bool
+    {
+        self.flags.contains(DEBUG_REPORT_ERROR_BIT)
+    }
+}
+
+impl fmt::Display for DebugMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {:?} code {}: {}", self.layer_prefix, self.object_type,
+               self.message_code, self.message)
+    }
+}
+
+/// The user function called for each debug message.  Returning true asks the
+/// layer to abort the Vulkan call which triggered the message.
+pub type DebugCallbackFn = Fn(&DebugMessage) -> bool + Send + Sync;
 
 pub struct DebugCallback {
     loader: InstanceLoader,
-    instance: VkInstance, // reference only, we do not drop this.
-    callback: VkDebugReportCallbackEXT
+    instance: Instance, // keeps the instance alive until we are destroyed
+    callback: VkDebugReportCallbackEXT,
+    // pUserData points into this box, so it must outlive the callback
+    #[allow(dead_code)]
+    user_callback: Box<Box<DebugCallbackFn>>,
+    // Messages collected in capture mode
+    captured: Option<Arc<Mutex<Vec<DebugMessage>>>>,
+    panic_on_error: bool,
 }
 
 impl DebugCallback {
-    pub fn new(instance: VkInstance, loader: InstanceLoader) -> Result<DebugCallback, Error>
+    pub fn new<F>(instance: &Instance, loader: InstanceLoader, flags: DebugReportFlags,
+                  user_callback: F) -> Result<DebugCallback, Error>
+        where F: Fn(&DebugMessage) -> bool + Send + Sync + 'static
     {
+        loader.require_extension(VK_EXT_DEBUG_REPORT_EXTENSION_NAME_STR)?;
+
+        let user_callback: Box<Box<DebugCallbackFn>> = Box::new(Box::new(user_callback));
+
         let mut create_info: VkDebugReportCallbackCreateInfoEXT =
             Default::default();
-        // FIXME: add config settings for these flags.
-        create_info.flags = VK_DEBUG_REPORT_ERROR_BIT_EXT
-            | VK_DEBUG_REPORT_WARNING_BIT_EXT
-            | VK_DEBUG_REPORT_PERFORMANCE_WARNING_BIT_EXT;
+        create_info.flags = flags.into();
         create_info.pfnCallback = vulkan_debug_callback;
+        create_info.pUserData = &*user_callback as *const Box<DebugCallbackFn> as *mut c_void;
 
         let mut callback: VkDebugReportCallbackEXT = unsafe { mem::uninitialized() };
 
         unsafe {
             vk_try!((loader.0.ext_debug_report.vkCreateDebugReportCallbackEXT)(
-                instance, &create_info,
-                ptr::null(), // pAllocator: *const VkAllocationCallbacks
-                &mut callback));
+                instance.inner(), &create_info,
+                vk_allocator(&instance.allocator()),
+                &mut callback), "vkCreateDebugReportCallbackEXT", "DebugCallback::new");
         }
 
         Ok(DebugCallback {
             loader: loader,
-            instance: instance,
-            callback: callback
+            instance: instance.clone(),
+            callback: callback,
+            user_callback: user_callback,
+            captured: None,
+            panic_on_error: false,
         })
     }
+
+    pub fn inner(&self) -> VkDebugReportCallbackEXT
+    {
+        self.callback
+    }
+
+    /// Create a callback which collects every message matching `flags`, to be
+    /// retrieved with `take_messages()` or `take_errors()`.
+    pub fn capture(instance: &Instance, loader: InstanceLoader, flags: DebugReportFlags)
+                   -> Result<DebugCallback, Error>
+    {
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let sink = captured.clone();
+        let mut callback = DebugCallback::new(instance, loader, flags, move |message| {
+            sink.lock().unwrap_or_else(PoisonError::into_inner).push(message.clone());
+            false
+        })?;
+        callback.captured = Some(captured);
+        Ok(callback)
+    }
+
+    /// In capture mode, panic when dropped if any error messages were
+    /// captured and not taken, listing every captured message.  This turns
+    /// validation errors into test failures.
+    pub fn panic_on_error(mut self, panic_on_error: bool) -> DebugCallback
+    {
+        self.panic_on_error = panic_on_error;
+        self
+    }
+
+    /// Remove and return all captured messages (always empty unless in
+    /// capture mode)
+    pub fn take_messages(&self) -> Vec<DebugMessage>
+    {
+        match self.captured {
+            Some(ref captured) => {
+                let mut captured = captured.lock().unwrap_or_else(PoisonError::into_inner);
+                mem::replace(&mut *captured, Vec::new())
+            },
+            None => Vec::new(),
+        }
+    }
+
+    /// Remove and return the captured error messages, leaving any others
+    pub fn take_errors(&self) -> Vec<DebugMessage>
+    {
+        match self.captured {
+            Some(ref captured) => {
+                let mut captured = captured.lock().unwrap_or_else(PoisonError::into_inner);
+                let (errors, others): (Vec<DebugMessage>, Vec<DebugMessage>) =
+                    mem::replace(&mut *captured, Vec::new())
+                        .into_iter()
+                        .partition(|m| m.is_error());
+                *captured = others;
+                errors
+            },
+            None => Vec::new(),
+        }
+    }
 }
 
 impl Drop for DebugCallback {
     fn drop(&mut self) {
         unsafe {
             (self.loader.0.ext_debug_report.vkDestroyDebugReportCallbackEXT)(
-                self.instance, self.callback, ptr::null());
+                self.instance.inner(), self.callback,
+                vk_allocator(&self.instance.allocator()));
+        }
+
+        // Do not panic while already panicking, which would abort
+        if self.panic_on_error && !thread::panicking() {
+            let messages = self.take_messages();
+            if messages.iter().any(|m| m.is_error()) {
+                let list: Vec<String> = messages.iter().map(|m| m.to_string()).collect();
+                panic!("Vulkan reported errors:\n{}", list.join("\n"));
+            }
         }
     }
 }
 
+unsafe fn lossy_string(p: *const c_char) -> String
+{
+    if p.is_null() {
+        String::new()
+    } else {
+        String::from_utf8_lossy(CStr::from_ptr(p).to_bytes()).into_owned()
+    }
+}
+
 unsafe extern "system" fn vulkan_debug_callback(flags: VkDebugReportFlagsEXT,
-                                                _: VkDebugReportObjectTypeEXT,
-                                                _: u64,
-                                                _: usize,
-                                                _: i32,
-                                                _: *const i8,
-                                                p_message: *const i8,
-                                                _: *mut ::libc::c_void)
+                                                object_type: VkDebugReportObjectTypeEXT,
+                                                object: u64,
+                                                location: usize,
+                                                message_code: i32,
+                                                p_layer_prefix: *const c_char,
+                                                p_message: *const c_char,
+                                                p_user_data: *mut c_void)
                                                 -> u32
 {
-    println!("{:?}", CStr::from_ptr(p_message));
+    let user_callback = &*(p_user_data as *const Box<DebugCallbackFn>);
 
-    // We should return true here ONLY IF this was a validation ERROR (not warning
-    // or info).
-    if flags.intersects(VK_DEBUG_REPORT_ERROR_BIT_EXT) {
-        1
-    } else {
-        0
+    // Unwinding into the driver is undefined behaviour, so a panic in the
+    // user's closure is caught here and the call is not aborted
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let message = DebugMessage {
+            flags: From::from(flags),
+            object_type: From::from(object_type),
+            object: object,
+            location: location,
+            message_code: message_code,
+            layer_prefix: lossy_string(p_layer_prefix),
+            message: lossy_string(p_message),
+        };
+        user_callback(&message)
+    }));
+
+    match result {
+        Ok(true) => VK_TRUE,
+        _ => VK_FALSE,
     }
 }
@@ -0,0 +1,176 @@
+use std::ffi::CString;
+use std::mem;
+use std::ptr;
+use libc::c_void;
+use vks::*;
+
+use {Error, Instance};
+use instance::{DebugCallback, DebugReportObjectType};
+use instance::physical_device::PhysicalDevice;
+#[cfg(feature = "khr_surface")]
+use instance::surface::Surface;
+use super::{Device, Queue, CommandBuffer};
+
+/// A Vulkan object which can be given a name or tag with
+/// `Device::set_object_name()` and `Device::set_object_tag()`
+pub trait DebugMarkerObject {
+    fn object_type(&self) -> DebugReportObjectType;
+
+    /// The raw handle, as passed to the debug marker functions
+    fn object_handle(&self) -> u64;
+}
+
+impl DebugMarkerObject for Instance {
+    fn object_type(&self) -> DebugReportObjectType {
+        DebugReportObjectType::Instance
+    }
+
+    fn object_handle(&self) -> u64 {
+        self.inner() as u64
+    }
+}
+
+impl DebugMarkerObject for PhysicalDevice {
+    fn object_type(&self) -> DebugReportObjectType {
+        DebugReportObjectType::PhysicalDevice
+    }
+
+    fn object_handle(&self) -> u64 {
+        self.inner() as u64
+    }
+}
+
+impl DebugMarkerObject for Device {
+    fn object_type(&self) -> DebugReportObjectType {
+        DebugReportObjectType::Device
+    }
+
+    fn object_handle(&self) -> u64 {
+        self.inner() as u64
+    }
+}
+
+impl DebugMarkerObject for Queue {
+    fn object_type(&self) -> DebugReportObjectType {
+        DebugReportObjectType::Queue
+    }
+
+    fn object_handle(&self) -> u64 {
+        self.inner() as u64
+    }
+}
+
+#[cfg(feature = "khr_surface")]
+impl DebugMarkerObject for Surface {
+    fn object_type(&self) -> DebugReportObjectType {
+        DebugReportObjectType::SurfaceKHR
+    }
+
+    fn object_handle(&self) -> u64 {
+        unsafe { mem::transmute(self.inner()) }
+    }
+}
+
+impl DebugMarkerObject for DebugCallback {
+    fn object_type(&self) -> DebugReportObjectType {
+        DebugReportObjectType::DebugReportCallbackEXT
+    }
+
+    fn object_handle(&self) -> u64 {
+        unsafe { mem::transmute(self.inner()) }
+    }
+}
+
+/// See vulkan specification, section 33.3 Debug Markers
+impl Device {
+    /// Give an object a name, to be shown by debuggers and in validation
+    /// messages in place of its handle
+    pub fn set_object_name<T: DebugMarkerObject>(&self, object: &T, name: &str)
+                                                 -> Result<(), Error>
+    {
+        self.inner.loader.require_extension(VK_EXT_DEBUG_MARKER_EXTENSION_NAME_STR)?;
+        let name = CString::new(name)?;
+        let name_info = VkDebugMarkerObjectNameInfoEXT {
+            sType: VK_STRUCTURE_TYPE_DEBUG_MARKER_OBJECT_NAME_INFO_EXT,
+            pNext: ptr::null(),
+            objectType: object.object_type().into(),
+            object: object.object_handle(),
+            pObjectName: name.as_ptr(),
+        };
+        vk_try!(unsafe {
+            (self.inner.loader.0.ext_debug_marker.vkDebugMarkerSetObjectNameEXT)(
+                self.inner(), &name_info)
+        }, "vkDebugMarkerSetObjectNameEXT", "Device::set_object_name");
+        Ok(())
+    }
+
+    /// Attach arbitrary binary data to an object, for use by debuggers
+    pub fn set_object_tag<T: DebugMarkerObject>(&self, object: &T, tag_name: u64, tag: &[u8])
+                                                -> Result<(), Error>
+    {
+        self.inner.loader.require_extension(VK_EXT_DEBUG_MARKER_EXTENSION_NAME_STR)?;
+        let tag_info = VkDebugMarkerObjectTagInfoEXT {
+            sType: VK_STRUCTURE_TYPE_DEBUG_MARKER_OBJECT_TAG_INFO_EXT,
+            pNext: ptr::null(),
+            objectType: object.object_type().into(),
+            object: object.object_handle(),
+            tagName: tag_name,
+            tagSize: tag.len(),
+            pTag: tag.as_ptr() as *const c_void,
+        };
+        vk_try!(unsafe {
+            (self.inner.loader.0.ext_debug_marker.vkDebugMarkerSetObjectTagEXT)(
+                self.inner(), &tag_info)
+        }, "vkDebugMarkerSetObjectTagEXT", "Device::set_object_tag");
+        Ok(())
+    }
+
+    /// Open a named region of the command buffer, which must be closed with
+    /// `cmd_debug_marker_end()`.  A `color` of all zeroes means none.
+    pub fn cmd_debug_marker_begin(&self, command_buffer: CommandBuffer, label: &str,
+                                  color: [f32; 4]) -> Result<(), Error>
+    {
+        self.inner.loader.require_extension(VK_EXT_DEBUG_MARKER_EXTENSION_NAME_STR)?;
+        let label = CString::new(label)?;
+        let marker_info = marker_info(&label, color);
+        unsafe {
+            (self.inner.loader.0.ext_debug_marker.vkCmdDebugMarkerBeginEXT)(
+                command_buffer, &marker_info);
+        }
+        Ok(())
+    }
+
+    /// Close the region most recently opened with `cmd_debug_marker_begin()`
+    pub fn cmd_debug_marker_end(&self, command_buffer: CommandBuffer) -> Result<(), Error>
+    {
+        self.inner.loader.require_extension(VK_EXT_DEBUG_MARKER_EXTENSION_NAME_STR)?;
+        unsafe {
+            (self.inner.loader.0.ext_debug_marker.vkCmdDebugMarkerEndEXT)(command_buffer);
+        }
+        Ok(())
+    }
+
+    /// Insert a single named marker into the command buffer
+    pub fn cmd_debug_marker_insert(&self, command_buffer: CommandBuffer, label: &str,
+                                   color: [f32; 4]) -> Result<(), Error>
+    {
+        self.inner.loader.require_extension(VK_EXT_DEBUG_MARKER_EXTENSION_NAME_STR)?;
+        let label = CString::new(label)?;
+        let marker_info = marker_info(&label, color);
+        unsafe {
+            (self.inner.loader.0.ext_debug_marker.vkCmdDebugMarkerInsertEXT)(
+                command_buffer, &marker_info);
+        }
+        Ok(())
+    }
+}
+
+fn marker_info(label: &CString, color: [f32; 4]) -> VkDebugMarkerMarkerInfoEXT
+{
+    VkDebugMarkerMarkerInfoEXT {
+        sType: VK_STRUCTURE_TYPE_DEBUG_MARKER_MARKER_INFO_EXT,
+        pNext: ptr::null(),
+        pMarkerName: label.as_ptr(),
+        color: color,
+    }
+}
@@ -2,69 +2,139 @@
 use std::sync::Arc;
 use vks::*;
 use error::Error;
+use InstanceLoader;
 
+/// The second field is the device extensions whose functions were loaded
 #[derive(Clone)]
-pub struct DeviceLoader(pub Arc<DeviceProcAddrLoader>);
+pub struct DeviceLoader(pub Arc<DeviceProcAddrLoader>, Arc<Vec<String>>);
 
 impl DeviceLoader {
-    pub fn new() -> DeviceLoader {
+    /// Create a loader using the `vkGetDeviceProcAddr` obtained through the
+    /// instance loader, so that it comes from the same Vulkan library.
+    pub fn new(instance_loader: &InstanceLoader) -> DeviceLoader {
         // Instantiate a loader
         let loader = DeviceProcAddrLoader::from_get_device_proc_addr(
-            vkGetDeviceProcAddr);
+            instance_loader.0.core.vkGetDeviceProcAddr);
 
-        DeviceLoader(Arc::new(loader))
+        DeviceLoader(Arc::new(loader), Arc::new(Vec::new()))
     }
 
-    pub fn load(&mut self, device: VkDevice) -> Result<(), Error>
+    pub fn is_extension_enabled(&self, extension_name: &str) -> bool
     {
+        self.1.iter().any(|name| name == extension_name)
+    }
+
+    /// Fails with `Error::ExtensionNotEnabled` unless the functions of the
+    /// device extension were loaded
+    pub fn require_extension(&self, extension_name: &str) -> Result<(), Error>
+    {
+        if self.is_extension_enabled(extension_name) {
+            Ok(())
+        } else {
+            Err(Error::ExtensionNotEnabled(extension_name.to_owned()))
+        }
+    }
+
+    /// Load the device level function pointers.  Extension functions are
+    /// only loaded for the extensions listed in `enabled_extension_names`,
+    /// which should be those that the device was created with.
+    #[allow(unused_variables)]
+    pub fn load(&mut self, device: VkDevice, enabled_extension_names: &[String])
+                -> Result<(), Error>
+    {
+        let enabled = |extension_name: &str| {
+            enabled_extension_names.iter().any(|name| name == extension_name)
+        };
+
         let loader = match Arc::get_mut(&mut self.0) {
             Some(l) => l,
             None => return Err(Error::General(
                 "Loader was cloned prior to running load()".to_owned()))
         };
+        self.1 = Arc::new(enabled_extension_names.to_vec());
 
         unsafe { loader.load_core(device); }
 
         #[cfg(feature = "khr_display_swapchain")]
-        unsafe { loader.load_khr_display_swapchain(device); }
+        if enabled(VK_KHR_DISPLAY_SWAPCHAIN_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_display_swapchain(device); }
+        }
         #[cfg(feature = "ext_debug_marker")]
-        unsafe { loader.load_ext_debug_marker(device); }
+        if enabled(VK_EXT_DEBUG_MARKER_EXTENSION_NAME_STR) {
+            unsafe { loader.load_ext_debug_marker(device); }
+        }
         #[cfg(feature = "amd_draw_indirect_count")]
-        unsafe { loader.load_amd_draw_indirect_count(device); }
+        if enabled(VK_AMD_DRAW_INDIRECT_COUNT_EXTENSION_NAME_STR) {
+            unsafe { loader.load_amd_draw_indirect_count(device); }
+        }
         #[cfg(feature = "nvx_device_generated_commands")]
-        unsafe { loader.load_nvx_device_generated_commands(device); }
+        if enabled(VK_NVX_DEVICE_GENERATED_COMMANDS_EXTENSION_NAME_STR) {
+            unsafe { loader.load_nvx_device_generated_commands(device); }
+        }
         #[cfg(feature = "khr_maintenance1")]
-        unsafe { loader.load_khr_maintenance1(device); }
+        if enabled(VK_KHR_MAINTENANCE1_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_maintenance1(device); }
+        }
         #[cfg(feature = "ext_display_control")]
-        unsafe { loader.load_ext_display_control(device); }
+        if enabled(VK_EXT_DISPLAY_CONTROL_EXTENSION_NAME_STR) {
+            unsafe { loader.load_ext_display_control(device); }
+        }
         #[cfg(feature = "khr_push_descriptor")]
-        unsafe { loader.load_khr_push_descriptor(device); }
+        if enabled(VK_KHR_PUSH_DESCRIPTOR_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_push_descriptor(device); }
+        }
         #[cfg(feature = "khr_descriptor_update_template")]
-        unsafe { loader.load_khr_descriptor_update_template(device); }
+        if enabled(VK_KHR_DESCRIPTOR_UPDATE_TEMPLATE_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_descriptor_update_template(device); }
+        }
         #[cfg(feature = "khx_device_group")]
-        unsafe { loader.load_khx_device_group(device); }
+        if enabled(VK_KHX_DEVICE_GROUP_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khx_device_group(device); }
+        }
         #[cfg(feature = "khx_external_memory_win32")]
-        unsafe { loader.load_khx_external_memory_win32(device); }
+        if enabled(VK_KHX_EXTERNAL_MEMORY_WIN32_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khx_external_memory_win32(device); }
+        }
         #[cfg(feature = "khx_external_memory_fd")]
-        unsafe { loader.load_khx_external_memory_fd(device); }
+        if enabled(VK_KHX_EXTERNAL_MEMORY_FD_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khx_external_memory_fd(device); }
+        }
         #[cfg(feature = "khx_external_semaphore_win32")]
-        unsafe { loader.load_khx_external_semaphore_win32(device); }
+        if enabled(VK_KHX_EXTERNAL_SEMAPHORE_WIN32_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khx_external_semaphore_win32(device); }
+        }
         #[cfg(feature = "khx_external_semaphore_fd")]
-        unsafe { loader.load_khx_external_semaphore_fd(device); }
+        if enabled(VK_KHX_EXTERNAL_SEMAPHORE_FD_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khx_external_semaphore_fd(device); }
+        }
         #[cfg(feature = "nv_clip_space_w_scaling")]
-        unsafe { loader.load_nv_clip_space_w_scaling(device); }
+        if enabled(VK_NV_CLIP_SPACE_W_SCALING_EXTENSION_NAME_STR) {
+            unsafe { loader.load_nv_clip_space_w_scaling(device); }
+        }
         #[cfg(feature = "ext_discard_rectangles")]
-        unsafe { loader.load_ext_discard_rectangles(device); }
+        if enabled(VK_EXT_DISCARD_RECTANGLES_EXTENSION_NAME_STR) {
+            unsafe { loader.load_ext_discard_rectangles(device); }
+        }
         #[cfg(feature = "google_display_timing")]
-        unsafe { loader.load_google_display_timing(device); }
+        if enabled(VK_GOOGLE_DISPLAY_TIMING_EXTENSION_NAME_STR) {
+            unsafe { loader.load_google_display_timing(device); }
+        }
         #[cfg(feature = "ext_hdr_metadata")]
-        unsafe { loader.load_ext_hdr_metadata(device); }
+        if enabled(VK_EXT_HDR_METADATA_EXTENSION_NAME_STR) {
+            unsafe { loader.load_ext_hdr_metadata(device); }
+        }
         #[cfg(feature = "khr_swapchain")]
-        unsafe { loader.load_khr_swapchain(device); }
+        if enabled(VK_KHR_SWAPCHAIN_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_swapchain(device); }
+        }
         #[cfg(feature = "nv_external_memory_win32")]
-        unsafe { loader.load_nv_external_memory_win32(device); }
+        if enabled(VK_NV_EXTERNAL_MEMORY_WIN32_EXTENSION_NAME_STR) {
+            unsafe { loader.load_nv_external_memory_win32(device); }
+        }
         #[cfg(feature = "khr_shared_presentable_image")]
-        unsafe { loader.load_khr_shared_presentable_image(device); }
+        if enabled(VK_KHR_SHARED_PRESENTABLE_IMAGE_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_shared_presentable_image(device); }
+        }
 
         Ok(())
     }
@@ -2,68 +2,223 @@
 mod loader;
 use self::loader::DeviceLoader;
 
+mod queue;
+use self::queue::{QueueInner, get_device_queues};
+pub use self::queue::{Queue, SubmitInfo, Semaphore, Fence, PipelineStageFlags};
+pub use self::queue::{PIPELINE_STAGE_TOP_OF_PIPE_BIT, PIPELINE_STAGE_DRAW_INDIRECT_BIT,
+                      PIPELINE_STAGE_VERTEX_INPUT_BIT, PIPELINE_STAGE_VERTEX_SHADER_BIT,
+                      PIPELINE_STAGE_TESSELLATION_CONTROL_SHADER_BIT,
+                      PIPELINE_STAGE_TESSELLATION_EVALUATION_SHADER_BIT,
+                      PIPELINE_STAGE_GEOMETRY_SHADER_BIT, PIPELINE_STAGE_FRAGMENT_SHADER_BIT,
+                      PIPELINE_STAGE_EARLY_FRAGMENT_TESTS_BIT,
+                      PIPELINE_STAGE_LATE_FRAGMENT_TESTS_BIT,
+                      PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT,
+                      PIPELINE_STAGE_COMPUTE_SHADER_BIT, PIPELINE_STAGE_TRANSFER_BIT,
+                      PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT, PIPELINE_STAGE_HOST_BIT,
+                      PIPELINE_STAGE_ALL_GRAPHICS_BIT, PIPELINE_STAGE_ALL_COMMANDS_BIT};
+#[cfg(feature = "nvx_device_generated_commands")]
+pub use self::queue::PIPELINE_STAGE_COMMAND_PROCESS_BIT_NVX;
+#[cfg(feature = "khr_swapchain")]
+pub use self::queue::{PresentInfo, SwapchainKHR};
+
+#[cfg(feature = "ext_debug_marker")]
+mod debug_marker;
+#[cfg(feature = "ext_debug_marker")]
+pub use self::debug_marker::DebugMarkerObject;
+
+use libc::c_char;
+use std::ffi::CString;
 use std::mem;
 use std::ptr;
+use std::sync::Arc;
 use vks::*;
-use {Error, InstanceLoader, Instance};
+use allocator::vk_allocator;
+use {Error, InstanceLoader, Instance, Allocator};
+use error::{MissingName, NameKind};
 use instance::physical_device::{PhysicalDevice, PhysicalDeviceFeatures};
 
-pub type Queue = VkQueue;
+pub type CommandBuffer = VkCommandBuffer;
+
+/// Queues to create in one queue family, with a priority from 0.0 to 1.0 for
+/// each.  See vulkan specification, section 4.3.2 Queue Creation
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceQueueCreateInfo {
+    pub queue_family_index: u32,
+    pub queue_priorities: Vec<f32>,
+}
 
+/// See vulkan specification, section 4.2.1 Device Creation
+#[derive(Clone, Default)]
+pub struct DeviceCreateInfo {
+    /// At most one entry per queue family.  `QueuePlan::queue_create_infos`
+    /// can be used here.
+    pub queue_create_infos: Vec<DeviceQueueCreateInfo>,
+    /// Device layers are deprecated, but for compatibility with older
+    /// implementations should match the layers enabled on the instance
+    pub enabled_layer_names: Vec<String>,
+    /// Device extensions to enable, such as VK_KHR_swapchain for presenting.
+    /// Device creation fails if any of these are not available.
+    pub enabled_extension_names: Vec<String>,
+    pub enabled_features: PhysicalDeviceFeatures,
+    /// Host memory allocator for the device and the objects created from it.
+    /// `None` lets the implementation allocate.
+    pub allocator: Option<Allocator>,
+}
+
+/// See vulkan specification, section 4.2 Devices
+///
+/// Clones share the same underlying device, which is destroyed when the last
+/// clone is dropped.  The device keeps its physical device, and so its
+/// instance, alive.
+#[derive(Clone)]
 pub struct Device {
+    inner: Arc<DeviceInner>,
+}
+
+struct DeviceInner {
     device: VkDevice,
     loader: DeviceLoader,
+    allocator: Option<Allocator>,
+    physical_device: PhysicalDevice,
+    queue_create_infos: Vec<DeviceQueueCreateInfo>,
+    queues: Vec<Arc<QueueInner>>,
+    enabled_layer_names: Vec<String>,
+    enabled_extension_names: Vec<String>,
+    enabled_features: PhysicalDeviceFeatures,
 }
 
+// Vulkan devices may be used from any thread
+unsafe impl Send for DeviceInner {}
+unsafe impl Sync for DeviceInner {}
+
 impl Device {
     pub fn inner(&self) -> VkDevice
     {
-        self.device
+        self.inner.device
+    }
+
+    /// The physical device this device was created from
+    pub fn physical_device(&self) -> &PhysicalDevice
+    {
+        &self.inner.physical_device
+    }
+
+    /// The instance this device was created from
+    pub fn instance(&self) -> &Instance
+    {
+        self.inner.physical_device.instance()
+    }
+
+    /// The host memory allocator the device was created with
+    pub fn allocator(&self) -> Option<Allocator>
+    {
+        self.inner.allocator.clone()
+    }
+
+    /// The queues the device was created with
+    pub fn queue_create_infos(&self) -> &[DeviceQueueCreateInfo]
+    {
+        &self.inner.queue_create_infos
+    }
+
+    pub fn enabled_layer_names(&self) -> &[String]
+    {
+        &self.inner.enabled_layer_names
+    }
+
+    pub fn enabled_extension_names(&self) -> &[String]
+    {
+        &self.inner.enabled_extension_names
+    }
+
+    pub fn is_extension_enabled(&self, extension_name: &str) -> bool
+    {
+        self.inner.enabled_extension_names.iter().any(|name| name == extension_name)
+    }
+
+    pub fn enabled_features(&self) -> &PhysicalDeviceFeatures
+    {
+        &self.inner.enabled_features
     }
 }
 
-impl Drop for Device {
+impl Drop for DeviceInner {
     fn drop(&mut self) {
         unsafe {
             (self.loader.0.core.vkDestroyDevice)(
                 self.device,
-                ptr::null());
+                vk_allocator(&self.allocator));
         }
     }
 }
 
 impl Instance {
+    /// See vulkan specification, section 4.2.1 Device Creation.  Fails with
+    /// `Error::NotPresent` if any of the layers, extensions or features are
+    /// not offered by the physical device.
+    #[allow(unused_variables)]
     pub fn create_device(&self, instance_loader: InstanceLoader,
                          physical_device: &PhysicalDevice,
-                         enabled_physical_device_features: PhysicalDeviceFeatures,
-                         queue_family_index: u32)
+                         create_info: DeviceCreateInfo)
                          -> Result<Device, Error>
     {
-        let device_extension_names = [ VK_KHR_SWAPCHAIN_EXTENSION_NAME.as_ptr() as *const i8 ];
-
-        let enabled_features = enabled_physical_device_features.into_vk();
+        check_device_create_info(&instance_loader, physical_device, &create_info)?;
 
-        let priorities = [ 1.0 ];
+        // Setup strings for passing into vkCreateDevice down below.  These must
+        // not go out of scope until after that function is called.
+        let (extension_names_owned, extension_names) = {
+            let mut extension_names_owned: Vec<CString> = Vec::new();
+            for ref name in &create_info.enabled_extension_names {
+                extension_names_owned.push( CString::new(name.as_bytes())? );
+            }
+            let extension_names: Vec<*const c_char> = extension_names_owned.iter()
+                .map(|name| name.as_ptr())
+                .collect();
+            (extension_names_owned, extension_names)
+        };
 
-        let queue_info = VkDeviceQueueCreateInfo {
-            sType: VK_STRUCTURE_TYPE_DEVICE_QUEUE_CREATE_INFO,
-            pNext: ptr::null(),
-            flags: Default::default(),
-            queueFamilyIndex: queue_family_index,
-            queueCount: priorities.len() as u32,
-            pQueuePriorities: priorities.as_ptr()
+        let (layer_names_owned, layer_names) = {
+            let mut layer_names_owned: Vec<CString> = Vec::new();
+            for ref name in &create_info.enabled_layer_names {
+                layer_names_owned.push( CString::new(name.as_bytes())? );
+            }
+            let layer_names: Vec<*const c_char> = layer_names_owned.iter()
+                .map(|name| name.as_ptr())
+                .collect();
+            (layer_names_owned, layer_names)
         };
 
-        let create_info = VkDeviceCreateInfo {
+        let enabled_features = create_info.enabled_features.clone().into_vk();
+
+        let queue_infos: Vec<VkDeviceQueueCreateInfo> = create_info.queue_create_infos.iter()
+            .map(|info| VkDeviceQueueCreateInfo {
+                sType: VK_STRUCTURE_TYPE_DEVICE_QUEUE_CREATE_INFO,
+                pNext: ptr::null(),
+                flags: Default::default(),
+                queueFamilyIndex: info.queue_family_index,
+                queueCount: info.queue_priorities.len() as u32,
+                pQueuePriorities: info.queue_priorities.as_ptr()
+            })
+            .collect();
+
+        let vk_create_info = VkDeviceCreateInfo {
             sType: VK_STRUCTURE_TYPE_DEVICE_CREATE_INFO,
             pNext: ptr::null(),
             flags: Default::default(),
-            queueCreateInfoCount: 1,
-            pQueueCreateInfos: &queue_info,
-            enabledLayerCount: 0,
-            ppEnabledLayerNames: ptr::null(),
-            enabledExtensionCount: device_extension_names.len() as u32,
-            ppEnabledExtensionNames: device_extension_names.as_ptr(),
+            queueCreateInfoCount: queue_infos.len() as u32,
+            pQueueCreateInfos: queue_infos.as_ptr(),
+            enabledLayerCount: layer_names.len() as u32,
+            ppEnabledLayerNames: if layer_names.len() > 0 {
+                layer_names.as_ptr()
+            } else {
+                ptr::null()
+            },
+            enabledExtensionCount: extension_names.len() as u32,
+            ppEnabledExtensionNames: if extension_names.len() > 0 {
+                extension_names.as_ptr()
+            } else {
+                ptr::null()
+            },
             pEnabledFeatures: &enabled_features
         };
 
@@ -71,37 +226,110 @@ impl Instance {
             let mut vkdevice: VkDevice = mem::uninitialized();
             vk_try!((instance_loader.0.core.vkCreateDevice)(
                 physical_device.inner(),
-                &create_info,
-                ptr::null(),
-                &mut vkdevice));
+                &vk_create_info,
+                vk_allocator(&create_info.allocator),
+                &mut vkdevice), "vkCreateDevice", "Instance::create_device");
             vkdevice
         };
 
-        let mut device_loader = DeviceLoader::new();
-        device_loader.load(vkdevice)?;
+        let mut device_loader = DeviceLoader::new(&instance_loader);
+        if let Err(e) = device_loader.load(vkdevice, &create_info.enabled_extension_names) {
+            // Nothing owns the device yet, so destroy it here rather than leak it
+            let mut loader = DeviceProcAddrLoader::from_get_device_proc_addr(
+                instance_loader.0.core.vkGetDeviceProcAddr);
+            unsafe {
+                loader.load_core(vkdevice);
+                (loader.core.vkDestroyDevice)(vkdevice, vk_allocator(&create_info.allocator));
+            }
+            return Err(e);
+        }
+
+        let queues = get_device_queues(&device_loader, vkdevice, &create_info.queue_create_infos);
 
         Ok(Device {
-            device: vkdevice,
-            loader: device_loader
+            inner: Arc::new(DeviceInner {
+                device: vkdevice,
+                loader: device_loader,
+                allocator: create_info.allocator,
+                physical_device: physical_device.clone(),
+                queue_create_infos: create_info.queue_create_infos,
+                queues: queues,
+                enabled_layer_names: create_info.enabled_layer_names,
+                enabled_extension_names: create_info.enabled_extension_names,
+                enabled_features: create_info.enabled_features,
+            })
         })
     }
 }
 
-impl Device {
-    pub fn get_queue(&self, family_index: u32, queue_index: u32)
-                     -> Result<Queue, Error>
-    {
-        let vkqueue = unsafe {
-            let mut vkqueue: VkQueue = mem::uninitialized();
-            (self.loader.0.core.vkGetDeviceQueue)(
-                self.device,
-                family_index,
-                queue_index,
-                &mut vkqueue
-            );
-            vkqueue
+// Check the requested layers, extensions and features against those offered
+// by the physical device (extensions may also come from the layers being
+// enabled), and the requested queues against its queue families.
+fn check_device_create_info(loader: &InstanceLoader, physical_device: &PhysicalDevice,
+                            create_info: &DeviceCreateInfo)
+                            -> Result<(), Error>
+{
+    let available_layers: Vec<String> = physical_device.enumerate_layer_properties(loader)?
+        .into_iter()
+        .map(|lp| lp.layer_name)
+        .collect();
+    let mut missing = MissingName::find(NameKind::Layer,
+                                        &create_info.enabled_layer_names,
+                                        &available_layers);
+
+    let mut available: Vec<String> = physical_device.get_extension_properties(loader, None)?
+        .into_iter()
+        .map(|ep| ep.extension_name)
+        .collect();
+    for layer_name in &create_info.enabled_layer_names {
+        if available_layers.contains(layer_name) {
+            available.extend(physical_device.get_extension_properties(loader, Some(layer_name))?
+                             .into_iter()
+                             .map(|ep| ep.extension_name));
+        }
+    }
+    missing.extend(MissingName::find(NameKind::DeviceExtension,
+                                     &create_info.enabled_extension_names,
+                                     &available));
+
+    let supported_features = physical_device.get_features(loader)?;
+    missing.extend(create_info.enabled_features.missing_from(&supported_features)
+                   .into_iter()
+                   .map(|name| MissingName {
+                       kind: NameKind::DeviceFeature,
+                       name: name.to_owned(),
+                       similar: Vec::new(),
+                   }));
+    if !missing.is_empty() {
+        return Err(Error::NotPresent(missing));
+    }
+
+    if create_info.queue_create_infos.is_empty() {
+        return Err(Error::General("At least one queue must be requested".to_owned()));
+    }
+    let families = physical_device.get_queue_family_properties(loader)?;
+    for (i, info) in create_info.queue_create_infos.iter().enumerate() {
+        let index = info.queue_family_index;
+        let family = match families.get(index as usize) {
+            Some(family) => family,
+            None => return Err(Error::General(
+                format!("Queue family {} does not exist", index))),
         };
-        Ok(vkqueue)
+        if info.queue_priorities.is_empty()
+            || info.queue_priorities.len() > family.queue_count as usize
+        {
+            return Err(Error::General(
+                format!("Queue family {} has {} queues but {} were requested",
+                        index, family.queue_count, info.queue_priorities.len())));
+        }
+        if info.queue_priorities.iter().any(|&p| !(p >= 0.0 && p <= 1.0)) {
+            return Err(Error::General(
+                format!("Queue priorities for family {} must be between 0.0 and 1.0", index)));
+        }
+        if create_info.queue_create_infos[..i].iter().any(|q| q.queue_family_index == index) {
+            return Err(Error::General(
+                format!("Queue family {} was requested more than once", index)));
+        }
     }
+    Ok(())
 }
-
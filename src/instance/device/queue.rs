@@ -0,0 +1,278 @@
+use std::mem;
+use std::ptr;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use vks::*;
+use Error;
+#[cfg(feature = "khr_swapchain")]
+use error::Status;
+use super::{Device, DeviceQueueCreateInfo, DeviceLoader, CommandBuffer};
+
+pub type Semaphore = VkSemaphore;
+pub type Fence = VkFence;
+#[cfg(feature = "khr_swapchain")]
+pub type SwapchainKHR = VkSwapchainKHR;
+
+bitflags! {
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct PipelineStageFlags: u32 {
+        const PIPELINE_STAGE_TOP_OF_PIPE_BIT = 0x00000001;
+        const PIPELINE_STAGE_DRAW_INDIRECT_BIT = 0x00000002;
+        const PIPELINE_STAGE_VERTEX_INPUT_BIT = 0x00000004;
+        const PIPELINE_STAGE_VERTEX_SHADER_BIT = 0x00000008;
+        const PIPELINE_STAGE_TESSELLATION_CONTROL_SHADER_BIT = 0x00000010;
+        const PIPELINE_STAGE_TESSELLATION_EVALUATION_SHADER_BIT = 0x00000020;
+        const PIPELINE_STAGE_GEOMETRY_SHADER_BIT = 0x00000040;
+        const PIPELINE_STAGE_FRAGMENT_SHADER_BIT = 0x00000080;
+        const PIPELINE_STAGE_EARLY_FRAGMENT_TESTS_BIT = 0x00000100;
+        const PIPELINE_STAGE_LATE_FRAGMENT_TESTS_BIT = 0x00000200;
+        const PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT = 0x00000400;
+        const PIPELINE_STAGE_COMPUTE_SHADER_BIT = 0x00000800;
+        const PIPELINE_STAGE_TRANSFER_BIT = 0x00001000;
+        const PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT = 0x00002000;
+        const PIPELINE_STAGE_HOST_BIT = 0x00004000;
+        const PIPELINE_STAGE_ALL_GRAPHICS_BIT = 0x00008000;
+        const PIPELINE_STAGE_ALL_COMMANDS_BIT = 0x00010000;
+        #[cfg(feature = "nvx_device_generated_commands")]
+        const PIPELINE_STAGE_COMMAND_PROCESS_BIT_NVX = 0x00020000;
+    }
+}
+
+impl From<VkPipelineStageFlags> for PipelineStageFlags {
+    fn from(vk: VkPipelineStageFlags) -> PipelineStageFlags {
+        PipelineStageFlags::from_bits(vk.bits()).unwrap()
+    }
+}
+
+impl Into<VkPipelineStageFlags> for PipelineStageFlags {
+    fn into(self) -> VkPipelineStageFlags {
+        VkPipelineStageFlags::from_bits(self.bits()).unwrap()
+    }
+}
+
+/// A batch of command buffers to submit.  See vulkan specification, section
+/// 5.4 Queue Submission
+#[derive(Clone, Default)]
+pub struct SubmitInfo {
+    /// Semaphores to wait on, each with the pipeline stages which wait for it
+    pub wait_semaphores: Vec<(Semaphore, PipelineStageFlags)>,
+    pub command_buffers: Vec<CommandBuffer>,
+    /// Semaphores to signal once the command buffers have completed
+    pub signal_semaphores: Vec<Semaphore>,
+}
+
+/// See vulkan specification, section 29.6 WSI Swapchain
+#[cfg(feature = "khr_swapchain")]
+#[derive(Clone, Default)]
+pub struct PresentInfo {
+    pub wait_semaphores: Vec<Semaphore>,
+    /// Each swapchain to present to, with the index of the image to present
+    pub swapchains: Vec<(SwapchainKHR, u32)>,
+}
+
+/// See vulkan specification, section 4.3 Queues
+///
+/// Clones share the same queue, as do queues got from the device more than
+/// once.  Vulkan requires host access to a queue to be externally
+/// synchronized, so each operation here holds a lock on the queue, and clones
+/// may be used from several threads.  The queue keeps its device alive.
+#[derive(Clone)]
+pub struct Queue {
+    device: Device,
+    inner: Arc<QueueInner>,
+}
+
+/// The queue handle and its lock, shared by the device and every `Queue` for
+/// that queue
+pub struct QueueInner {
+    queue: VkQueue,
+    family_index: u32,
+    queue_index: u32,
+    lock: Mutex<()>,
+}
+
+// Vulkan queues may be used from any thread, one thread at a time
+unsafe impl Send for QueueInner {}
+unsafe impl Sync for QueueInner {}
+
+impl QueueInner {
+    // The guard protects no data, so a panic while it was held cannot have
+    // left anything inconsistent
+    fn lock(&self) -> MutexGuard<()>
+    {
+        self.lock.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Get every queue the device was created with.  Used by
+/// `Instance::create_device()`.
+pub fn get_device_queues(loader: &DeviceLoader, device: VkDevice,
+                         queue_create_infos: &[DeviceQueueCreateInfo])
+                         -> Vec<Arc<QueueInner>>
+{
+    let mut queues = Vec::new();
+    for info in queue_create_infos {
+        for queue_index in 0..info.queue_priorities.len() as u32 {
+            let vkqueue = unsafe {
+                let mut vkqueue: VkQueue = mem::uninitialized();
+                (loader.0.core.vkGetDeviceQueue)(
+                    device,
+                    info.queue_family_index,
+                    queue_index,
+                    &mut vkqueue
+                );
+                vkqueue
+            };
+            queues.push(Arc::new(QueueInner {
+                queue: vkqueue,
+                family_index: info.queue_family_index,
+                queue_index: queue_index,
+                lock: Mutex::new(()),
+            }));
+        }
+    }
+    queues
+}
+
+impl Device {
+    /// Fails unless the queue was requested when the device was created
+    pub fn get_queue(&self, family_index: u32, queue_index: u32)
+                     -> Result<Queue, Error>
+    {
+        match self.inner.queues.iter()
+            .find(|q| q.family_index == family_index && q.queue_index == queue_index)
+        {
+            Some(inner) => Ok(Queue {
+                device: self.clone(),
+                inner: inner.clone(),
+            }),
+            None => Err(Error::General(
+                format!("Queue {} of family {} was not requested when creating the device",
+                        queue_index, family_index))),
+        }
+    }
+
+    /// Wait for every queue of the device to become idle.  This locks every
+    /// queue while waiting.  See vulkan specification, section 6.3 Wait Idle
+    /// Operations
+    pub fn wait_idle(&self) -> Result<(), Error>
+    {
+        let _guards: Vec<MutexGuard<()>> = self.inner.queues.iter()
+            .map(|q| q.lock())
+            .collect();
+        vk_try!(unsafe {
+            (self.inner.loader.0.core.vkDeviceWaitIdle)(self.inner())
+        }, "vkDeviceWaitIdle", "Device::wait_idle");
+        Ok(())
+    }
+}
+
+impl Queue {
+    pub fn inner(&self) -> VkQueue
+    {
+        self.inner.queue
+    }
+
+    pub fn device(&self) -> &Device
+    {
+        &self.device
+    }
+
+    pub fn family_index(&self) -> u32
+    {
+        self.inner.family_index
+    }
+
+    /// The index of the queue within its family
+    pub fn queue_index(&self) -> u32
+    {
+        self.inner.queue_index
+    }
+
+    /// Submit batches of command buffers, signalling `fence` once they have
+    /// all completed.  See vulkan specification, section 5.4 Queue Submission
+    pub fn submit(&self, submits: &[SubmitInfo], fence: Option<&Fence>) -> Result<(), Error>
+    {
+        // These must not go out of scope until after vkQueueSubmit is called
+        let wait_semaphores: Vec<Vec<VkSemaphore>> = submits.iter()
+            .map(|s| s.wait_semaphores.iter().map(|&(semaphore, _)| semaphore).collect())
+            .collect();
+        let wait_stages: Vec<Vec<VkPipelineStageFlags>> = submits.iter()
+            .map(|s| s.wait_semaphores.iter().map(|&(_, stages)| stages.into()).collect())
+            .collect();
+
+        let submit_infos: Vec<VkSubmitInfo> = submits.iter().enumerate()
+            .map(|(i, s)| VkSubmitInfo {
+                sType: VK_STRUCTURE_TYPE_SUBMIT_INFO,
+                pNext: ptr::null(),
+                waitSemaphoreCount: wait_semaphores[i].len() as u32,
+                pWaitSemaphores: wait_semaphores[i].as_ptr(),
+                pWaitDstStageMask: wait_stages[i].as_ptr(),
+                commandBufferCount: s.command_buffers.len() as u32,
+                pCommandBuffers: s.command_buffers.as_ptr(),
+                signalSemaphoreCount: s.signal_semaphores.len() as u32,
+                pSignalSemaphores: s.signal_semaphores.as_ptr(),
+            })
+            .collect();
+
+        let fence: VkFence = match fence {
+            Some(fence) => *fence,
+            None => unsafe { mem::zeroed() },
+        };
+
+        let _guard = self.inner.lock();
+        vk_try!(unsafe {
+            (self.device.inner.loader.0.core.vkQueueSubmit)(
+                self.inner.queue,
+                submit_infos.len() as u32,
+                submit_infos.as_ptr(),
+                fence)
+        }, "vkQueueSubmit", "Queue::submit");
+        Ok(())
+    }
+
+    /// Wait for the queue to become idle.  See vulkan specification, section
+    /// 6.3 Wait Idle Operations
+    pub fn wait_idle(&self) -> Result<(), Error>
+    {
+        let _guard = self.inner.lock();
+        vk_try!(unsafe {
+            (self.device.inner.loader.0.core.vkQueueWaitIdle)(self.inner.queue)
+        }, "vkQueueWaitIdle", "Queue::wait_idle");
+        Ok(())
+    }
+
+    /// Queue images for presentation.  Returns `Status::SuboptimalKHR` if
+    /// presentation succeeded but the swapchain no longer matches the surface
+    /// exactly; an out of date swapchain is an error (see
+    /// `Error::is_out_of_date()`).
+    #[cfg(feature = "khr_swapchain")]
+    pub fn present(&self, present_info: &PresentInfo) -> Result<Status, Error>
+    {
+        self.device.inner.loader.require_extension(VK_KHR_SWAPCHAIN_EXTENSION_NAME_STR)?;
+
+        let swapchains: Vec<VkSwapchainKHR> = present_info.swapchains.iter()
+            .map(|&(swapchain, _)| swapchain)
+            .collect();
+        let image_indices: Vec<u32> = present_info.swapchains.iter()
+            .map(|&(_, image_index)| image_index)
+            .collect();
+
+        let vk_present_info = VkPresentInfoKHR {
+            sType: VK_STRUCTURE_TYPE_PRESENT_INFO_KHR,
+            pNext: ptr::null(),
+            waitSemaphoreCount: present_info.wait_semaphores.len() as u32,
+            pWaitSemaphores: present_info.wait_semaphores.as_ptr(),
+            swapchainCount: swapchains.len() as u32,
+            pSwapchains: swapchains.as_ptr(),
+            pImageIndices: image_indices.as_ptr(),
+            pResults: ptr::null_mut(),
+        };
+
+        let _guard = self.inner.lock();
+        Ok(vk_try_status!(unsafe {
+            (self.device.inner.loader.0.khr_swapchain.vkQueuePresentKHR)(
+                self.inner.queue, &vk_present_info)
+        }, "vkQueuePresentKHR", "Queue::present"))
+    }
+}
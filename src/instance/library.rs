@@ -0,0 +1,127 @@
+
+use std::ffi::CString;
+#[cfg(unix)]
+use std::ffi::CStr;
+use std::path::Path;
+use libc::c_void;
+#[cfg(windows)]
+use std::os::windows::ffi::OsStrExt;
+#[cfg(windows)]
+use kernel32;
+use error::Error;
+
+/// A dynamically loaded shared library (e.g. libvulkan.so.1).  The library is
+/// unloaded when this is dropped, so it must outlive every function pointer
+/// obtained from it.
+pub struct Library {
+    handle: *mut c_void,
+}
+
+// The handle is only used to look up symbols and to unload, both of which are
+// thread safe.
+unsafe impl Send for Library {}
+unsafe impl Sync for Library {}
+
+impl Library {
+    #[cfg(unix)]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Library, Error>
+    {
+        let path = path.as_ref();
+        let c_path = CString::new(path.to_string_lossy().as_bytes())?;
+        let handle = unsafe {
+            ::libc::dlopen(c_path.as_ptr(), ::libc::RTLD_NOW | ::libc::RTLD_LOCAL)
+        };
+        if handle.is_null() {
+            return Err(Error::VulkanNotAvailable(
+                format!("{}: {}", path.display(), last_dl_error())));
+        }
+        Ok(Library {
+            handle: handle
+        })
+    }
+
+    #[cfg(windows)]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Library, Error>
+    {
+        let path = path.as_ref();
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        let handle = unsafe { kernel32::LoadLibraryW(wide.as_ptr()) };
+        if handle.is_null() {
+            return Err(Error::VulkanNotAvailable(
+                format!("{}: error {}", path.display(),
+                        unsafe { kernel32::GetLastError() })));
+        }
+        Ok(Library {
+            handle: handle as *mut c_void
+        })
+    }
+
+    /// Look up a symbol by name, returning an error if it is not present.
+    #[cfg(unix)]
+    pub fn symbol(&self, name: &str) -> Result<*mut c_void, Error>
+    {
+        let c_name = CString::new(name)?;
+        let symbol = unsafe { ::libc::dlsym(self.handle, c_name.as_ptr()) };
+        if symbol.is_null() {
+            return Err(Error::VulkanNotAvailable(
+                format!("{}: {}", name, last_dl_error())));
+        }
+        Ok(symbol)
+    }
+
+    /// Look up a symbol by name, returning an error if it is not present.
+    #[cfg(windows)]
+    pub fn symbol(&self, name: &str) -> Result<*mut c_void, Error>
+    {
+        let c_name = CString::new(name)?;
+        let symbol = unsafe {
+            kernel32::GetProcAddress(self.handle as _, c_name.as_ptr())
+        };
+        if symbol.is_null() {
+            return Err(Error::VulkanNotAvailable(
+                format!("{}: symbol not found", name)));
+        }
+        Ok(symbol as *mut c_void)
+    }
+}
+
+impl Drop for Library {
+    #[cfg(unix)]
+    fn drop(&mut self) {
+        unsafe {
+            ::libc::dlclose(self.handle);
+        }
+    }
+
+    #[cfg(windows)]
+    fn drop(&mut self) {
+        unsafe {
+            kernel32::FreeLibrary(self.handle as _);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn last_dl_error() -> String
+{
+    unsafe {
+        let error = ::libc::dlerror();
+        if error.is_null() {
+            "unknown error".to_owned()
+        } else {
+            CStr::from_ptr(error).to_string_lossy().into_owned()
+        }
+    }
+}
+
+/// The names under which the system Vulkan loader is normally installed, in
+/// the order we try them.
+#[cfg(all(unix, not(target_os = "android"), not(target_os = "macos"), not(target_os = "ios")))]
+pub const SYSTEM_LIBRARY_NAMES: &'static [&'static str] = &["libvulkan.so.1", "libvulkan.so"];
+#[cfg(target_os = "android")]
+pub const SYSTEM_LIBRARY_NAMES: &'static [&'static str] = &["libvulkan.so"];
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub const SYSTEM_LIBRARY_NAMES: &'static [&'static str] = &["libvulkan.1.dylib", "libvulkan.dylib",
+                                                           "libMoltenVK.dylib"];
+#[cfg(windows)]
+pub const SYSTEM_LIBRARY_NAMES: &'static [&'static str] = &["vulkan-1.dll"];
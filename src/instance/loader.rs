@@ -1,75 +1,230 @@
 
+use std::mem;
+use std::ptr;
+use std::path::Path;
 use std::sync::Arc;
 use vks::*;
-use error::Error;
+use error::{Error, VulkanError};
+use Version;
+use super::library::{Library, SYSTEM_LIBRARY_NAMES};
 
+/// The second field keeps a dynamically loaded Vulkan library alive for as
+/// long as its function pointers may be called.  The third is the
+/// `vkGetInstanceProcAddr` everything was loaded through, and the fourth the
+/// instance extensions whose functions were loaded.
 #[derive(Clone)]
-pub struct InstanceLoader(pub Arc<InstanceProcAddrLoader>);
+pub struct InstanceLoader(pub Arc<InstanceProcAddrLoader>, Option<Arc<Library>>,
+                          PFN_vkGetInstanceProcAddr, Arc<Vec<String>>);
 
 impl InstanceLoader {
-    pub fn new() -> InstanceLoader {
+    /// Create a loader for the system Vulkan loader.  This is the same as
+    /// `load_system()`; nothing is linked against Vulkan at build time.
+    pub fn new() -> Result<InstanceLoader, Error> {
+        InstanceLoader::load_system()
+    }
+
+    /// Create a loader from any `vkGetInstanceProcAddr`, such as one supplied by
+    /// a driver you have loaded yourself, or by a mock driver for testing.
+    pub fn from_get_instance_proc_addr(get_instance_proc_addr: PFN_vkGetInstanceProcAddr)
+                                       -> InstanceLoader
+    {
+        InstanceLoader::with_library(get_instance_proc_addr, None)
+    }
+
+    /// Create a loader by dynamically loading the Vulkan library at `path`.
+    /// This may be the system loader or a driver which can be used directly,
+    /// such as a software implementation.  An `Error::VulkanNotAvailable` is
+    /// returned if the library cannot be loaded or does not export
+    /// `vkGetInstanceProcAddr`.
+    pub fn from_library<P: AsRef<Path>>(path: P) -> Result<InstanceLoader, Error>
+    {
+        let library = Library::open(path)?;
+        let get_instance_proc_addr: PFN_vkGetInstanceProcAddr = unsafe {
+            mem::transmute(library.symbol("vkGetInstanceProcAddr")?)
+        };
+        Ok(InstanceLoader::with_library(
+            get_instance_proc_addr, Some(Arc::new(library))))
+    }
+
+    /// Create a loader by dynamically loading the system Vulkan loader
+    /// (e.g. libvulkan.so.1 or vulkan-1.dll).  An `Error::VulkanNotAvailable`
+    /// is returned if Vulkan is not installed.
+    pub fn load_system() -> Result<InstanceLoader, Error>
+    {
+        let mut failures: Vec<String> = Vec::new();
+        for name in SYSTEM_LIBRARY_NAMES {
+            match InstanceLoader::from_library(name) {
+                Ok(loader) => return Ok(loader),
+                Err(Error::VulkanNotAvailable(s)) => failures.push(s),
+                Err(e) => return Err(e),
+            }
+        }
+        Err(Error::VulkanNotAvailable(failures.join("; ")))
+    }
+
+    fn with_library(get_instance_proc_addr: PFN_vkGetInstanceProcAddr,
+                    library: Option<Arc<Library>>)
+                    -> InstanceLoader
+    {
         // Instantiate a loader
         let mut loader = InstanceProcAddrLoader::from_get_instance_proc_addr(
-            vkGetInstanceProcAddr);
+            get_instance_proc_addr);
 
         // Load function pointers with global scope
         unsafe { loader.load_core_null_instance(); }
 
-        InstanceLoader(Arc::new(loader))
+        InstanceLoader(Arc::new(loader), library, get_instance_proc_addr,
+                       Arc::new(Vec::new()))
     }
 
-    pub fn load(&mut self, instance: VkInstance) -> Result<(), Error>
+    /// The `vkGetInstanceProcAddr` everything was loaded through
+    pub fn get_instance_proc_addr(&self) -> PFN_vkGetInstanceProcAddr
     {
+        self.2
+    }
+
+    /// The instance extensions passed to `load()`
+    pub fn enabled_extension_names(&self) -> &[String]
+    {
+        &self.3
+    }
+
+    pub fn is_extension_enabled(&self, extension_name: &str) -> bool
+    {
+        self.3.iter().any(|name| name == extension_name)
+    }
+
+    /// Fails with `Error::ExtensionNotEnabled` unless the functions of the
+    /// instance extension were loaded.  Checked by each method which calls
+    /// an instance extension function.
+    pub fn require_extension(&self, extension_name: &str) -> Result<(), Error>
+    {
+        if self.is_extension_enabled(extension_name) {
+            Ok(())
+        } else {
+            Err(Error::ExtensionNotEnabled(extension_name.to_owned()))
+        }
+    }
+
+    /// The highest Vulkan version supported by the loader, for instance
+    /// creation.  Loaders which predate vkEnumerateInstanceVersion only
+    /// support Vulkan 1.0.
+    pub fn enumerate_instance_version(&self) -> Result<Version, Error>
+    {
+        type PfnEnumerateInstanceVersion = unsafe extern "system" fn(*mut u32) -> VkResult;
+
+        let enumerate_instance_version: Option<PfnEnumerateInstanceVersion> = unsafe {
+            mem::transmute((self.2)(ptr::null_mut(),
+                                    b"vkEnumerateInstanceVersion\0".as_ptr() as *const _))
+        };
+        let enumerate_instance_version = match enumerate_instance_version {
+            Some(f) => f,
+            None => return Ok(Version(1, 0, 0)),
+        };
+        let mut api_version: u32 = 0;
+        let result = unsafe { enumerate_instance_version(&mut api_version) };
+        if result != VK_SUCCESS {
+            return Err(From::from(VulkanError::new(
+                result, "vkEnumerateInstanceVersion",
+                Some("InstanceLoader::enumerate_instance_version"))));
+        }
+        Ok(Version::from_vk(api_version))
+    }
+
+    /// Load the instance level function pointers.  Instance extension
+    /// functions are only loaded for the extensions listed in
+    /// `enabled_extension_names`, which should be those that the instance was
+    /// actually created with.  Device extension functions are loaded whenever
+    /// they are compiled in, as they are enabled per-device.
+    #[allow(unused_variables)]
+    pub fn load(&mut self, instance: VkInstance, enabled_extension_names: &[String])
+                -> Result<(), Error>
+    {
+        let enabled = |extension_name: &str| {
+            enabled_extension_names.iter().any(|name| name == extension_name)
+        };
+
         let loader = match Arc::get_mut(&mut self.0) {
             Some(l) => l,
             None => return Err(Error::General(
                 "Loader was cloned prior to running load()".to_owned()))
         };
+        self.3 = Arc::new(enabled_extension_names.to_vec());
 
         unsafe { loader.load_core(instance); }
 
         #[cfg(feature = "khr_surface")]
-        unsafe { loader.load_khr_surface(instance); }
+        if enabled(VK_KHR_SURFACE_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_surface(instance); }
+        }
         #[cfg(feature = "khr_display")]
-        unsafe { loader.load_khr_display(instance); }
+        if enabled(VK_KHR_DISPLAY_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_display(instance); }
+        }
         #[cfg(feature = "khr_display_swapchain")]
         unsafe { loader.load_khr_display_swapchain(instance); }
         #[cfg(feature = "khr_xlib_surface")]
-        unsafe { loader.load_khr_xlib_surface(instance); }
+        if enabled(VK_KHR_XLIB_SURFACE_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_xlib_surface(instance); }
+        }
         #[cfg(feature = "khr_xcb_surface")]
-        unsafe { loader.load_khr_xcb_surface(instance); }
+        if enabled(VK_KHR_XCB_SURFACE_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_xcb_surface(instance); }
+        }
         #[cfg(feature = "khr_wayland_surface")]
-        unsafe { loader.load_khr_wayland_surface(instance); }
+        if enabled(VK_KHR_WAYLAND_SURFACE_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_wayland_surface(instance); }
+        }
         #[cfg(feature = "khr_mir_surface")]
-        unsafe { loader.load_khr_mir_surface(instance); }
+        if enabled(VK_KHR_MIR_SURFACE_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_mir_surface(instance); }
+        }
         #[cfg(feature = "khr_android_surface")]
-        unsafe { loader.load_khr_android_surface(instance); }
+        if enabled(VK_KHR_ANDROID_SURFACE_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_android_surface(instance); }
+        }
         #[cfg(feature = "khr_win32_surface")]
-        unsafe { loader.load_khr_win32_surface(instance); }
+        if enabled(VK_KHR_WIN32_SURFACE_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_win32_surface(instance); }
+        }
         #[cfg(feature = "ext_debug_report")]
-        unsafe { loader.load_ext_debug_report(instance); }
+        if enabled(VK_EXT_DEBUG_REPORT_EXTENSION_NAME_STR) {
+            unsafe { loader.load_ext_debug_report(instance); }
+        }
         #[cfg(feature = "ext_debug_marker")]
         unsafe { loader.load_ext_debug_marker(instance); }
         #[cfg(feature = "amd_draw_indirect_count")]
         unsafe { loader.load_amd_draw_indirect_count(instance); }
         #[cfg(feature = "nv_external_memory_capabilities")]
-        unsafe { loader.load_nv_external_memory_capabilities(instance); }
+        if enabled(VK_NV_EXTERNAL_MEMORY_CAPABILITIES_EXTENSION_NAME_STR) {
+            unsafe { loader.load_nv_external_memory_capabilities(instance); }
+        }
         #[cfg(feature = "nv_external_memory_win32")]
         unsafe { loader.load_nv_external_memory_win32(instance); }
         #[cfg(feature = "nvx_device_generated_commands")]
         unsafe { loader.load_nvx_device_generated_commands(instance); }
         #[cfg(feature = "khr_get_physical_device_properties2")]
-        unsafe { loader.load_khr_get_physical_device_properties2(instance); }
+        if enabled(VK_KHR_GET_PHYSICAL_DEVICE_PROPERTIES2_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khr_get_physical_device_properties2(instance); }
+        }
         #[cfg(feature = "khr_maintenance1")]
         unsafe { loader.load_khr_maintenance1(instance); }
         #[cfg(feature = "nn_vi_surface")]
-        unsafe { loader.load_nn_vi_surface(instance); }
+        if enabled(VK_NN_VI_SURFACE_EXTENSION_NAME_STR) {
+            unsafe { loader.load_nn_vi_surface(instance); }
+        }
         #[cfg(feature = "ext_direct_mode_display")]
-        unsafe { loader.load_ext_direct_mode_display(instance); }
+        if enabled(VK_EXT_DIRECT_MODE_DISPLAY_EXTENSION_NAME_STR) {
+            unsafe { loader.load_ext_direct_mode_display(instance); }
+        }
         #[cfg(feature = "ext_acquire_xlib_display")]
-        unsafe { loader.load_ext_acquire_xlib_display(instance); }
+        if enabled(VK_EXT_ACQUIRE_XLIB_DISPLAY_EXTENSION_NAME_STR) {
+            unsafe { loader.load_ext_acquire_xlib_display(instance); }
+        }
         #[cfg(feature = "ext_display_surface_counter")]
-        unsafe { loader.load_ext_display_surface_counter(instance); }
+        if enabled(VK_EXT_DISPLAY_SURFACE_COUNTER_EXTENSION_NAME_STR) {
+            unsafe { loader.load_ext_display_surface_counter(instance); }
+        }
         #[cfg(feature = "ext_display_control")]
         unsafe { loader.load_ext_display_control(instance); }
         #[cfg(feature = "khr_push_descriptor")]
@@ -79,15 +234,21 @@ impl InstanceLoader {
         #[cfg(feature = "khx_device_group")]
         unsafe { loader.load_khx_device_group(instance); }
         #[cfg(feature = "khx_device_group_creation")]
-        unsafe { loader.load_khx_device_group_creation(instance); }
+        if enabled(VK_KHX_DEVICE_GROUP_CREATION_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khx_device_group_creation(instance); }
+        }
         #[cfg(feature = "khx_external_memory_capabilities")]
-        unsafe { loader.load_khx_external_memory_capabilities(instance); }
+        if enabled(VK_KHX_EXTERNAL_MEMORY_CAPABILITIES_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khx_external_memory_capabilities(instance); }
+        }
         #[cfg(feature = "khx_external_memory_win32")]
         unsafe { loader.load_khx_external_memory_win32(instance); }
         #[cfg(feature = "khx_external_memory_fd")]
         unsafe { loader.load_khx_external_memory_fd(instance); }
         #[cfg(feature = "khx_external_semaphore_capabilities")]
-        unsafe { loader.load_khx_external_semaphore_capabilities(instance); }
+        if enabled(VK_KHX_EXTERNAL_SEMAPHORE_CAPABILITIES_EXTENSION_NAME_STR) {
+            unsafe { loader.load_khx_external_semaphore_capabilities(instance); }
+        }
         #[cfg(feature = "khx_external_semaphore_win32")]
         unsafe { loader.load_khx_external_semaphore_win32(instance); }
         #[cfg(feature = "khx_external_semaphore_fd")]
@@ -97,9 +258,13 @@ impl InstanceLoader {
         #[cfg(feature = "ext_discard_rectangles")]
         unsafe { loader.load_ext_discard_rectangles(instance); }
         #[cfg(feature = "mvk_ios_surface")]
-        unsafe { loader.load_mvk_ios_surface(instance); }
+        if enabled(VK_MVK_IOS_SURFACE_EXTENSION_NAME_STR) {
+            unsafe { loader.load_mvk_ios_surface(instance); }
+        }
         #[cfg(feature = "mvk_macos_surface")]
-        unsafe { loader.load_mvk_macos_surface(instance); }
+        if enabled(VK_MVK_MACOS_SURFACE_EXTENSION_NAME_STR) {
+            unsafe { loader.load_mvk_macos_surface(instance); }
+        }
         #[cfg(feature = "google_display_timing")]
         unsafe { loader.load_google_display_timing(instance); }
         #[cfg(feature = "ext_hdr_metadata")]
@@ -1,13 +1,25 @@
 
+mod library;
+
 mod loader;
 pub use self::loader::InstanceLoader;
 
+#[cfg(feature = "ext_debug_report")]
 mod debug;
-pub use self::debug::DebugCallback;
+#[cfg(feature = "ext_debug_report")]
+pub use self::debug::{DebugCallback, DebugCallbackFn, DebugMessage, DebugReportFlags,
+                      DebugReportObjectType};
+#[cfg(feature = "ext_debug_report")]
+pub use self::debug::{DEBUG_REPORT_INFORMATION_BIT, DEBUG_REPORT_WARNING_BIT,
+                      DEBUG_REPORT_PERFORMANCE_WARNING_BIT, DEBUG_REPORT_ERROR_BIT,
+                      DEBUG_REPORT_DEBUG_BIT};
 
 #[cfg(feature = "khr_surface")]
 pub mod surface;
 
+mod validation_check;
+pub use self::validation_check::ValidationCheck;
+
 pub mod physical_device;
 pub mod device;
 
@@ -15,41 +27,91 @@ use libc::c_char;
 use std::ffi::CString;
 use std::ptr;
 use std::mem;
+use std::sync::Arc;
 use vks::*;
 use self::physical_device::PhysicalDevice;
 
-use {Error, Version};
+use allocator::vk_allocator;
+use {Error, Version, HEADER_VERSION, Allocator, enumerate_instance_extension_properties,
+     enumerate_instance_layer_properties};
+use error::{MissingName, NameKind};
 
 pub struct ApplicationInfo {
     pub application_name: String,
     pub application_version: Version,
     pub engine_name: String,
     pub engine_version: Version,
+    /// The highest version of Vulkan the application is designed to use
+    pub api_version: Version,
+}
+
+impl Default for ApplicationInfo {
+    fn default() -> ApplicationInfo {
+        ApplicationInfo {
+            application_name: String::new(),
+            application_version: Version(0, 0, 0),
+            engine_name: String::new(),
+            engine_version: Version(0, 0, 0),
+            api_version: HEADER_VERSION,
+        }
+    }
 }
 
 pub struct InstanceCreateInfo {
     pub application_info: ApplicationInfo,
     pub enabled_layer_count: u32,
     pub enabled_layer_names: Vec<String>,
+    /// Instance extensions which must be enabled.  Instance creation fails if
+    /// any of these are not available.
+    pub enabled_extension_names: Vec<String>,
+    /// Instance extensions which are enabled only if they are available.
+    pub optional_extension_names: Vec<String>,
+    /// Host memory allocator for the instance and the objects created from
+    /// it, other than devices which take their own.  `None` lets the
+    /// implementation allocate.
+    pub allocator: Option<Allocator>,
+    /// Validation checks to disable.  If any are given, VK_EXT_validation_flags
+    /// is enabled when available (it is usually offered by the validation
+    /// layers), and the checks are passed on only if it was.  Without the
+    /// `ext_validation_flags` feature these are ignored.
+    pub disabled_validation_checks: Vec<ValidationCheck>,
 }
 
 
 /// See vulkan specification, section 3.2 Instances
-pub struct Instance(VkInstance);
+///
+/// Clones share the same underlying instance, which is destroyed when the
+/// last clone is dropped.  Objects created from the instance hold a clone, so
+/// the instance always outlives them.
+#[derive(Clone)]
+pub struct Instance {
+    inner: Arc<InstanceInner>,
+}
+
+struct InstanceInner {
+    instance: VkInstance,
+    loader: InstanceLoader,
+    allocator: Option<Allocator>,
+    api_version: Version,
+    enabled_extension_names: Vec<String>,
+}
+
+// Vulkan instances may be used from any thread
+unsafe impl Send for InstanceInner {}
+unsafe impl Sync for InstanceInner {}
 
 impl Instance {
-    #[cfg(not(feature = "ext_validation_flags"))]
     #[allow(unused_variables)]
     pub fn new(mut loader: InstanceLoader, create_info: InstanceCreateInfo)
                -> Result<(Instance, InstanceLoader), Error>
     {
-        let extension_names = get_extension_names();
+        let enabled_extension_names = select_extension_names(&loader, &create_info)?;
 
         // Setup strings for passing into vkCreateInstance down below.  These must
         // not go out of scope until after that function is called.
         let (extension_names_owned, extension_names) = {
             let mut extension_names_owned: Vec<CString> = Vec::new();
-            for ref name in extension_names {
+            for ref name in &enabled_extension_names {
                 extension_names_owned.push( CString::new(name.as_bytes())? );
             }
             let extension_names: Vec<*const c_char> = extension_names_owned.iter()
@@ -58,6 +120,9 @@ impl Instance {
             (extension_names_owned, extension_names)
         };
 
+        let allocator = create_info.allocator.clone();
+        let api_version = create_info.application_info.api_version;
+
         let app_name = CString::new(create_info.application_info.application_name.as_bytes())?;
         let engine_name = CString::new(create_info.application_info.engine_name.as_bytes())?;
 
@@ -82,14 +147,43 @@ impl Instance {
                 pEngineName: engine_name.as_ptr(),
                 engineVersion: create_info.application_info
                     .engine_version.to_vk(),
-                apiVersion: vk_make_version!(1, 0, 3),
+                apiVersion: api_version.to_vk(),
             }
         };
 
+        // Chain the disabled validation checks only if the extension made it
+        // into the enabled set.
+        #[cfg(feature = "ext_validation_flags")]
+        let disabled_validation_checks: Vec<VkValidationCheckEXT> =
+            if enabled_extension_names.iter()
+                .any(|name| name == VK_EXT_VALIDATION_FLAGS_EXTENSION_NAME_STR)
+            {
+                create_info.disabled_validation_checks.iter()
+                    .map(|&vc| From::from(vc))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+        #[cfg(feature = "ext_validation_flags")]
+        let vflags = VkValidationFlagsEXT {
+            sType: VK_STRUCTURE_TYPE_VALIDATION_FLAGS_EXT,
+            pNext: ptr::null(),
+            disabledValidationCheckCount: disabled_validation_checks.len() as u32,
+            pDisabledValidationChecks: disabled_validation_checks.as_ptr() as *mut _,
+        };
+        #[cfg(feature = "ext_validation_flags")]
+        let p_next = if disabled_validation_checks.len() > 0 {
+            &vflags as *const VkValidationFlagsEXT as *const ::libc::c_void
+        } else {
+            ptr::null()
+        };
+        #[cfg(not(feature = "ext_validation_flags"))]
+        let p_next = ptr::null();
+
         let create_info = {
             VkInstanceCreateInfo {
                 sType: VK_STRUCTURE_TYPE_INSTANCE_CREATE_INFO,
-                pNext: ptr::null(),
+                pNext: p_next,
                 flags: VK_INSTANCE_CREATE_DUMMY,
                 pApplicationInfo: &app_info,
                 enabledLayerCount: layer_names.len() as u32,
@@ -111,52 +205,105 @@ impl Instance {
             let mut instance: VkInstance = ptr::null_mut();
             vk_try!((loader.0.core_null_instance.vkCreateInstance)(
                 &create_info,
-                ptr::null(),
+                vk_allocator(&allocator),
                 &mut instance
-            ));
+            ), "vkCreateInstance", "Instance::new");
             assert!(instance != ptr::null_mut());
             instance
         };
 
         // Load instance functions
-        loader.load(instance)?;
+        if let Err(e) = loader.load(instance, &enabled_extension_names) {
+            // Nothing owns the instance yet, so destroy it here rather than leak it
+            let mut instance_loader = InstanceProcAddrLoader::from_get_instance_proc_addr(
+                loader.get_instance_proc_addr());
+            unsafe {
+                instance_loader.load_core(instance);
+                (instance_loader.core.vkDestroyInstance)(instance, vk_allocator(&allocator));
+            }
+            return Err(e);
+        }
 
-        Ok((Instance(instance), loader))
+        Ok((Instance {
+            inner: Arc::new(InstanceInner {
+                instance: instance,
+                loader: loader.clone(),
+                allocator: allocator,
+                api_version: api_version,
+                enabled_extension_names: enabled_extension_names,
+            })
+        }, loader))
     }
 }
 
-impl Drop for Instance {
+impl Drop for InstanceInner {
     fn drop(&mut self) {
         unsafe {
-            vkDestroyInstance(
-                self.0,
-                ptr::null());
+            (self.loader.0.core.vkDestroyInstance)(
+                self.instance,
+                vk_allocator(&self.allocator));
         }
     }
 }
 
 impl Instance {
+    pub fn inner(&self) -> VkInstance
+    {
+        self.inner.instance
+    }
+
+    /// The loader the instance functions were loaded with
+    pub fn loader(&self) -> &InstanceLoader
+    {
+        &self.inner.loader
+    }
+
+    /// The Vulkan version the instance was created for
+    pub fn api_version(&self) -> Version
+    {
+        self.inner.api_version
+    }
+
+    /// The host memory allocator the instance was created with
+    pub fn allocator(&self) -> Option<Allocator>
+    {
+        self.inner.allocator.clone()
+    }
+
+    /// The instance extensions that were actually enabled, which includes
+    /// every required extension and those optional extensions which were
+    /// available.
+    pub fn enabled_extension_names(&self) -> &[String]
+    {
+        &self.inner.enabled_extension_names
+    }
+
+    pub fn is_extension_enabled(&self, extension_name: &str) -> bool
+    {
+        self.inner.enabled_extension_names.iter().any(|name| name == extension_name)
+    }
+
     pub fn enumerate_physical_devices(&self, loader: &InstanceLoader)
                                       -> Result<Vec<PhysicalDevice>, Error>
     {
         // Call once to get the count
         let mut physical_device_count: u32 = unsafe { mem::uninitialized() };
         vk_try!(unsafe { (loader.0.core.vkEnumeratePhysicalDevices)(
-            self.0,
+            self.inner(),
             &mut physical_device_count,
             ptr::null_mut()
-        )});
+        )}, "vkEnumeratePhysicalDevices", "Instance::enumerate_physical_devices");
 
         // Prepare room for the output
         let capacity: usize = physical_device_count as usize;
         let mut devices: Vec<VkPhysicalDevice> = Vec::with_capacity(capacity);
 
         // Call again to get the data
-        vk_try!(unsafe { (loader.0.core.vkEnumeratePhysicalDevices)(
-            self.0,
+        vk_try_status!(unsafe { (loader.0.core.vkEnumeratePhysicalDevices)(
+            self.inner(),
             &mut physical_device_count,
             devices.as_mut_ptr()
-        )});
+        )}, "vkEnumeratePhysicalDevices", "Instance::enumerate_physical_devices");
 
         // Trust the data now in the devices vector
         let devices = unsafe {
@@ -168,161 +315,80 @@ impl Instance {
         // Translate for output
         let mut output: Vec<PhysicalDevice> = Vec::with_capacity(physical_device_count as usize);
         for device in devices {
-            output.push(PhysicalDevice::from_vk(device)?);
+            output.push(PhysicalDevice::from_vk(self, device)?);
         }
         Ok(output)
     }
 
-    pub fn get_debug_callback(&self, loader: InstanceLoader) -> Result<DebugCallback, Error>
+    /// Register `callback` to be called with each debug message matching
+    /// `flags`, for as long as the returned `DebugCallback` is alive.
+    #[cfg(feature = "ext_debug_report")]
+    pub fn get_debug_callback<F>(&self, loader: InstanceLoader, flags: DebugReportFlags,
+                                 callback: F) -> Result<DebugCallback, Error>
+        where F: Fn(&DebugMessage) -> bool + Send + Sync + 'static
     {
-        DebugCallback::new(self.0, loader)
+        DebugCallback::new(self, loader, flags, callback)
+    }
+
+    /// Collect each debug message matching `flags`, for as long as the
+    /// returned `DebugCallback` is alive.  See `DebugCallback::capture()`.
+    #[cfg(feature = "ext_debug_report")]
+    pub fn capture_debug_messages(&self, loader: InstanceLoader, flags: DebugReportFlags)
+                                  -> Result<DebugCallback, Error>
+    {
+        DebugCallback::capture(self, loader, flags)
     }
 }
 
-#[allow(unused_mut)]
-fn get_extension_names() -> Vec<&'static str>
+// Check the requested layers and extensions against those offered by the
+// implementation (and, for extensions, by the layers being enabled), returning
+// the extension names to actually enable.  Any missing layers or required
+// extensions are reported together.
+fn select_extension_names(loader: &InstanceLoader, create_info: &InstanceCreateInfo)
+                          -> Result<Vec<String>, Error>
 {
-    let mut extension_names: Vec<&'static str> = Vec::new();
-
-    #[cfg(feature = "khr_surface")]
-    extension_names.push(VK_KHR_SURFACE_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_swapchain")]
-    extension_names.push(VK_KHR_SWAPCHAIN_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_display")]
-    extension_names.push(VK_KHR_DISPLAY_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_display_swapchain")]
-    extension_names.push(VK_KHR_DISPLAY_SWAPCHAIN_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_xlib_surface")]
-    extension_names.push(VK_KHR_XLIB_SURFACE_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_xcb_surface")]
-    extension_names.push(VK_KHR_XCB_SURFACE_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_wayland_surface")]
-    extension_names.push(VK_KHR_WAYLAND_SURFACE_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_mir_surface")]
-    extension_names.push(VK_KHR_MIR_SURFACE_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_android_surface")]
-    extension_names.push(VK_KHR_ANDROID_SURFACE_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_win32_surface")]
-    extension_names.push(VK_KHR_WIN32_SURFACE_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_sampler_mirror_clamp_to_edge")]
-    extension_names.push(VK_KHR_SAMPLER_MIRROR_CLAMP_TO_EDGE_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_get_physical_device_properties2")]
-    extension_names.push(VK_KHR_GET_PHYSICAL_DEVICE_PROPERTIES2_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_shader_draw_parameters")]
-    extension_names.push(VK_KHR_SHADER_DRAW_PARAMETERS_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_maintenance1")]
-    extension_names.push(VK_KHR_MAINTENANCE1_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_push_descriptor")]
-    extension_names.push(VK_KHR_PUSH_DESCRIPTOR_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_incremental_present")]
-    extension_names.push(VK_KHR_INCREMENTAL_PRESENT_EXTENSION_NAME_STR);
-    #[cfg(feature = "khr_descriptor_update_template")]
-    extension_names.push(VK_KHR_DESCRIPTOR_UPDATE_TEMPLATE_EXTENSION_NAME_STR);
-    #[cfg(feature = "ext_debug_report")]
-    extension_names.push(VK_EXT_DEBUG_REPORT_EXTENSION_NAME_STR);
-    #[cfg(feature = "nv_glsl_shader")]
-    extension_names.push(VK_NV_GLSL_SHADER_EXTENSION_NAME_STR);
-    #[cfg(feature = "img_filter_cubic")]
-    extension_names.push(VK_IMG_FILTER_CUBIC_EXTENSION_NAME_STR);
-    #[cfg(feature = "amd_rasterization_order")]
-    extension_names.push(VK_AMD_RASTERIZATION_ORDER_EXTENSION_NAME_STR);
-    #[cfg(feature = "amd_shader_trinary_minmax")]
-    extension_names.push(VK_AMD_SHADER_TRINARY_MINMAX_EXTENSION_NAME_STR);
-    #[cfg(feature = "amd_shader_explicit_vertex_parameter")]
-    extension_names.push(VK_AMD_SHADER_EXPLICIT_VERTEX_PARAMETER_EXTENSION_NAME_STR);
-    #[cfg(feature = "ext_debug_marker")]
-    extension_names.push(VK_EXT_DEBUG_MARKER_EXTENSION_NAME_STR);
-    #[cfg(feature = "amd_gcn_shader")]
-    extension_names.push(VK_AMD_GCN_SHADER_EXTENSION_NAME_STR);
-    #[cfg(feature = "nv_dedicated_allocation")]
-    extension_names.push(VK_NV_DEDICATED_ALLOCATION_EXTENSION_NAME_STR);
-    #[cfg(feature = "amd_draw_indirect_count")]
-    extension_names.push(VK_AMD_DRAW_INDIRECT_COUNT_EXTENSION_NAME_STR);
-    #[cfg(feature = "amd_negative_viewport_height")]
-    extension_names.push(VK_AMD_NEGATIVE_VIEWPORT_HEIGHT_EXTENSION_NAME_STR);
-    #[cfg(feature = "amd_gpu_shader_half_float")]
-    extension_names.push(VK_AMD_GPU_SHADER_HALF_FLOAT_EXTENSION_NAME_STR);
-    #[cfg(feature = "amd_shader_ballot")]
-    extension_names.push(VK_AMD_SHADER_BALLOT_EXTENSION_NAME_STR);
-    #[cfg(feature = "khx_multiview")]
-    extension_names.push(VK_KHX_MULTIVIEW_EXTENSION_NAME_STR);
-    #[cfg(feature = "img_format_pvrtc")]
-    extension_names.push(VK_IMG_FORMAT_PVRTC_EXTENSION_NAME_STR);
-    #[cfg(feature = "nv_external_memory_capabilities")]
-    extension_names.push(VK_NV_EXTERNAL_MEMORY_CAPABILITIES_EXTENSION_NAME_STR);
-    #[cfg(feature = "nv_external_memory")]
-    extension_names.push(VK_NV_EXTERNAL_MEMORY_EXTENSION_NAME_STR);
-    #[cfg(feature = "nv_external_memory_win32")]
-    extension_names.push(VK_NV_EXTERNAL_MEMORY_WIN32_EXTENSION_NAME_STR);
-    #[cfg(feature = "nv_win32_keyed_mutex")]
-    extension_names.push(VK_NV_WIN32_KEYED_MUTEX_EXTENSION_NAME_STR);
-    #[cfg(feature = "khx_device_group")]
-    extension_names.push(VK_KHX_DEVICE_GROUP_EXTENSION_NAME_STR);
+    let available_layers: Vec<String> = enumerate_instance_layer_properties(loader)?
+        .into_iter()
+        .map(|lp| lp.layer_name)
+        .collect();
+    let mut missing = MissingName::find(NameKind::Layer,
+                                        &create_info.enabled_layer_names,
+                                        &available_layers);
+
+    let mut available: Vec<String> = enumerate_instance_extension_properties(loader, None)?
+        .into_iter()
+        .map(|ep| ep.extension_name)
+        .collect();
+    for layer_name in &create_info.enabled_layer_names {
+        if available_layers.contains(layer_name) {
+            available.extend(enumerate_instance_extension_properties(loader, Some(layer_name))?
+                             .into_iter()
+                             .map(|ep| ep.extension_name));
+        }
+    }
+    missing.extend(MissingName::find(NameKind::InstanceExtension,
+                                     &create_info.enabled_extension_names,
+                                     &available));
+    if !missing.is_empty() {
+        return Err(Error::NotPresent(missing));
+    }
+
+    #[allow(unused_mut)]
+    let mut optional_extension_names = create_info.optional_extension_names.clone();
     #[cfg(feature = "ext_validation_flags")]
-    extension_names.push(VK_EXT_VALIDATION_FLAGS_EXTENSION_NAME_STR);
-    #[cfg(feature = "nn_vi_surface")]
-    extension_names.push(VK_NN_VI_SURFACE_EXTENSION_NAME_STR);
-    #[cfg(feature = "ext_shader_subgroup_ballot")]
-    extension_names.push(VK_EXT_SHADER_SUBGROUP_BALLOT_EXTENSION_NAME_STR);
-    #[cfg(feature = "ext_shader_subgroup_vote")]
-    extension_names.push(VK_EXT_SHADER_SUBGROUP_VOTE_EXTENSION_NAME_STR);
-    #[cfg(feature = "khx_device_group_creation")]
-    extension_names.push(VK_KHX_DEVICE_GROUP_CREATION_EXTENSION_NAME_STR);
-    #[cfg(feature = "khx_external_memory_capabilities")]
-    extension_names.push(VK_KHX_EXTERNAL_MEMORY_CAPABILITIES_EXTENSION_NAME_STR);
-    #[cfg(feature = "khx_external_memory")]
-    extension_names.push(VK_KHX_EXTERNAL_MEMORY_EXTENSION_NAME_STR);
-    #[cfg(feature = "khx_external_memory_win32")]
-    extension_names.push(VK_KHX_EXTERNAL_MEMORY_WIN32_EXTENSION_NAME_STR);
-    #[cfg(feature = "khx_external_memory_fd")]
-    extension_names.push(VK_KHX_EXTERNAL_MEMORY_FD_EXTENSION_NAME_STR);
-    #[cfg(feature = "khx_win32_keyed_mutex")]
-    extension_names.push(VK_KHX_WIN32_KEYED_MUTEX_EXTENSION_NAME_STR);
-    #[cfg(feature = "khx_external_semaphore_capabilities")]
-    extension_names.push(VK_KHX_EXTERNAL_SEMAPHORE_CAPABILITIES_EXTENSION_NAME_STR);
-    #[cfg(feature = "khx_external_semaphore")]
-    extension_names.push(VK_KHX_EXTERNAL_SEMAPHORE_EXTENSION_NAME_STR);
-    #[cfg(feature = "khx_external_semaphore_win32")]
-    extension_names.push(VK_KHX_EXTERNAL_SEMAPHORE_WIN32_EXTENSION_NAME_STR);
-    #[cfg(feature = "khx_external_semaphore_fd")]
-    extension_names.push(VK_KHX_EXTERNAL_SEMAPHORE_FD_EXTENSION_NAME_STR);
-    #[cfg(feature = "nvx_device_generated_commands")]
-    extension_names.push(VK_NVX_DEVICE_GENERATED_COMMANDS_EXTENSION_NAME_STR);
-    #[cfg(feature = "nv_clip_space_w_scaling")]
-    extension_names.push(VK_NV_CLIP_SPACE_W_SCALING_EXTENSION_NAME_STR);
-    #[cfg(feature = "ext_direct_mode_display")]
-    extension_names.push(VK_EXT_DIRECT_MODE_DISPLAY_EXTENSION_NAME_STR);
-    #[cfg(feature = "ext_acquire_xlib_display")]
-    extension_names.push(VK_EXT_ACQUIRE_XLIB_DISPLAY_EXTENSION_NAME_STR);
-    #[cfg(feature = "ext_display_surface_counter")]
-    extension_names.push(VK_EXT_DISPLAY_SURFACE_COUNTER_EXTENSION_NAME_STR);
-    #[cfg(feature = "ext_display_control")]
-    extension_names.push(VK_EXT_DISPLAY_CONTROL_EXTENSION_NAME_STR);
-    #[cfg(feature = "google_display_timing")]
-    extension_names.push(VK_GOOGLE_DISPLAY_TIMING_EXTENSION_NAME_STR);
-    #[cfg(feature = "nv_sample_mask_override_coverage")]
-    extension_names.push(VK_NV_SAMPLE_MASK_OVERRIDE_COVERAGE_EXTENSION_NAME_STR);
-    #[cfg(feature = "nv_geometry_shader_passthrough")]
-    extension_names.push(VK_NV_GEOMETRY_SHADER_PASSTHROUGH_EXTENSION_NAME_STR);
-    #[cfg(feature = "nv_viewport_array2")]
-    extension_names.push(VK_NV_VIEWPORT_ARRAY2_EXTENSION_NAME_STR);
-    #[cfg(feature = "nvx_multiview_per_view_attributes")]
-    extension_names.push(VK_NVX_MULTIVIEW_PER_VIEW_ATTRIBUTES_EXTENSION_NAME_STR);
-    #[cfg(feature = "nv_viewport_swizzle")]
-    extension_names.push(VK_NV_VIEWPORT_SWIZZLE_EXTENSION_NAME_STR);
-    #[cfg(feature = "ext_discard_rectangles")]
-    extension_names.push(VK_EXT_DISCARD_RECTANGLES_EXTENSION_NAME_STR);
-    #[cfg(feature = "ext_swapchain_colorspace")]
-    extension_names.push(VK_EXT_SWAPCHAIN_COLORSPACE_EXTENSION_NAME_STR);
-    #[cfg(feature = "ext_hdr_metadata")]
-    extension_names.push(VK_EXT_HDR_METADATA_EXTENSION_NAME_STR);
-    #[cfg(feature = "mvk_ios_surface")]
-    extension_names.push(VK_MVK_IOS_SURFACE_EXTENSION_NAME_STR);
-    #[cfg(feature = "mvk_macos_surface")]
-    extension_names.push(VK_MVK_MACOS_SURFACE_EXTENSION_NAME_STR);
-
-    extension_names
-}
+    {
+        if !create_info.disabled_validation_checks.is_empty() {
+            optional_extension_names.push(VK_EXT_VALIDATION_FLAGS_EXTENSION_NAME_STR.to_owned());
+        }
+    }
 
-#[cfg(feature = "ext_validation_flags")]
-include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/instance/ext_validation_flags_1.rs"));
+    let mut extension_names: Vec<String> = Vec::new();
+    for name in create_info.enabled_extension_names.iter()
+        .chain(optional_extension_names.iter())
+    {
+        if available.contains(name) && !extension_names.contains(name) {
+            extension_names.push(name.clone());
+        }
+    }
+    Ok(extension_names)
+}
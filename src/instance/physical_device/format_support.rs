@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use {Error, InstanceLoader};
+use format::*;
+use image::ImageTiling;
+use super::PhysicalDevice;
+
+/// Depth formats in order of preference: depth-only formats first, then
+/// combined depth/stencil formats, each from most to least precise
+pub const DEPTH_FORMATS: &'static [Format] = &[
+    Format::D32Sfloat,
+    Format::X8D24UnormPack32,
+    Format::D16Unorm,
+    Format::D32SfloatS8Uint,
+    Format::D24UnormS8Uint,
+    Format::D16UnormS8Uint,
+];
+
+/// Combined depth/stencil formats in order of preference
+pub const DEPTH_STENCIL_FORMATS: &'static [Format] = &[
+    Format::D32SfloatS8Uint,
+    Format::D24UnormS8Uint,
+    Format::D16UnormS8Uint,
+];
+
+/// Floating point colour formats for HDR rendering in order of preference
+pub const HDR_FORMATS: &'static [Format] = &[
+    Format::R16g16b16a16Sfloat,
+    Format::B10g11r11UfloatPack32,
+    Format::R32g32b32a32Sfloat,
+];
+
+// The first candidate whose properties have all of `features` with `tiling`
+fn find_format<F>(candidates: &[Format], tiling: ImageTiling, features: FormatFeatureFlags,
+                  mut get_properties: F)
+                  -> Result<Option<Format>, Error>
+    where F: FnMut(Format) -> Result<FormatProperties, Error>
+{
+    for &format in candidates {
+        if get_properties(format)?.tiling_features(tiling).contains(features) {
+            return Ok(Some(format));
+        }
+    }
+    Ok(None)
+}
+
+fn hdr_features() -> FormatFeatureFlags
+{
+    FORMAT_FEATURE_COLOR_ATTACHMENT_BIT | FORMAT_FEATURE_SAMPLED_IMAGE_BIT
+}
+
+impl PhysicalDevice {
+    /// The first of `candidates` supporting all of `features` with `tiling`,
+    /// or None if none of them do
+    pub fn find_supported_format(&self, loader: &InstanceLoader, candidates: &[Format],
+                                 tiling: ImageTiling, features: FormatFeatureFlags)
+                                 -> Result<Option<Format>, Error>
+    {
+        find_format(candidates, tiling, features,
+                    |format| self.get_format_properties(loader, format))
+    }
+
+    /// The best of `DEPTH_FORMATS` usable as an optimally tiled depth
+    /// attachment
+    pub fn find_depth_format(&self, loader: &InstanceLoader) -> Result<Option<Format>, Error>
+    {
+        self.find_supported_format(loader, DEPTH_FORMATS, ImageTiling::Optimal,
+                                   FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT)
+    }
+
+    /// The best of `DEPTH_STENCIL_FORMATS` usable as an optimally tiled
+    /// depth/stencil attachment
+    pub fn find_depth_stencil_format(&self, loader: &InstanceLoader)
+                                     -> Result<Option<Format>, Error>
+    {
+        self.find_supported_format(loader, DEPTH_STENCIL_FORMATS, ImageTiling::Optimal,
+                                   FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT)
+    }
+
+    /// The best of `HDR_FORMATS` which can be both rendered to and sampled
+    /// with optimal tiling
+    pub fn find_hdr_format(&self, loader: &InstanceLoader) -> Result<Option<Format>, Error>
+    {
+        self.find_supported_format(loader, HDR_FORMATS, ImageTiling::Optimal, hdr_features())
+    }
+
+    /// Query the properties of every format once, for answering later format
+    /// questions without calling into the driver
+    pub fn get_format_table(&self, loader: &InstanceLoader) -> Result<FormatTable, Error>
+    {
+        let mut formats = HashMap::new();
+        for format in Format::all() {
+            formats.insert(format, self.get_format_properties(loader, format)?);
+        }
+        Ok(FormatTable { formats: formats })
+    }
+}
+
+/// The format properties of every format on one physical device.  See
+/// `PhysicalDevice::get_format_table()`.
+#[derive(Debug, Clone)]
+pub struct FormatTable {
+    formats: HashMap<Format, FormatProperties>,
+}
+
+impl FormatTable {
+    /// The properties of `format`; no features for `Undefined`
+    pub fn get(&self, format: Format) -> FormatProperties
+    {
+        self.formats.get(&format).cloned().unwrap_or_default()
+    }
+
+    /// Every format supporting all of `features` with `tiling`, in order of
+    /// value
+    pub fn formats_supporting(&self, tiling: ImageTiling, features: FormatFeatureFlags)
+                              -> Vec<Format>
+    {
+        Format::all().into_iter()
+            .filter(|&format| self.get(format).tiling_features(tiling).contains(features))
+            .collect()
+    }
+
+    /// Every format supporting at least one feature, in order of value
+    pub fn supported_formats(&self) -> Vec<(Format, FormatProperties)>
+    {
+        Format::all().into_iter()
+            .map(|format| (format, self.get(format)))
+            .filter(|&(_, ref properties)| {
+                !properties.linear_tiling_features.is_empty()
+                    || !properties.optimal_tiling_features.is_empty()
+                    || !properties.buffer_features.is_empty()
+            })
+            .collect()
+    }
+
+    /// As `PhysicalDevice::find_supported_format()`
+    pub fn find_supported_format(&self, candidates: &[Format], tiling: ImageTiling,
+                                 features: FormatFeatureFlags)
+                                 -> Option<Format>
+    {
+        find_format(candidates, tiling, features, |format| Ok(self.get(format)))
+            .unwrap_or(None)
+    }
+
+    /// As `PhysicalDevice::find_depth_format()`
+    pub fn find_depth_format(&self) -> Option<Format>
+    {
+        self.find_supported_format(DEPTH_FORMATS, ImageTiling::Optimal,
+                                   FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT)
+    }
+
+    /// As `PhysicalDevice::find_depth_stencil_format()`
+    pub fn find_depth_stencil_format(&self) -> Option<Format>
+    {
+        self.find_supported_format(DEPTH_STENCIL_FORMATS, ImageTiling::Optimal,
+                                   FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT)
+    }
+
+    /// As `PhysicalDevice::find_hdr_format()`
+    pub fn find_hdr_format(&self) -> Option<Format>
+    {
+        self.find_supported_format(HDR_FORMATS, ImageTiling::Optimal, hdr_features())
+    }
+}
@@ -1,13 +1,20 @@
 
-use std::ptr;
+use self::output_chain as chain;
 
+/// The queries of VK_KHR_get_physical_device_properties2.  Each takes an
+/// `OutputChain` type naming the extension structures to fill in as well; use
+/// `()` for none.
 impl PhysicalDevice {
-    pub fn get_properties(&self, loader: &InstanceLoader) ->
-        Result<PhysicalDeviceProperties, Error>
+    /// See vulkan specification, section 4.1 Physical Devices
+    pub fn get_properties2<C>(&self, loader: &InstanceLoader)
+                              -> Result<(PhysicalDeviceProperties, C), Error>
+        where C: OutputChain<chain::Properties2>
     {
+        loader.require_extension(VK_KHR_GET_PHYSICAL_DEVICE_PROPERTIES2_EXTENSION_NAME_STR)?;
+        let mut raw = C::raw();
         let mut properties = VkPhysicalDeviceProperties2KHR {
             sType: VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_PROPERTIES_2_KHR,
-            pNext: ptr::null_mut(),
+            pNext: C::link(&mut raw, ptr::null_mut()),
             properties: Default::default(),
         };
         unsafe {
@@ -17,22 +24,222 @@ impl PhysicalDevice {
             );
         }
 
-        Ok(PhysicalDeviceProperties {
-            api_version: Version::from_vk(properties.properties.apiVersion),
-            driver_version: properties.properties.driverVersion,
-            vendor_id: properties.properties.vendorID,
-            device_id: properties.properties.deviceID,
-            device_type: unsafe { mem::transmute(properties.properties.deviceType) },
-            device_name:  unsafe {
-                str::from_utf8(
-                    CStr::from_ptr(
-                        properties.properties.deviceName.as_ptr())
-                        .to_bytes())?
-                .to_owned()
-            },
-            pipeline_cache_uuid: properties.properties.pipelineCacheUUID.clone(),
-            limits: From::from(properties.properties.limits),
-            sparse_properties: From::from(properties.properties.sparseProperties),
-        })
+        Ok((From::from(properties.properties), C::from_raw(&raw)))
+    }
+
+    /// See vulkan specification, section 31.1 Features
+    pub fn get_features2<C>(&self, loader: &InstanceLoader)
+                            -> Result<(PhysicalDeviceFeatures, C), Error>
+        where C: OutputChain<chain::Features2>
+    {
+        loader.require_extension(VK_KHR_GET_PHYSICAL_DEVICE_PROPERTIES2_EXTENSION_NAME_STR)?;
+        let mut raw = C::raw();
+        let mut features = VkPhysicalDeviceFeatures2KHR {
+            sType: VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_FEATURES_2_KHR,
+            pNext: C::link(&mut raw, ptr::null_mut()),
+            features: Default::default(),
+        };
+        unsafe {
+            (loader.0.khr_get_physical_device_properties2.vkGetPhysicalDeviceFeatures2KHR)(
+                self.device,
+                &mut features
+            );
+        }
+
+        Ok((From::from(features.features), C::from_raw(&raw)))
+    }
+
+    /// See vulkan specification, section 31.3 Formats
+    pub fn get_format_properties2<C>(&self, loader: &InstanceLoader, format: Format)
+                                     -> Result<(FormatProperties, C), Error>
+        where C: OutputChain<chain::FormatProperties2>
+    {
+        loader.require_extension(VK_KHR_GET_PHYSICAL_DEVICE_PROPERTIES2_EXTENSION_NAME_STR)?;
+        let mut raw = C::raw();
+        let mut format_properties = VkFormatProperties2KHR {
+            sType: VK_STRUCTURE_TYPE_FORMAT_PROPERTIES_2_KHR,
+            pNext: C::link(&mut raw, ptr::null_mut()),
+            formatProperties: Default::default(),
+        };
+        unsafe {
+            (loader.0.khr_get_physical_device_properties2.vkGetPhysicalDeviceFormatProperties2KHR)(
+                self.device,
+                format.into(),
+                &mut format_properties
+            );
+        }
+
+        Ok((From::from(format_properties.formatProperties), C::from_raw(&raw)))
+    }
+
+    /// See vulkan specification, section 31.4 Additional Image Capabilities.
+    /// Returns None if the combination of parameters is not supported.
+    pub fn get_image_format_properties2<C>(&self, loader: &InstanceLoader, format: Format,
+                                           image_type: ImageType, tiling: ImageTiling,
+                                           usage: ImageUsageFlags, flags: ImageCreateFlags)
+                                           -> Result<Option<(ImageFormatProperties, C)>, Error>
+        where C: OutputChain<chain::ImageFormatProperties2>
+    {
+        loader.require_extension(VK_KHR_GET_PHYSICAL_DEVICE_PROPERTIES2_EXTENSION_NAME_STR)?;
+        let format_info = VkPhysicalDeviceImageFormatInfo2KHR {
+            sType: VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_IMAGE_FORMAT_INFO_2_KHR,
+            pNext: ptr::null(),
+            format: format.into(),
+            type_: image_type.into(),
+            tiling: tiling.into(),
+            usage: usage.into(),
+            flags: flags.into(),
+        };
+        let mut raw = C::raw();
+        let mut image_format_properties = VkImageFormatProperties2KHR {
+            sType: VK_STRUCTURE_TYPE_IMAGE_FORMAT_PROPERTIES_2_KHR,
+            pNext: C::link(&mut raw, ptr::null_mut()),
+            imageFormatProperties: Default::default(),
+        };
+        let result = unsafe {
+            (loader.0.khr_get_physical_device_properties2
+             .vkGetPhysicalDeviceImageFormatProperties2KHR)(
+                self.device,
+                &format_info,
+                &mut image_format_properties
+            )
+        };
+        if result == VK_ERROR_FORMAT_NOT_SUPPORTED {
+            return Ok(None);
+        }
+        vk_try!(result, "vkGetPhysicalDeviceImageFormatProperties2KHR",
+                "PhysicalDevice::get_image_format_properties2");
+
+        Ok(Some((From::from(image_format_properties.imageFormatProperties),
+                 C::from_raw(&raw))))
+    }
+
+    /// See vulkan specification, section 4.1 Physical Devices
+    pub fn get_queue_family_properties2<C>(&self, loader: &InstanceLoader)
+                                           -> Result<Vec<(QueueFamilyProperties, C)>, Error>
+        where C: OutputChain<chain::QueueFamilyProperties2>
+    {
+        loader.require_extension(VK_KHR_GET_PHYSICAL_DEVICE_PROPERTIES2_EXTENSION_NAME_STR)?;
+        // Call once to get the count
+        let mut property_count: u32 = 0;
+        unsafe {
+            (loader.0.khr_get_physical_device_properties2
+             .vkGetPhysicalDeviceQueueFamilyProperties2KHR)(
+                self.device,
+                &mut property_count,
+                ptr::null_mut(),
+            );
+        }
+
+        // Prepare room for the output, with a chain for each element.  The
+        // chains are not moved again once linked.
+        let mut raws: Vec<C::Raw> = (0..property_count).map(|_| C::raw()).collect();
+        let mut properties: Vec<VkQueueFamilyProperties2KHR> = raws.iter_mut()
+            .map(|raw| VkQueueFamilyProperties2KHR {
+                sType: VK_STRUCTURE_TYPE_QUEUE_FAMILY_PROPERTIES_2_KHR,
+                pNext: C::link(raw, ptr::null_mut()),
+                queueFamilyProperties: Default::default(),
+            })
+            .collect();
+
+        // Call again to get the data
+        unsafe {
+            (loader.0.khr_get_physical_device_properties2
+             .vkGetPhysicalDeviceQueueFamilyProperties2KHR)(
+                self.device,
+                &mut property_count,
+                properties.as_mut_ptr(),
+            );
+        }
+        properties.truncate(property_count as usize);
+
+        // Translate for output
+        Ok(properties.into_iter()
+           .zip(raws.iter())
+           .map(|(property, raw)| (From::from(property.queueFamilyProperties),
+                                   C::from_raw(raw)))
+           .collect())
+    }
+
+    /// See vulkan specification, section 10.2 Device Memory
+    pub fn get_memory_properties2<C>(&self, loader: &InstanceLoader)
+                                     -> Result<(PhysicalDeviceMemoryProperties, C), Error>
+        where C: OutputChain<chain::MemoryProperties2>
+    {
+        loader.require_extension(VK_KHR_GET_PHYSICAL_DEVICE_PROPERTIES2_EXTENSION_NAME_STR)?;
+        let mut raw = C::raw();
+        let mut memory_properties = VkPhysicalDeviceMemoryProperties2KHR {
+            sType: VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_MEMORY_PROPERTIES_2_KHR,
+            pNext: C::link(&mut raw, ptr::null_mut()),
+            memoryProperties: Default::default(),
+        };
+        unsafe {
+            (loader.0.khr_get_physical_device_properties2.vkGetPhysicalDeviceMemoryProperties2KHR)(
+                self.device,
+                &mut memory_properties
+            );
+        }
+
+        Ok((From::from(memory_properties.memoryProperties), C::from_raw(&raw)))
+    }
+
+    /// See vulkan specification, section 28.7.3 Sparse Image Format Properties
+    pub fn get_sparse_image_format_properties2<C>(&self, loader: &InstanceLoader,
+                                                  format: Format, image_type: ImageType,
+                                                  samples: SampleCountFlags,
+                                                  usage: ImageUsageFlags, tiling: ImageTiling)
+        -> Result<Vec<(SparseImageFormatProperties, C)>, Error>
+        where C: OutputChain<chain::SparseImageFormatProperties2>
+    {
+        loader.require_extension(VK_KHR_GET_PHYSICAL_DEVICE_PROPERTIES2_EXTENSION_NAME_STR)?;
+        let format_info = VkPhysicalDeviceSparseImageFormatInfo2KHR {
+            sType: VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_SPARSE_IMAGE_FORMAT_INFO_2_KHR,
+            pNext: ptr::null(),
+            format: format.into(),
+            type_: image_type.into(),
+            samples: samples,
+            usage: usage.into(),
+            tiling: tiling.into(),
+        };
+
+        // Call once to get the count
+        let mut property_count: u32 = 0;
+        unsafe {
+            (loader.0.khr_get_physical_device_properties2
+             .vkGetPhysicalDeviceSparseImageFormatProperties2KHR)(
+                self.device,
+                &format_info,
+                &mut property_count,
+                ptr::null_mut(),
+            );
+        }
+
+        // Prepare room for the output, with a chain for each element
+        let mut raws: Vec<C::Raw> = (0..property_count).map(|_| C::raw()).collect();
+        let mut properties: Vec<VkSparseImageFormatProperties2KHR> = raws.iter_mut()
+            .map(|raw| VkSparseImageFormatProperties2KHR {
+                sType: VK_STRUCTURE_TYPE_SPARSE_IMAGE_FORMAT_PROPERTIES_2_KHR,
+                pNext: C::link(raw, ptr::null_mut()),
+                properties: Default::default(),
+            })
+            .collect();
+
+        // Call again to get the data
+        unsafe {
+            (loader.0.khr_get_physical_device_properties2
+             .vkGetPhysicalDeviceSparseImageFormatProperties2KHR)(
+                self.device,
+                &format_info,
+                &mut property_count,
+                properties.as_mut_ptr(),
+            );
+        }
+        properties.truncate(property_count as usize);
+
+        // Translate for output
+        Ok(properties.into_iter()
+           .zip(raws.iter())
+           .map(|(property, raw)| (From::from(property.properties), C::from_raw(raw)))
+           .collect())
     }
 }
@@ -3,7 +3,7 @@ mod physical_device_type;
 pub use self::physical_device_type::PhysicalDeviceType;
 
 mod physical_device_limits;
-pub use self::physical_device_limits::PhysicalDeviceLimits;
+pub use self::physical_device_limits::{PhysicalDeviceLimits, LimitValue};
 
 mod physical_device_sparse_properties;
 pub use self::physical_device_sparse_properties::PhysicalDeviceSparseProperties;
@@ -22,32 +22,73 @@ pub use self::extension_properties::ExtensionProperties;
 mod physical_device_features;
 pub use self::physical_device_features::PhysicalDeviceFeatures;
 
+mod selector;
+pub use self::selector::{PhysicalDeviceSelector, SelectedPhysicalDevice, DeviceRejection,
+                         RejectionReason};
+
+mod queue_planner;
+pub use self::queue_planner::{QueuePlanner, QueuePlan, QueueAssignment};
+
+mod profile;
+pub use self::profile::{Profile, LimitRequirement, FormatRequirement, FormatFeatureSet,
+                        ProfileViolation};
+
+mod format_support;
+pub use self::format_support::{FormatTable, DEPTH_FORMATS, DEPTH_STENCIL_FORMATS, HDR_FORMATS};
+
 mod physical_device_memory_properties;
-pub use self::physical_device_memory_properties::PhysicalDeviceMemoryProperties;
+pub use self::physical_device_memory_properties::{PhysicalDeviceMemoryProperties,
+                                                  MemoryType, MemoryHeap,
+                                                  MemoryPropertyFlags, MemoryHeapFlags};
+pub use self::physical_device_memory_properties::{MEMORY_PROPERTY_DEVICE_LOCAL_BIT,
+                                                  MEMORY_PROPERTY_HOST_VISIBLE_BIT,
+                                                  MEMORY_PROPERTY_HOST_COHERENT_BIT,
+                                                  MEMORY_PROPERTY_HOST_CACHED_BIT,
+                                                  MEMORY_PROPERTY_LAZILY_ALLOCATED_BIT,
+                                                  MEMORY_HEAP_DEVICE_LOCAL_BIT};
+#[cfg(feature = "khx_device_group_creation")]
+pub use self::physical_device_memory_properties::MEMORY_HEAP_MULTI_INSTANCE_BIT_KHX;
+
+#[cfg(feature = "khr_get_physical_device_properties2")]
+pub mod output_chain;
+#[cfg(feature = "khr_get_physical_device_properties2")]
+pub use self::output_chain::OutputChain;
+#[cfg(feature = "khr_push_descriptor")]
+pub use self::output_chain::PushDescriptorProperties;
+#[cfg(feature = "ext_discard_rectangles")]
+pub use self::output_chain::DiscardRectangleProperties;
+#[cfg(feature = "khx_multiview")]
+pub use self::output_chain::{MultiviewFeatures, MultiviewProperties};
 
 use std::mem;
-use std::str;
 use std::ptr;
+use std::ffi::CString;
 use vks::*;
-use {Error, InstanceLoader, Format, FormatProperties};
+use {Error, InstanceLoader, Instance, Format, FormatProperties, SampleCountFlags,
+     LayerProperties};
+use image::{ImageType, ImageTiling, ImageUsageFlags, ImageCreateFlags, ImageFormatProperties,
+            SparseImageFormatProperties};
 #[cfg(feature = "khr_surface")]
 use instance::surface::{Surface, SurfaceFormat, SurfaceCapabilities, PresentMode};
 
 pub type DeviceSize = VkDeviceSize; // u64
 
 /// See vulkan specification, section 4.1 Physical Devices
+#[derive(Clone)]
 pub struct PhysicalDevice {
-    #[allow(dead_code)]
     device: VkPhysicalDevice,
+    instance: Instance,
 }
 // No need to destroy VkPhysicalDevice explicitly.  They are implicitly destroyed
-// when the instance is destroyed (see Section 2.3)
+// when the instance is destroyed (see Section 2.3), so we keep the instance
+// alive for as long as we are.
 
 impl PhysicalDevice {
-    pub fn from_vk(vk: VkPhysicalDevice) -> Result<PhysicalDevice, Error>
+    pub fn from_vk(instance: &Instance, vk: VkPhysicalDevice) -> Result<PhysicalDevice, Error>
     {
         Ok(PhysicalDevice {
-            device: vk
+            device: vk,
+            instance: instance.clone(),
         })
     }
 
@@ -55,11 +96,16 @@ impl PhysicalDevice {
     {
         self.device
     }
+
+    /// The instance this physical device was enumerated from
+    pub fn instance(&self) -> &Instance
+    {
+        &self.instance
+    }
 }
 
 
 impl PhysicalDevice {
-    #[cfg(not(feature = "khr_get_physical_device_properties2"))]
     pub fn get_properties(&self, loader: &InstanceLoader) ->
         Result<PhysicalDeviceProperties, Error>
     {
@@ -74,6 +120,20 @@ impl PhysicalDevice {
         Ok(From::from(properties))
     }
 
+    /// See vulkan specification, section 31.1 Features
+    pub fn get_features(&self, loader: &InstanceLoader) -> Result<PhysicalDeviceFeatures, Error>
+    {
+        let mut features: VkPhysicalDeviceFeatures = Default::default();
+        unsafe {
+            (loader.0.core.vkGetPhysicalDeviceFeatures)(
+                self.device,
+                &mut features
+            )
+        }
+
+        Ok(From::from(features))
+    }
+
     // fixme: need custom version for khr_get_physical_device_properties2
     pub fn get_queue_family_properties(&self, loader: &InstanceLoader) ->
         Result<Vec<QueueFamilyProperties>, Error>
@@ -117,18 +177,31 @@ impl PhysicalDevice {
         Ok(output)
     }
 
-    pub fn get_extension_properties(&self, loader: &InstanceLoader) ->
-        Result<Vec<ExtensionProperties>, Error>
+    /// See vulkan specification, section 30.2 Extensions.  With a layer
+    /// name, returns the device extensions provided by that layer.
+    pub fn get_extension_properties(&self, loader: &InstanceLoader, layer_name: Option<&str>)
+                                    -> Result<Vec<ExtensionProperties>, Error>
     {
+        let layer_name_cstring: Option<CString> = match layer_name {
+            Some(s) => Some(CString::new(s)?),
+            None => None
+        };
+        let p_layer_name = match layer_name_cstring {
+            Some(ref s) => s.as_ptr(),
+            None => ptr::null(),
+        };
+
         // Call once to get the count
-        let mut property_count: u32 = unsafe { mem::uninitialized() };
+        let mut property_count: u32 = 0;
 
         unsafe {
-            (loader.0.core.vkEnumerateDeviceExtensionProperties)(
+            vk_try!((loader.0.core.vkEnumerateDeviceExtensionProperties)(
                 self.device,
-                ptr::null(), // pLayerName: *const c_char
+                p_layer_name, // pLayerName: *const c_char
                 &mut property_count, // pProprtyCount: *mut u32
-                ptr::null_mut()); // pProperties: *mut VkExtensionProperties
+                ptr::null_mut()), // pProperties: *mut VkExtensionProperties
+                    "vkEnumerateDeviceExtensionProperties",
+                    "PhysicalDevice::get_extension_properties");
         }
 
         let capacity: usize = property_count as usize;
@@ -136,11 +209,13 @@ impl PhysicalDevice {
 
         // Call again to get the data
         unsafe {
-            (loader.0.core.vkEnumerateDeviceExtensionProperties)(
+            vk_try_status!((loader.0.core.vkEnumerateDeviceExtensionProperties)(
                 self.device,
-                ptr::null(), // pLayerName: *const c_char
+                p_layer_name, // pLayerName: *const c_char
                 &mut property_count, // pProprtyCount: *mut u32
-                properties.as_mut_ptr()); // pProperties: *mut VkExtensionProperties
+                properties.as_mut_ptr()), // pProperties: *mut VkExtensionProperties
+                           "vkEnumerateDeviceExtensionProperties",
+                           "PhysicalDevice::get_extension_properties");
         }
         assert_eq!(property_count as usize, capacity);
 
@@ -159,11 +234,56 @@ impl PhysicalDevice {
         Ok(output)
     }
 
+    /// See vulkan specification, section 30.1 Layers.  Device layers are
+    /// deprecated, and report the same layers as
+    /// `enumerate_instance_layer_properties()` on current loaders.
+    pub fn enumerate_layer_properties(&self, loader: &InstanceLoader)
+                                      -> Result<Vec<LayerProperties>, Error>
+    {
+        // Call once to get the count
+        let mut property_count: u32 = 0;
+        unsafe {
+            vk_try!((loader.0.core.vkEnumerateDeviceLayerProperties)(
+                self.device,
+                &mut property_count,
+                ptr::null_mut()), "vkEnumerateDeviceLayerProperties",
+                                  "PhysicalDevice::enumerate_layer_properties");
+        }
+
+        let capacity: usize = property_count as usize;
+        let mut properties: Vec<VkLayerProperties> = Vec::with_capacity(capacity);
+
+        // Call again to get the data
+        unsafe {
+            vk_try_status!((loader.0.core.vkEnumerateDeviceLayerProperties)(
+                self.device,
+                &mut property_count,
+                properties.as_mut_ptr()), "vkEnumerateDeviceLayerProperties",
+                                          "PhysicalDevice::enumerate_layer_properties");
+        }
+        assert_eq!(property_count as usize, capacity);
+
+        // Trust the data now in the properties vector
+        let properties = unsafe {
+            let ptr = properties.as_mut_ptr();
+            mem::forget(properties);
+            Vec::from_raw_parts(ptr, property_count as usize, capacity)
+        };
+
+        // Translate for output
+        let mut output: Vec<LayerProperties> = Vec::with_capacity(property_count as usize);
+        for property in properties {
+            output.push(LayerProperties::from_vk(property)?);
+        }
+        Ok(output)
+    }
+
     #[cfg(feature = "khr_surface")]
     pub fn get_surface_support(&self, loader: &InstanceLoader, queue_family_index: u32,
                                surface: &Surface)
                                -> Result<bool, Error>
     {
+        loader.require_extension(VK_KHR_SURFACE_EXTENSION_NAME_STR)?;
         Ok( unsafe {
             let mut supported: u32 = mem::uninitialized();
             vk_try!((loader.0.khr_surface.vkGetPhysicalDeviceSurfaceSupportKHR)(
@@ -171,7 +291,7 @@ impl PhysicalDevice {
                 queue_family_index,
                 surface.inner(),
                 &mut supported
-            ));
+            ), "vkGetPhysicalDeviceSurfaceSupportKHR", "PhysicalDevice::get_surface_support");
             supported
         } != 0)
     }
@@ -180,6 +300,7 @@ impl PhysicalDevice {
     pub fn get_surface_formats(&self, loader: &InstanceLoader, surface: &Surface)
                                -> Result<Vec<SurfaceFormat>, Error>
     {
+        loader.require_extension(VK_KHR_SURFACE_EXTENSION_NAME_STR)?;
         // Call once to get the count
         let mut count: u32 = 0;
         unsafe {
@@ -188,7 +309,7 @@ impl PhysicalDevice {
                 surface.inner(),
                 &mut count,
                 ptr::null_mut()
-            ));
+            ), "vkGetPhysicalDeviceSurfaceFormatsKHR", "PhysicalDevice::get_surface_formats");
         }
 
         // Prepare room for the surface_formats output
@@ -197,12 +318,12 @@ impl PhysicalDevice {
 
         // Call again to get the data
         unsafe {
-            vk_try!((loader.0.khr_surface.vkGetPhysicalDeviceSurfaceFormatsKHR)(
+            vk_try_status!((loader.0.khr_surface.vkGetPhysicalDeviceSurfaceFormatsKHR)(
                 self.device,
                 surface.inner(),
                 &mut count,
                 surface_formats.as_mut_ptr()
-            ));
+            ), "vkGetPhysicalDeviceSurfaceFormatsKHR", "PhysicalDevice::get_surface_formats");
         }
         assert_eq!(count as usize, capacity);
 
@@ -222,13 +343,15 @@ impl PhysicalDevice {
     pub fn get_surface_capabilities(&self, loader: &InstanceLoader, surface: &Surface)
                                     -> Result<SurfaceCapabilities, Error>
     {
+        loader.require_extension(VK_KHR_SURFACE_EXTENSION_NAME_STR)?;
         let capabilities = unsafe {
             let mut capabilities: VkSurfaceCapabilitiesKHR = mem::uninitialized();
             vk_try!((loader.0.khr_surface.vkGetPhysicalDeviceSurfaceCapabilitiesKHR)(
                 self.device,
                 surface.inner(),
                 &mut capabilities
-            ));
+            ), "vkGetPhysicalDeviceSurfaceCapabilitiesKHR",
+               "PhysicalDevice::get_surface_capabilities");
             capabilities
         };
         Ok(From::from(capabilities))
@@ -238,6 +361,7 @@ impl PhysicalDevice {
     pub fn get_surface_present_modes(&self, loader: &InstanceLoader, surface: &Surface)
                                      -> Result<Vec<PresentMode>, Error>
     {
+        loader.require_extension(VK_KHR_SURFACE_EXTENSION_NAME_STR)?;
         // Call once to get the count
         let mut count: u32 = 0;
         unsafe {
@@ -246,7 +370,8 @@ impl PhysicalDevice {
                 surface.inner(),
                 &mut count,
                 ptr::null_mut()
-            ));
+            ), "vkGetPhysicalDeviceSurfacePresentModesKHR",
+               "PhysicalDevice::get_surface_present_modes");
         }
 
         // Prepare room for the present_modes output
@@ -254,12 +379,13 @@ impl PhysicalDevice {
 
         // Call again to get the data
         unsafe {
-            vk_try!((loader.0.khr_surface.vkGetPhysicalDeviceSurfacePresentModesKHR)(
+            vk_try_status!((loader.0.khr_surface.vkGetPhysicalDeviceSurfacePresentModesKHR)(
                 self.device,
                 surface.inner(),
                 &mut count,
                 present_modes.as_mut_ptr(),
-            ));
+            ), "vkGetPhysicalDeviceSurfacePresentModesKHR",
+               "PhysicalDevice::get_surface_present_modes");
         }
 
         // Trust the data now in the present_modes vector
@@ -290,6 +416,85 @@ impl PhysicalDevice {
         Ok(From::from(format_properties))
     }
 
+    /// See vulkan specification, section 31.4 Additional Image Capabilities.
+    /// Returns None if the combination of parameters is not supported.
+    pub fn get_image_format_properties(&self, loader: &InstanceLoader, format: Format,
+                                       image_type: ImageType, tiling: ImageTiling,
+                                       usage: ImageUsageFlags, flags: ImageCreateFlags)
+                                       -> Result<Option<ImageFormatProperties>, Error>
+    {
+        let mut image_format_properties: VkImageFormatProperties = unsafe {
+            mem::uninitialized()
+        };
+        let result = unsafe {
+            (loader.0.core.vkGetPhysicalDeviceImageFormatProperties)(
+                self.device,
+                format.into(),
+                image_type.into(),
+                tiling.into(),
+                usage.into(),
+                flags.into(),
+                &mut image_format_properties)
+        };
+        if result == VK_ERROR_FORMAT_NOT_SUPPORTED {
+            return Ok(None);
+        }
+        vk_try!(result, "vkGetPhysicalDeviceImageFormatProperties",
+                "PhysicalDevice::get_image_format_properties");
+        Ok(Some(From::from(image_format_properties)))
+    }
+
+    /// See vulkan specification, section 28.7.3 Sparse Image Format Properties.
+    /// Returns an empty vector if sparse images are not supported with these
+    /// parameters.
+    pub fn get_sparse_image_format_properties(&self, loader: &InstanceLoader, format: Format,
+                                              image_type: ImageType, samples: SampleCountFlags,
+                                              usage: ImageUsageFlags, tiling: ImageTiling)
+                                              -> Result<Vec<SparseImageFormatProperties>, Error>
+    {
+        // Call once to get the count
+        let mut property_count: u32 = 0;
+        unsafe {
+            (loader.0.core.vkGetPhysicalDeviceSparseImageFormatProperties)(
+                self.device,
+                format.into(),
+                image_type.into(),
+                samples,
+                usage.into(),
+                tiling.into(),
+                &mut property_count,
+                ptr::null_mut());
+        }
+
+        // Prepare room for the output
+        let capacity: usize = property_count as usize;
+        let mut properties: Vec<VkSparseImageFormatProperties> = Vec::with_capacity(capacity);
+
+        // Call again to get the data
+        unsafe {
+            (loader.0.core.vkGetPhysicalDeviceSparseImageFormatProperties)(
+                self.device,
+                format.into(),
+                image_type.into(),
+                samples,
+                usage.into(),
+                tiling.into(),
+                &mut property_count,
+                properties.as_mut_ptr());
+        }
+        assert!(property_count as usize <= capacity);
+
+        // Trust the data now in the properties vector
+        let properties = unsafe {
+            let ptr = properties.as_mut_ptr();
+            mem::forget(properties);
+            Vec::from_raw_parts(ptr, property_count as usize, capacity)
+        };
+
+        // Translate for output
+        Ok(properties.into_iter().map(From::from).collect())
+    }
+
     pub fn get_memory_properties(&self, loader: &InstanceLoader)
                                  -> Result<PhysicalDeviceMemoryProperties, Error>
     {
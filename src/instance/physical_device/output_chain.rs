@@ -0,0 +1,223 @@
+//! Typed pNext chains for the VK_KHR_get_physical_device_properties2 queries.
+//!
+//! Each query takes a type parameter naming the extension structures to chain
+//! on, and returns them filled in alongside the core result.  Use `()` for no
+//! extensions, a single structure, or a tuple of up to four structures.  For
+//! example:
+//!
+//! ```ignore
+//! let (properties, (push, discard)) = physical_device.get_properties2::<
+//!     (PushDescriptorProperties, DiscardRectangleProperties)>(&loader)?;
+//! ```
+
+use std::ptr;
+use libc::c_void;
+use vks::*;
+
+/// Marker for chains onto `PhysicalDevice::get_features2()`
+pub enum Features2 {}
+/// Marker for chains onto `PhysicalDevice::get_properties2()`
+pub enum Properties2 {}
+/// Marker for chains onto `PhysicalDevice::get_format_properties2()`.  No
+/// extension structures extend this query yet, so only `()` can be chained.
+pub enum FormatProperties2 {}
+/// Marker for chains onto `PhysicalDevice::get_image_format_properties2()`.
+/// No extension output structures are provided for this query yet, so only
+/// `()` can be chained.
+pub enum ImageFormatProperties2 {}
+/// Marker for chains onto `PhysicalDevice::get_queue_family_properties2()`.
+/// No extension structures extend this query yet, so only `()` can be chained.
+pub enum QueueFamilyProperties2 {}
+/// Marker for chains onto `PhysicalDevice::get_memory_properties2()`.  No
+/// extension structures extend this query yet, so only `()` can be chained.
+pub enum MemoryProperties2 {}
+/// Marker for chains onto `PhysicalDevice::get_sparse_image_format_properties2()`.
+/// No extension structures extend this query yet, so only `()` can be chained.
+pub enum SparseImageFormatProperties2 {}
+
+/// A chain of extension output structures which may extend query `Q`.
+///
+/// This is unsafe to implement because `link()` must return a pointer to a
+/// Vulkan structure of a type which may extend `Q`, with its pNext set to
+/// `next`.
+pub unsafe trait OutputChain<Q> {
+    /// The raw Vulkan structures, with sType set
+    type Raw;
+
+    fn raw() -> Self::Raw;
+
+    /// Link the raw structures together in front of `next`, returning the head
+    /// of the chain.  `raw` must not move until the query has been made.
+    fn link(raw: &mut Self::Raw, next: *mut c_void) -> *mut c_void;
+
+    fn from_raw(raw: &Self::Raw) -> Self;
+}
+
+unsafe impl<Q> OutputChain<Q> for () {
+    type Raw = ();
+
+    fn raw() -> () {
+    }
+
+    fn link(_raw: &mut (), next: *mut c_void) -> *mut c_void {
+        next
+    }
+
+    fn from_raw(_raw: &()) -> () {
+    }
+}
+
+unsafe impl<Q, A, B> OutputChain<Q> for (A, B)
+    where A: OutputChain<Q>, B: OutputChain<Q>
+{
+    type Raw = (A::Raw, B::Raw);
+
+    fn raw() -> Self::Raw {
+        (A::raw(), B::raw())
+    }
+
+    fn link(raw: &mut Self::Raw, next: *mut c_void) -> *mut c_void {
+        let next = B::link(&mut raw.1, next);
+        A::link(&mut raw.0, next)
+    }
+
+    fn from_raw(raw: &Self::Raw) -> Self {
+        (A::from_raw(&raw.0), B::from_raw(&raw.1))
+    }
+}
+
+unsafe impl<Q, A, B, C> OutputChain<Q> for (A, B, C)
+    where A: OutputChain<Q>, B: OutputChain<Q>, C: OutputChain<Q>
+{
+    type Raw = (A::Raw, B::Raw, C::Raw);
+
+    fn raw() -> Self::Raw {
+        (A::raw(), B::raw(), C::raw())
+    }
+
+    fn link(raw: &mut Self::Raw, next: *mut c_void) -> *mut c_void {
+        let next = C::link(&mut raw.2, next);
+        let next = B::link(&mut raw.1, next);
+        A::link(&mut raw.0, next)
+    }
+
+    fn from_raw(raw: &Self::Raw) -> Self {
+        (A::from_raw(&raw.0), B::from_raw(&raw.1), C::from_raw(&raw.2))
+    }
+}
+
+unsafe impl<Q, A, B, C, D> OutputChain<Q> for (A, B, C, D)
+    where A: OutputChain<Q>, B: OutputChain<Q>, C: OutputChain<Q>, D: OutputChain<Q>
+{
+    type Raw = (A::Raw, B::Raw, C::Raw, D::Raw);
+
+    fn raw() -> Self::Raw {
+        (A::raw(), B::raw(), C::raw(), D::raw())
+    }
+
+    fn link(raw: &mut Self::Raw, next: *mut c_void) -> *mut c_void {
+        let next = D::link(&mut raw.3, next);
+        let next = C::link(&mut raw.2, next);
+        let next = B::link(&mut raw.1, next);
+        A::link(&mut raw.0, next)
+    }
+
+    fn from_raw(raw: &Self::Raw) -> Self {
+        (A::from_raw(&raw.0), B::from_raw(&raw.1), C::from_raw(&raw.2), D::from_raw(&raw.3))
+    }
+}
+
+// Implement OutputChain for an extension structure, given its raw type and
+// sType, how to build it from the raw structure, and the queries it extends.
+macro_rules! output_struct {
+    ( $name:ident, $vk:ident, $s_type:ident, [ $( $query:ident ),* ],
+      | $raw:ident | $from_raw:expr ) => {
+        $(
+            unsafe impl OutputChain<$query> for $name {
+                type Raw = $vk;
+
+                fn raw() -> $vk {
+                    let mut raw: $vk = Default::default();
+                    raw.sType = $s_type;
+                    raw.pNext = ptr::null_mut();
+                    raw
+                }
+
+                fn link(raw: &mut $vk, next: *mut c_void) -> *mut c_void {
+                    raw.pNext = next;
+                    raw as *mut $vk as *mut c_void
+                }
+
+                fn from_raw($raw: &$vk) -> $name {
+                    $from_raw
+                }
+            }
+        )*
+    }
+}
+
+/// See vulkan specification, section 31.2 Limits
+#[cfg(feature = "khr_push_descriptor")]
+#[derive(Debug, Clone, Default)]
+pub struct PushDescriptorProperties {
+    pub max_push_descriptors: u32,
+}
+
+#[cfg(feature = "khr_push_descriptor")]
+output_struct!(PushDescriptorProperties, VkPhysicalDevicePushDescriptorPropertiesKHR,
+               VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_PUSH_DESCRIPTOR_PROPERTIES_KHR,
+               [Properties2],
+               |raw| PushDescriptorProperties {
+                   max_push_descriptors: raw.maxPushDescriptors,
+               });
+
+/// See vulkan specification, section 31.2 Limits
+#[cfg(feature = "ext_discard_rectangles")]
+#[derive(Debug, Clone, Default)]
+pub struct DiscardRectangleProperties {
+    pub max_discard_rectangles: u32,
+}
+
+#[cfg(feature = "ext_discard_rectangles")]
+output_struct!(DiscardRectangleProperties, VkPhysicalDeviceDiscardRectanglePropertiesEXT,
+               VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_DISCARD_RECTANGLE_PROPERTIES_EXT,
+               [Properties2],
+               |raw| DiscardRectangleProperties {
+                   max_discard_rectangles: raw.maxDiscardRectangles,
+               });
+
+/// See vulkan specification, section 31.1 Features
+#[cfg(feature = "khx_multiview")]
+#[derive(Debug, Clone, Default)]
+pub struct MultiviewFeatures {
+    pub multiview: bool,
+    pub multiview_geometry_shader: bool,
+    pub multiview_tessellation_shader: bool,
+}
+
+#[cfg(feature = "khx_multiview")]
+output_struct!(MultiviewFeatures, VkPhysicalDeviceMultiviewFeaturesKHX,
+               VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_MULTIVIEW_FEATURES_KHX,
+               [Features2],
+               |raw| MultiviewFeatures {
+                   multiview: raw.multiview != 0,
+                   multiview_geometry_shader: raw.multiviewGeometryShader != 0,
+                   multiview_tessellation_shader: raw.multiviewTessellationShader != 0,
+               });
+
+/// See vulkan specification, section 31.2 Limits
+#[cfg(feature = "khx_multiview")]
+#[derive(Debug, Clone, Default)]
+pub struct MultiviewProperties {
+    pub max_multiview_view_count: u32,
+    pub max_multiview_instance_index: u32,
+}
+
+#[cfg(feature = "khx_multiview")]
+output_struct!(MultiviewProperties, VkPhysicalDeviceMultiviewPropertiesKHX,
+               VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_MULTIVIEW_PROPERTIES_KHX,
+               [Properties2],
+               |raw| MultiviewProperties {
+                   max_multiview_view_count: raw.maxMultiviewViewCount,
+                   max_multiview_instance_index: raw.maxMultiviewInstanceIndex,
+               });
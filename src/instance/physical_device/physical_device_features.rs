@@ -2,65 +2,129 @@
 use std::mem;
 use vks::VkPhysicalDeviceFeatures;
 
-/// See vulkan specification, section 31.1 Features
-#[repr(C)] // laid out exactly like VkPhysicalDeviceFeatures so we can just transmute
-#[derive(Debug, Clone)]
-pub struct PhysicalDeviceFeatures {
-    pub robust_buffer_access: u32,
-    pub full_draw_index_uint32: u32,
-    pub image_cube_array: u32,
-    pub independent_blend: u32,
-    pub geometry_shader: u32,
-    pub tessellation_shader: u32,
-    pub sample_rate_shading: u32,
-    pub dual_src_blend: u32,
-    pub logic_op: u32,
-    pub multi_draw_indirect: u32,
-    pub draw_indirect_first_instance: u32,
-    pub depth_clamp: u32,
-    pub depth_bias_clamp: u32,
-    pub fill_mode_non_solid: u32,
-    pub depth_bounds: u32,
-    pub wide_lines: u32,
-    pub large_points: u32,
-    pub alpha_to_one: u32,
-    pub multi_viewport: u32,
-    pub sampler_anisotropy: u32,
-    pub texture_compression_etc2: u32,
-    pub texture_compression_astc_ldr: u32,
-    pub texture_compression_bc: u32,
-    pub occlusion_query_precise: u32,
-    pub pipeline_statistics_query: u32,
-    pub vertex_pipeline_stores_and_atomics: u32,
-    pub fragment_stores_and_atomics: u32,
-    pub shader_tessellation_and_geometry_point_size: u32,
-    pub shader_image_gather_extended: u32,
-    pub shader_storage_image_extended_formats: u32,
-    pub shader_storage_image_multisample: u32,
-    pub shader_storage_image_read_without_format: u32,
-    pub shader_storage_image_write_without_format: u32,
-    pub shader_uniform_buffer_array_dynamic_indexing: u32,
-    pub shader_sampled_image_array_dynamic_indexing: u32,
-    pub shader_storage_buffer_array_dynamic_indexing: u32,
-    pub shader_storage_image_array_dynamic_indexing: u32,
-    pub shader_clip_distance: u32,
-    pub shader_cull_distance: u32,
-    pub shader_float64: u32,
-    pub shader_int64: u32,
-    pub shader_int16: u32,
-    pub shader_resource_residency: u32,
-    pub shader_resource_min_lod: u32,
-    pub sparse_binding: u32,
-    pub sparse_residency_buffer: u32,
-    pub sparse_residency_image2d: u32,
-    pub sparse_residency_image3d: u32,
-    pub sparse_residency2_samples: u32,
-    pub sparse_residency4_samples: u32,
-    pub sparse_residency8_samples: u32,
-    pub sparse_residency16_samples: u32,
-    pub sparse_residency_aliased: u32,
-    pub variable_multisample_rate: u32,
-    pub inherited_queries: u32,
+// Define the struct along with the per-feature operations, so that the list of
+// features is only written once.
+macro_rules! physical_device_features {
+    ( $( $field:ident ),* ) => {
+        /// See vulkan specification, section 31.1 Features
+        #[repr(C)] // laid out exactly like VkPhysicalDeviceFeatures so we can just transmute
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct PhysicalDeviceFeatures {
+            $( pub $field: u32, )*
+        }
+
+        impl PhysicalDeviceFeatures {
+            /// The features enabled in both
+            pub fn intersection(&self, other: &PhysicalDeviceFeatures) -> PhysicalDeviceFeatures
+            {
+                PhysicalDeviceFeatures {
+                    $( $field: (self.$field != 0 && other.$field != 0) as u32, )*
+                }
+            }
+
+            /// The features enabled in either
+            pub fn union(&self, other: &PhysicalDeviceFeatures) -> PhysicalDeviceFeatures
+            {
+                PhysicalDeviceFeatures {
+                    $( $field: (self.$field != 0 || other.$field != 0) as u32, )*
+                }
+            }
+
+            /// Whether every feature enabled here is also enabled in `other`
+            pub fn is_subset_of(&self, other: &PhysicalDeviceFeatures) -> bool
+            {
+                true $( && (self.$field == 0 || other.$field != 0) )*
+            }
+
+            /// The names of the features enabled here but not in `supported`
+            pub fn missing_from(&self, supported: &PhysicalDeviceFeatures) -> Vec<&'static str>
+            {
+                let mut missing = Vec::new();
+                $(
+                    if self.$field != 0 && supported.$field == 0 {
+                        missing.push(stringify!($field));
+                    }
+                )*
+                missing
+            }
+
+            /// The names of the features enabled here
+            pub fn enabled_names(&self) -> Vec<&'static str>
+            {
+                let mut enabled = Vec::new();
+                $(
+                    if self.$field != 0 {
+                        enabled.push(stringify!($field));
+                    }
+                )*
+                enabled
+            }
+
+            /// Every feature name, with whether it is enabled here
+            pub fn fields(&self) -> Vec<(&'static str, bool)>
+            {
+                vec![ $( (stringify!($field), self.$field != 0), )* ]
+            }
+        }
+    }
+}
+
+physical_device_features! {
+    robust_buffer_access,
+    full_draw_index_uint32,
+    image_cube_array,
+    independent_blend,
+    geometry_shader,
+    tessellation_shader,
+    sample_rate_shading,
+    dual_src_blend,
+    logic_op,
+    multi_draw_indirect,
+    draw_indirect_first_instance,
+    depth_clamp,
+    depth_bias_clamp,
+    fill_mode_non_solid,
+    depth_bounds,
+    wide_lines,
+    large_points,
+    alpha_to_one,
+    multi_viewport,
+    sampler_anisotropy,
+    texture_compression_etc2,
+    texture_compression_astc_ldr,
+    texture_compression_bc,
+    occlusion_query_precise,
+    pipeline_statistics_query,
+    vertex_pipeline_stores_and_atomics,
+    fragment_stores_and_atomics,
+    shader_tessellation_and_geometry_point_size,
+    shader_image_gather_extended,
+    shader_storage_image_extended_formats,
+    shader_storage_image_multisample,
+    shader_storage_image_read_without_format,
+    shader_storage_image_write_without_format,
+    shader_uniform_buffer_array_dynamic_indexing,
+    shader_sampled_image_array_dynamic_indexing,
+    shader_storage_buffer_array_dynamic_indexing,
+    shader_storage_image_array_dynamic_indexing,
+    shader_clip_distance,
+    shader_cull_distance,
+    shader_float64,
+    shader_int64,
+    shader_int16,
+    shader_resource_residency,
+    shader_resource_min_lod,
+    sparse_binding,
+    sparse_residency_buffer,
+    sparse_residency_image2d,
+    sparse_residency_image3d,
+    sparse_residency2_samples,
+    sparse_residency4_samples,
+    sparse_residency8_samples,
+    sparse_residency16_samples,
+    sparse_residency_aliased,
+    variable_multisample_rate,
+    inherited_queries
 }
 
 impl From<VkPhysicalDeviceFeatures> for PhysicalDeviceFeatures {
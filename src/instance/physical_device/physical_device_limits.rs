@@ -1,119 +1,187 @@
-
 use std::mem;
 use vks::*;
 use {Bool32, SampleCountFlags};
 use super::DeviceSize;
 
-/// See vulkan specification, section 4.1 Physical Devices
-#[repr(C)] // laid out exactly like VkPhysicalDeviceLimits so we can just transmute
-#[derive(Debug, Clone)]
-pub struct PhysicalDeviceLimits {
-    pub max_image_dimension_1d: u32,
-    pub max_image_dimension_2d: u32,
-    pub max_image_dimension_3d: u32,
-    pub max_image_dimension_cube: u32,
-    pub max_image_array_layers: u32,
-    pub max_texel_buffer_elements: u32,
-    pub max_uniform_buffer_range: u32,
-    pub max_storage_buffer_range: u32,
-    pub max_push_constants_size: u32,
-    pub max_memory_allocation_count: u32,
-    pub max_sampler_allocation_count: u32,
-    pub buffer_image_granularity: DeviceSize,
-    pub sparse_address_space_size: DeviceSize,
-    pub max_bound_descriptor_sets: u32,
-    pub max_per_stage_descriptor_samplers: u32,
-    pub max_per_stage_descriptor_uniform_buffers: u32,
-    pub max_per_stage_descriptor_storage_buffers: u32,
-    pub max_per_stage_descriptor_sampled_images: u32,
-    pub max_per_stage_descriptor_storage_images: u32,
-    pub max_per_stage_descriptor_input_attachments: u32,
-    pub max_per_stage_resources: u32,
-    pub max_descriptor_set_samplers: u32,
-    pub max_descriptor_set_uniform_buffers: u32,
-    pub max_descriptor_set_uniform_buffers_dynamic: u32,
-    pub max_descriptor_set_storage_buffers: u32,
-    pub max_descriptor_set_storage_buffers_dynamic: u32,
-    pub max_descriptor_set_sampled_images: u32,
-    pub max_descriptor_set_storage_images: u32,
-    pub max_descriptor_set_input_attachments: u32,
-    pub max_vertex_input_attributes: u32,
-    pub max_vertex_input_bindings: u32,
-    pub max_vertex_input_attribute_offset: u32,
-    pub max_vertex_input_binding_stride: u32,
-    pub max_vertex_output_components: u32,
-    pub max_tessellation_generation_level: u32,
-    pub max_tessellation_patch_size: u32,
-    pub max_tessellation_control_per_vertex_input_components: u32,
-    pub max_tessellation_control_per_vertex_output_components: u32,
-    pub max_tessellation_control_per_patch_output_components: u32,
-    pub max_tessellation_control_total_output_components: u32,
-    pub max_tessellation_evaluation_input_components: u32,
-    pub max_tessellation_evaluation_output_components: u32,
-    pub max_geometry_shader_invocations: u32,
-    pub max_geometry_input_components: u32,
-    pub max_geometry_output_components: u32,
-    pub max_geometry_output_vertices: u32,
-    pub max_geometry_total_output_components: u32,
-    pub max_fragment_input_components: u32,
-    pub max_fragment_output_attachments: u32,
-    pub max_fragment_dual_src_attachments: u32,
-    pub max_fragment_combined_output_resources: u32,
-    pub max_compute_shared_memory_size: u32,
-    pub max_compute_work_group_count: [u32; 3],
-    pub max_compute_work_group_invocations: u32,
-    pub max_compute_work_group_size: [u32; 3],
-    pub sub_pixel_precision_bits: u32,
-    pub sub_texel_precision_bits: u32,
-    pub mipmap_precision_bits: u32,
-    pub max_draw_indexed_index_value: u32,
-    pub max_draw_indirect_count: u32,
-    pub max_sampler_lod_bios: f32,
-    pub max_sampler_anisotropy: f32,
-    pub max_viewports: u32,
-    pub max_viewport_dimensions: [u32; 2],
-    pub viewport_bounds_range: [f32; 2],
-    pub viewport_sub_pixel_bits: u32,
-    pub min_memory_map_alignment: usize,
-    pub min_texel_buffer_offset_alignment: DeviceSize,
-    pub min_uniform_buffer_offset_alignment: DeviceSize,
-    pub min_storage_buffer_offset_alignment: DeviceSize,
-    pub min_texel_offset: i32,
-    pub max_texel_offset: u32,
-    pub min_texel_gather_offset: i32,
-    pub max_texel_gather_offset: u32,
-    pub min_interpolation_offset: f32,
-    pub max_interpolation_offset: f32,
-    pub sub_pixel_interpolation_offset_bits: u32,
-    pub max_framebuffer_width: u32,
-    pub max_framebuffer_height: u32,
-    pub max_framebuffer_layers: u32,
-    pub framebuffer_color_sample_counts: SampleCountFlags,
-    pub framebuffer_depth_sample_counts: SampleCountFlags,
-    pub framebuffer_stencil_sample_counts: SampleCountFlags,
-    pub framebuffer_no_attachments_sample_counts: SampleCountFlags,
-    pub max_color_attachments: u32,
-    pub sampled_image_color_sample_counts: SampleCountFlags,
-    pub sampled_image_integer_sample_counts: SampleCountFlags,
-    pub sampled_image_depth_sample_counts: SampleCountFlags,
-    pub sampled_image_stencil_sample_counts: SampleCountFlags,
-    pub storage_image_sample_counts: SampleCountFlags,
-    pub max_sample_mask_words: u32,
-    pub timestamp_compute_and_graphics: Bool32,
-    pub timestamp_period: f32,
-    pub max_clip_distances: u32,
-    pub max_cull_distances: u32,
-    pub max_combined_clip_and_cull_distances: u32,
-    pub discrete_queue_priorities: u32,
-    pub point_size_range: [f32; 2],
-    pub line_width_range: [f32; 2],
-    pub point_size_granularity: f32,
-    pub line_width_granularity: f32,
-    pub strict_lines: Bool32,
-    pub standard_sample_locations: Bool32,
-    pub optimal_buffer_copy_offset_alignment: DeviceSize,
-    pub optimal_buffer_copy_row_pitch_alignment: DeviceSize,
-    pub non_coherent_atom_size: DeviceSize,
+/// The value of a single limit, for reporting and comparison
+#[derive(Debug, Clone, PartialEq)]
+pub enum LimitValue {
+    U32(u32),
+    I32(i32),
+    U64(u64),
+    F32(f32),
+    U32Array(Vec<u32>),
+    F32Array(Vec<f32>),
+}
+
+trait ToLimitValue {
+    fn to_limit_value(&self) -> LimitValue;
+}
+
+impl ToLimitValue for u32 {
+    fn to_limit_value(&self) -> LimitValue { LimitValue::U32(*self) }
+}
+
+impl ToLimitValue for i32 {
+    fn to_limit_value(&self) -> LimitValue { LimitValue::I32(*self) }
+}
+
+impl ToLimitValue for u64 {
+    fn to_limit_value(&self) -> LimitValue { LimitValue::U64(*self) }
+}
+
+impl ToLimitValue for usize {
+    fn to_limit_value(&self) -> LimitValue { LimitValue::U64(*self as u64) }
+}
+
+impl ToLimitValue for f32 {
+    fn to_limit_value(&self) -> LimitValue { LimitValue::F32(*self) }
+}
+
+impl ToLimitValue for [u32; 2] {
+    fn to_limit_value(&self) -> LimitValue { LimitValue::U32Array(self.to_vec()) }
+}
+
+impl ToLimitValue for [u32; 3] {
+    fn to_limit_value(&self) -> LimitValue { LimitValue::U32Array(self.to_vec()) }
+}
+
+impl ToLimitValue for [f32; 2] {
+    fn to_limit_value(&self) -> LimitValue { LimitValue::F32Array(self.to_vec()) }
+}
+
+impl ToLimitValue for SampleCountFlags {
+    fn to_limit_value(&self) -> LimitValue { LimitValue::U32(self.bits()) }
+}
+
+// Define the struct along with a by-name view of it, so that the list of
+// limits is only written once.
+macro_rules! physical_device_limits {
+    ( $( $field:ident : $ty:ty ),* ) => {
+        /// See vulkan specification, section 4.1 Physical Devices
+        #[repr(C)] // laid out exactly like VkPhysicalDeviceLimits so we can just transmute
+        #[derive(Debug, Clone)]
+        pub struct PhysicalDeviceLimits {
+            $( pub $field: $ty, )*
+        }
+
+        impl PhysicalDeviceLimits {
+            /// Every limit name, with its value
+            pub fn fields(&self) -> Vec<(&'static str, LimitValue)>
+            {
+                vec![ $( (stringify!($field), self.$field.to_limit_value()), )* ]
+            }
+        }
+    }
+}
+
+physical_device_limits! {
+    max_image_dimension_1d: u32,
+    max_image_dimension_2d: u32,
+    max_image_dimension_3d: u32,
+    max_image_dimension_cube: u32,
+    max_image_array_layers: u32,
+    max_texel_buffer_elements: u32,
+    max_uniform_buffer_range: u32,
+    max_storage_buffer_range: u32,
+    max_push_constants_size: u32,
+    max_memory_allocation_count: u32,
+    max_sampler_allocation_count: u32,
+    buffer_image_granularity: DeviceSize,
+    sparse_address_space_size: DeviceSize,
+    max_bound_descriptor_sets: u32,
+    max_per_stage_descriptor_samplers: u32,
+    max_per_stage_descriptor_uniform_buffers: u32,
+    max_per_stage_descriptor_storage_buffers: u32,
+    max_per_stage_descriptor_sampled_images: u32,
+    max_per_stage_descriptor_storage_images: u32,
+    max_per_stage_descriptor_input_attachments: u32,
+    max_per_stage_resources: u32,
+    max_descriptor_set_samplers: u32,
+    max_descriptor_set_uniform_buffers: u32,
+    max_descriptor_set_uniform_buffers_dynamic: u32,
+    max_descriptor_set_storage_buffers: u32,
+    max_descriptor_set_storage_buffers_dynamic: u32,
+    max_descriptor_set_sampled_images: u32,
+    max_descriptor_set_storage_images: u32,
+    max_descriptor_set_input_attachments: u32,
+    max_vertex_input_attributes: u32,
+    max_vertex_input_bindings: u32,
+    max_vertex_input_attribute_offset: u32,
+    max_vertex_input_binding_stride: u32,
+    max_vertex_output_components: u32,
+    max_tessellation_generation_level: u32,
+    max_tessellation_patch_size: u32,
+    max_tessellation_control_per_vertex_input_components: u32,
+    max_tessellation_control_per_vertex_output_components: u32,
+    max_tessellation_control_per_patch_output_components: u32,
+    max_tessellation_control_total_output_components: u32,
+    max_tessellation_evaluation_input_components: u32,
+    max_tessellation_evaluation_output_components: u32,
+    max_geometry_shader_invocations: u32,
+    max_geometry_input_components: u32,
+    max_geometry_output_components: u32,
+    max_geometry_output_vertices: u32,
+    max_geometry_total_output_components: u32,
+    max_fragment_input_components: u32,
+    max_fragment_output_attachments: u32,
+    max_fragment_dual_src_attachments: u32,
+    max_fragment_combined_output_resources: u32,
+    max_compute_shared_memory_size: u32,
+    max_compute_work_group_count: [u32; 3],
+    max_compute_work_group_invocations: u32,
+    max_compute_work_group_size: [u32; 3],
+    sub_pixel_precision_bits: u32,
+    sub_texel_precision_bits: u32,
+    mipmap_precision_bits: u32,
+    max_draw_indexed_index_value: u32,
+    max_draw_indirect_count: u32,
+    max_sampler_lod_bios: f32,
+    max_sampler_anisotropy: f32,
+    max_viewports: u32,
+    max_viewport_dimensions: [u32; 2],
+    viewport_bounds_range: [f32; 2],
+    viewport_sub_pixel_bits: u32,
+    min_memory_map_alignment: usize,
+    min_texel_buffer_offset_alignment: DeviceSize,
+    min_uniform_buffer_offset_alignment: DeviceSize,
+    min_storage_buffer_offset_alignment: DeviceSize,
+    min_texel_offset: i32,
+    max_texel_offset: u32,
+    min_texel_gather_offset: i32,
+    max_texel_gather_offset: u32,
+    min_interpolation_offset: f32,
+    max_interpolation_offset: f32,
+    sub_pixel_interpolation_offset_bits: u32,
+    max_framebuffer_width: u32,
+    max_framebuffer_height: u32,
+    max_framebuffer_layers: u32,
+    framebuffer_color_sample_counts: SampleCountFlags,
+    framebuffer_depth_sample_counts: SampleCountFlags,
+    framebuffer_stencil_sample_counts: SampleCountFlags,
+    framebuffer_no_attachments_sample_counts: SampleCountFlags,
+    max_color_attachments: u32,
+    sampled_image_color_sample_counts: SampleCountFlags,
+    sampled_image_integer_sample_counts: SampleCountFlags,
+    sampled_image_depth_sample_counts: SampleCountFlags,
+    sampled_image_stencil_sample_counts: SampleCountFlags,
+    storage_image_sample_counts: SampleCountFlags,
+    max_sample_mask_words: u32,
+    timestamp_compute_and_graphics: Bool32,
+    timestamp_period: f32,
+    max_clip_distances: u32,
+    max_cull_distances: u32,
+    max_combined_clip_and_cull_distances: u32,
+    discrete_queue_priorities: u32,
+    point_size_range: [f32; 2],
+    line_width_range: [f32; 2],
+    point_size_granularity: f32,
+    line_width_granularity: f32,
+    strict_lines: Bool32,
+    standard_sample_locations: Bool32,
+    optimal_buffer_copy_offset_alignment: DeviceSize,
+    optimal_buffer_copy_row_pitch_alignment: DeviceSize,
+    non_coherent_atom_size: DeviceSize
 }
 
 impl From<VkPhysicalDeviceLimits> for PhysicalDeviceLimits {
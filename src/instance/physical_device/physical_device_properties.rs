@@ -20,6 +20,16 @@ pub struct PhysicalDeviceProperties {
     pub sparse_properties: PhysicalDeviceSparseProperties,
 }
 
+impl PhysicalDeviceProperties {
+    /// Whether the device's driver supports the given Vulkan version (ignoring
+    /// the patch version), so that devices with older drivers than we target
+    /// can be rejected.
+    pub fn supports_api_version(&self, api_version: &Version) -> bool
+    {
+        self.api_version.supports(api_version)
+    }
+}
+
 impl From<VkPhysicalDeviceProperties> for PhysicalDeviceProperties {
     fn from(vk: VkPhysicalDeviceProperties) -> PhysicalDeviceProperties {
         PhysicalDeviceProperties {
@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+use std::fmt;
+#[cfg(feature = "profile")]
+use std::fs::File;
+#[cfg(feature = "profile")]
+use std::io::Read;
+#[cfg(feature = "profile")]
+use std::path::Path;
+use {Error, InstanceLoader};
+use format::*;
+use error::{MissingName, NameKind};
+use super::{PhysicalDevice, LimitValue};
+
+/// A hardware baseline which a physical device must meet.  With the `profile`
+/// feature, profiles can be read from JSON or TOML files:
+///
+/// ```text
+/// extensions = ["VK_KHR_swapchain"]
+/// features = ["sampler_anisotropy"]
+///
+/// [limits]
+/// max_image_dimension_2d = 8192
+/// max_compute_work_group_size = [128, 128, 64]
+/// min_uniform_buffer_offset_alignment = 256
+///
+/// [formats.D24UnormS8Uint]
+/// optimal_tiling_features = ["FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT"]
+/// ```
+///
+/// Limits are named as in `PhysicalDeviceLimits` (or `max_sampler_lod_bias`, as
+/// in the specification), features as in `PhysicalDeviceFeatures`, formats as
+/// the `Format` variants, and format features as the `FORMAT_FEATURE_*`
+/// constants.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "profile", derive(Deserialize))]
+#[cfg_attr(feature = "profile", serde(default, deny_unknown_fields))]
+pub struct Profile {
+    /// How good each limit must be.  See `LimitRequirement`.
+    pub limits: BTreeMap<String, LimitRequirement>,
+    pub features: Vec<String>,
+    pub extensions: Vec<String>,
+    pub formats: BTreeMap<String, FormatRequirement>,
+}
+
+/// The required value of a limit.  Whether the device must be above or below
+/// it depends on the limit: `min_*` limits, alignments and granularities must
+/// be no larger (except `min_memory_map_alignment`); sample counts must
+/// include every bit; ranges must contain the range; any other limit must be
+/// no smaller.  Array limits are compared element-wise.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "profile", derive(Deserialize))]
+#[cfg_attr(feature = "profile", serde(untagged))]
+pub enum LimitRequirement {
+    Scalar(f64),
+    Array(Vec<f64>),
+}
+
+/// The format features required of one format, named as the
+/// `FORMAT_FEATURE_*` constants
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "profile", derive(Deserialize))]
+#[cfg_attr(feature = "profile", serde(default, deny_unknown_fields))]
+pub struct FormatRequirement {
+    pub linear_tiling_features: Vec<String>,
+    pub optimal_tiling_features: Vec<String>,
+    pub buffer_features: Vec<String>,
+}
+
+#[cfg(feature = "profile")]
+impl Profile {
+    pub fn from_json(s: &str) -> Result<Profile, Error>
+    {
+        ::serde_json::from_str(s).map_err(|e| Error::General(format!("Invalid profile: {}", e)))
+    }
+
+    pub fn from_toml(s: &str) -> Result<Profile, Error>
+    {
+        ::toml::from_str(s).map_err(|e| Error::General(format!("Invalid profile: {}", e)))
+    }
+
+    /// Read a profile, as TOML if the file name ends in `.toml` and as JSON
+    /// otherwise
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Profile, Error>
+    {
+        let path = path.as_ref();
+        let mut contents = String::new();
+        File::open(path)
+            .and_then(|mut f| f.read_to_string(&mut contents))
+            .map_err(|e| Error::General(format!("{}: {}", path.display(), e)))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Profile::from_toml(&contents),
+            _ => Profile::from_json(&contents),
+        }
+    }
+}
+
+/// Which set of format features a `ProfileViolation` concerns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatFeatureSet {
+    LinearTiling,
+    OptimalTiling,
+    Buffer,
+}
+
+/// One way in which a physical device fails to meet a `Profile`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProfileViolation {
+    Limit { name: String, required: LimitRequirement, actual: LimitValue },
+    MissingFeature(String),
+    MissingExtension(MissingName),
+    MissingFormatFeatures { format: Format, set: FormatFeatureSet, missing: FormatFeatureFlags },
+    /// The profile names a limit, feature, format or format feature which
+    /// does not exist
+    UnknownName(String),
+}
+
+impl fmt::Display for ProfileViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProfileViolation::Limit { ref name, ref required, ref actual } =>
+                write!(f, "limit {} is {:?} but {:?} is required", name, actual, required),
+            ProfileViolation::MissingFeature(ref name) =>
+                write!(f, "missing feature {}", name),
+            ProfileViolation::MissingExtension(ref missing) =>
+                write!(f, "missing {}", missing),
+            ProfileViolation::MissingFormatFeatures { format, set, missing } =>
+                write!(f, "{:?} is missing {:?} features {:?}", format, set, missing),
+            ProfileViolation::UnknownName(ref name) =>
+                write!(f, "profile names unknown {}", name),
+        }
+    }
+}
+
+impl PhysicalDevice {
+    /// Check this device against `profile`, returning every violation (so an
+    /// empty vector means the device meets it)
+    pub fn check_profile(&self, loader: &InstanceLoader, profile: &Profile)
+                         -> Result<Vec<ProfileViolation>, Error>
+    {
+        let mut violations: Vec<ProfileViolation> = Vec::new();
+
+        if !profile.limits.is_empty() {
+            let limits = self.get_properties(loader)?.limits.fields();
+            for (name, required) in &profile.limits {
+                let field = limit_field_name(name);
+                match limits.iter().find(|&&(n, _)| n == field) {
+                    Some(&(_, ref actual)) => if !limit_meets(name, actual, required) {
+                        violations.push(ProfileViolation::Limit {
+                            name: name.clone(),
+                            required: required.clone(),
+                            actual: actual.clone(),
+                        });
+                    },
+                    None => violations.push(
+                        ProfileViolation::UnknownName(format!("limit {}", name))),
+                }
+            }
+        }
+
+        if !profile.features.is_empty() {
+            let features = self.get_features(loader)?.fields();
+            for name in &profile.features {
+                match features.iter().find(|&&(n, _)| n == name) {
+                    Some(&(_, true)) => {},
+                    Some(&(_, false)) => violations.push(
+                        ProfileViolation::MissingFeature(name.clone())),
+                    None => violations.push(
+                        ProfileViolation::UnknownName(format!("feature {}", name))),
+                }
+            }
+        }
+
+        if !profile.extensions.is_empty() {
+            let available: Vec<String> = self.get_extension_properties(loader, None)?
+                .into_iter()
+                .map(|ep| ep.extension_name)
+                .collect();
+            violations.extend(MissingName::find(NameKind::DeviceExtension,
+                                                &profile.extensions, &available)
+                              .into_iter()
+                              .map(ProfileViolation::MissingExtension));
+        }
+
+        for (format_name, requirement) in &profile.formats {
+            let format = match Format::all().into_iter()
+                .find(|format| format!("{:?}", format) == *format_name)
+            {
+                Some(format) => format,
+                None => {
+                    violations.push(
+                        ProfileViolation::UnknownName(format!("format {}", format_name)));
+                    continue;
+                },
+            };
+            let properties = self.get_format_properties(loader, format)?;
+            let sets = [
+                (FormatFeatureSet::LinearTiling, &requirement.linear_tiling_features,
+                 properties.linear_tiling_features),
+                (FormatFeatureSet::OptimalTiling, &requirement.optimal_tiling_features,
+                 properties.optimal_tiling_features),
+                (FormatFeatureSet::Buffer, &requirement.buffer_features,
+                 properties.buffer_features),
+            ];
+            for &(set, names, supported) in &sets {
+                let mut required = FormatFeatureFlags::empty();
+                for name in names {
+                    match format_feature_by_name(name) {
+                        Some(flag) => required |= flag,
+                        None => violations.push(
+                            ProfileViolation::UnknownName(format!("format feature {}", name))),
+                    }
+                }
+                let missing = required - supported;
+                if !missing.is_empty() {
+                    violations.push(ProfileViolation::MissingFormatFeatures {
+                        format: format,
+                        set: set,
+                        missing: missing,
+                    });
+                }
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+enum LimitKind {
+    /// The device value must be at least the required value
+    Max,
+    /// The device value must be at most the required value
+    Min,
+    /// The device value must include all the required bits
+    Bits,
+    /// The device range must contain the required range
+    Range,
+}
+
+// PhysicalDeviceLimits misspells maxSamplerLodBias, so accept the spec's name
+fn limit_field_name(name: &str) -> &str
+{
+    match name {
+        "max_sampler_lod_bias" => "max_sampler_lod_bios",
+        name => name,
+    }
+}
+
+fn limit_kind(name: &str) -> LimitKind
+{
+    if name == "min_memory_map_alignment" {
+        // Despite the name, a larger alignment is better
+        LimitKind::Max
+    } else if name.ends_with("_sample_counts") {
+        LimitKind::Bits
+    } else if name.ends_with("_range") && !name.starts_with("max_") {
+        LimitKind::Range
+    } else if name.starts_with("min_") || name.ends_with("_alignment")
+        || name.ends_with("_granularity") || name == "non_coherent_atom_size"
+        || name == "timestamp_period"
+    {
+        LimitKind::Min
+    } else {
+        LimitKind::Max
+    }
+}
+
+fn limit_meets(name: &str, actual: &LimitValue, required: &LimitRequirement) -> bool
+{
+    let actual: Vec<f64> = match *actual {
+        LimitValue::U32(v) => vec![v as f64],
+        LimitValue::I32(v) => vec![v as f64],
+        LimitValue::U64(v) => vec![v as f64],
+        LimitValue::F32(v) => vec![v as f64],
+        LimitValue::U32Array(ref v) => v.iter().map(|&v| v as f64).collect(),
+        LimitValue::F32Array(ref v) => v.iter().map(|&v| v as f64).collect(),
+    };
+    let required: Vec<f64> = match *required {
+        LimitRequirement::Scalar(v) => vec![v],
+        LimitRequirement::Array(ref v) => v.clone(),
+    };
+    if actual.len() != required.len() {
+        return false;
+    }
+
+    match limit_kind(name) {
+        LimitKind::Max => actual.iter().zip(required.iter()).all(|(a, r)| a >= r),
+        LimitKind::Min => actual.iter().zip(required.iter()).all(|(a, r)| a <= r),
+        LimitKind::Bits => actual.iter().zip(required.iter())
+            .all(|(&a, &r)| (a as u32) & (r as u32) == r as u32),
+        LimitKind::Range => actual.len() == 2 && actual[0] <= required[0]
+            && actual[1] >= required[1],
+    }
+}
+
+fn format_feature_by_name(name: &str) -> Option<FormatFeatureFlags>
+{
+    #[allow(unused_mut)]
+    let mut features = vec![
+        ("FORMAT_FEATURE_SAMPLED_IMAGE_BIT", FORMAT_FEATURE_SAMPLED_IMAGE_BIT),
+        ("FORMAT_FEATURE_STORAGE_IMAGE_BIT", FORMAT_FEATURE_STORAGE_IMAGE_BIT),
+        ("FORMAT_FEATURE_STORAGE_IMAGE_ATOMIC_BIT", FORMAT_FEATURE_STORAGE_IMAGE_ATOMIC_BIT),
+        ("FORMAT_FEATURE_UNIFORM_TEXEL_BUFFER_BIT", FORMAT_FEATURE_UNIFORM_TEXEL_BUFFER_BIT),
+        ("FORMAT_FEATURE_STORAGE_TEXEL_BUFFER_BIT", FORMAT_FEATURE_STORAGE_TEXEL_BUFFER_BIT),
+        ("FORMAT_FEATURE_STORAGE_TEXEL_BUFFER_ATOMIC_BIT",
+         FORMAT_FEATURE_STORAGE_TEXEL_BUFFER_ATOMIC_BIT),
+        ("FORMAT_FEATURE_VERTEX_BUFFER_BIT", FORMAT_FEATURE_VERTEX_BUFFER_BIT),
+        ("FORMAT_FEATURE_COLOR_ATTACHMENT_BIT", FORMAT_FEATURE_COLOR_ATTACHMENT_BIT),
+        ("FORMAT_FEATURE_COLOR_ATTACHMENT_BLEND_BIT", FORMAT_FEATURE_COLOR_ATTACHMENT_BLEND_BIT),
+        ("FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT",
+         FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT),
+        ("FORMAT_FEATURE_BLIT_SRC_BIT", FORMAT_FEATURE_BLIT_SRC_BIT),
+        ("FORMAT_FEATURE_BLIT_DST_BIT", FORMAT_FEATURE_BLIT_DST_BIT),
+        ("FORMAT_FEATURE_SAMPLED_IMAGE_FILTER_LINEAR_BIT",
+         FORMAT_FEATURE_SAMPLED_IMAGE_FILTER_LINEAR_BIT),
+    ];
+    #[cfg(feature = "img_filter_cubic")]
+    {
+        features.push(("FORMAT_FEATURE_SAMPLED_IMAGE_FILTER_CUBIC_BIT_IMG",
+                       FORMAT_FEATURE_SAMPLED_IMAGE_FILTER_CUBIC_BIT_IMG));
+    }
+    #[cfg(feature = "khr_maintenance1")]
+    {
+        features.push(("FORMAT_FEATURE_TRANSFER_SRC_BIT", FORMAT_FEATURE_TRANSFER_SRC_BIT));
+        features.push(("FORMAT_FEATURE_TRANSFER_DST_BIT", FORMAT_FEATURE_TRANSFER_DST_BIT));
+    }
+    features.into_iter().find(|&(n, _)| n == name).map(|(_, flag)| flag)
+}
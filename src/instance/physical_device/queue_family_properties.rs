@@ -44,3 +44,52 @@ impl From<VkQueueFamilyProperties> for QueueFamilyProperties {
         }
     }
 }
+
+impl QueueFamilyProperties {
+    /// Whether the family has queues offering all of `flags`
+    pub fn supports(&self, flags: QueueFlags) -> bool
+    {
+        self.queue_count > 0 && self.queue_flags.contains(flags)
+    }
+
+    pub fn supports_graphics(&self) -> bool
+    {
+        self.supports(QUEUE_FLAGS_GRAPHICS_BIT)
+    }
+
+    pub fn supports_compute(&self) -> bool
+    {
+        self.supports(QUEUE_FLAGS_COMPUTE_BIT)
+    }
+
+    /// Graphics and compute queues support transfers whether or not they
+    /// report QUEUE_FLAGS_TRANSFER_BIT.  See vulkan specification, section
+    /// 4.1 Physical Devices
+    pub fn supports_transfer(&self) -> bool
+    {
+        self.queue_count > 0
+            && self.queue_flags.intersects(QUEUE_FLAGS_GRAPHICS_BIT | QUEUE_FLAGS_COMPUTE_BIT
+                                           | QUEUE_FLAGS_TRANSFER_BIT)
+    }
+
+    /// A family for transfers only, which usually maps to a DMA engine
+    pub fn is_dedicated_transfer(&self) -> bool
+    {
+        self.supports_transfer()
+            && !self.queue_flags.intersects(QUEUE_FLAGS_GRAPHICS_BIT | QUEUE_FLAGS_COMPUTE_BIT)
+    }
+
+    /// A compute family without graphics, whose work can overlap rendering
+    pub fn is_async_compute(&self) -> bool
+    {
+        self.supports_compute() && !self.queue_flags.contains(QUEUE_FLAGS_GRAPHICS_BIT)
+    }
+
+    /// Whether image transfers on this family must copy whole mip levels,
+    /// which is what a `min_image_transfer_granularity` of (0,0,0) means
+    pub fn transfers_whole_images_only(&self) -> bool
+    {
+        let g = &self.min_image_transfer_granularity;
+        g.width == 0 && g.height == 0 && g.depth == 0
+    }
+}
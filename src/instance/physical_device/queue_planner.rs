@@ -0,0 +1,251 @@
+use {Error, InstanceLoader, Extent3D};
+#[cfg(feature = "khr_surface")]
+use instance::surface::Surface;
+use instance::device::DeviceQueueCreateInfo;
+use super::{PhysicalDevice, QueueFamilyProperties};
+
+/// A queue chosen by a `QueuePlanner`: its family, and its index within the
+/// family
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueAssignment {
+    pub family_index: u32,
+    pub queue_index: u32,
+}
+
+/// The queues to create on a device, and what each of them is for.  Roles
+/// which could not be given a queue of their own share one with another role,
+/// so the same assignment may appear more than once.
+#[derive(Debug, Clone)]
+pub struct QueuePlan {
+    /// None on compute-only devices
+    pub graphics: Option<QueueAssignment>,
+    /// A queue which can present to the surface, if one was given.  This is
+    /// the graphics queue where possible.
+    pub present: Option<QueueAssignment>,
+    /// A queue in a compute family without graphics if there is one,
+    /// otherwise in the graphics family
+    pub compute: QueueAssignment,
+    /// A queue in a transfer-only family if there is one, otherwise in the
+    /// compute or graphics family
+    pub transfer: QueueAssignment,
+    /// The `min_image_transfer_granularity` of the transfer queue's family,
+    /// which image copies on that queue must respect
+    pub transfer_granularity: Extent3D,
+    /// What to pass when creating the device: one entry per family used
+    pub queue_create_infos: Vec<DeviceQueueCreateInfo>,
+}
+
+impl QueuePlan {
+    pub fn has_dedicated_transfer(&self) -> bool
+    {
+        self.transfer.family_index != self.compute.family_index
+            && self.graphics.map_or(true, |g| self.transfer.family_index != g.family_index)
+    }
+
+    /// Whether compute work can overlap rendering.  Always false without a
+    /// graphics queue.
+    pub fn has_async_compute(&self) -> bool
+    {
+        self.graphics.map_or(false, |g| self.compute.family_index != g.family_index)
+    }
+}
+
+/// Chooses queue families for graphics, presentation, async compute and
+/// transfers on a physical device.  Where a family has several queues, roles
+/// sharing the family get separate queues.  Compute-only devices are planned
+/// without a graphics queue.
+///
+/// ```ignore
+/// let plan = QueuePlanner::new()
+///     .surface(&surface)
+///     .plan(&loader, &physical_device)?;
+/// ```
+pub struct QueuePlanner<'a> {
+    max_transfer_granularity: Option<Extent3D>,
+    #[cfg(feature = "khr_surface")]
+    surface: Option<&'a Surface>,
+    #[cfg(not(feature = "khr_surface"))]
+    _surface: ::std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> QueuePlanner<'a> {
+    pub fn new() -> QueuePlanner<'a>
+    {
+        QueuePlanner {
+            max_transfer_granularity: None,
+            #[cfg(feature = "khr_surface")]
+            surface: None,
+            #[cfg(not(feature = "khr_surface"))]
+            _surface: ::std::marker::PhantomData,
+        }
+    }
+
+    /// Also plan a queue which can present to `surface`
+    #[cfg(feature = "khr_surface")]
+    pub fn surface(mut self, surface: &'a Surface) -> QueuePlanner<'a>
+    {
+        self.surface = Some(surface);
+        self
+    }
+
+    /// Only use a separate transfer family if its image transfer granularity
+    /// is no coarser than `granularity`, so that families which can only copy
+    /// whole mip levels are passed over
+    pub fn max_transfer_granularity(mut self, granularity: Extent3D) -> QueuePlanner<'a>
+    {
+        self.max_transfer_granularity = Some(granularity);
+        self
+    }
+
+    /// Plan the queues for `physical_device`, which must have a graphics or
+    /// compute queue family (and one which can present, if a surface was
+    /// given)
+    pub fn plan(&self, loader: &InstanceLoader, physical_device: &PhysicalDevice)
+                -> Result<QueuePlan, Error>
+    {
+        let families = physical_device.get_queue_family_properties(loader)?;
+
+        let (graphics_family, present_family) =
+            self.graphics_and_present_families(loader, physical_device, &families)?;
+
+        let compute_family = families.iter()
+            .position(|f| f.is_async_compute())
+            .map(|i| i as u32)
+            .or(graphics_family);
+        let compute_family = match compute_family {
+            Some(family) => family,
+            None => return Err(Error::General(
+                "No queue family supports graphics or compute".to_owned())),
+        };
+
+        let transfer_family = families.iter().enumerate()
+            .filter(|&(_, f)| f.is_dedicated_transfer() && self.granularity_acceptable(f))
+            .min_by_key(|&(_, f)| granularity_rank(f))
+            .map(|(i, _)| i as u32)
+            .unwrap_or_else(|| match graphics_family {
+                Some(graphics_family)
+                    if compute_family == graphics_family
+                    || !self.granularity_acceptable(&families[compute_family as usize]) =>
+                    graphics_family,
+                _ => compute_family,
+            });
+
+        let mut used: Vec<u32> = vec![0; families.len()];
+        let graphics = graphics_family.map(|family| allocate_queue(&families, &mut used, family));
+        let present = present_family.map(|family| {
+            match graphics {
+                Some(graphics) if graphics.family_index == family => graphics,
+                _ => allocate_queue(&families, &mut used, family),
+            }
+        });
+        let compute = allocate_queue(&families, &mut used, compute_family);
+        let transfer = allocate_queue(&families, &mut used, transfer_family);
+
+        let queue_create_infos = used.iter().enumerate()
+            .filter(|&(_, &count)| count > 0)
+            .map(|(family, &count)| DeviceQueueCreateInfo {
+                queue_family_index: family as u32,
+                queue_priorities: vec![1.0; count as usize],
+            })
+            .collect();
+
+        Ok(QueuePlan {
+            graphics: graphics,
+            present: present,
+            compute: compute,
+            transfer: transfer,
+            transfer_granularity: families[transfer_family as usize]
+                .min_image_transfer_granularity.clone(),
+            queue_create_infos: queue_create_infos,
+        })
+    }
+
+    fn granularity_acceptable(&self, family: &QueueFamilyProperties) -> bool
+    {
+        match self.max_transfer_granularity {
+            None => true,
+            Some(ref max) => {
+                let g = &family.min_image_transfer_granularity;
+                !family.transfers_whole_images_only()
+                    && g.width <= max.width && g.height <= max.height && g.depth <= max.depth
+            },
+        }
+    }
+
+    // Prefer a family which does both, so that rendering and presenting need
+    // no ownership transfers
+    #[cfg(feature = "khr_surface")]
+    fn graphics_and_present_families(&self, loader: &InstanceLoader,
+                                     physical_device: &PhysicalDevice,
+                                     families: &[QueueFamilyProperties])
+                                     -> Result<(Option<u32>, Option<u32>), Error>
+    {
+        let graphics_family = first_graphics_family(families);
+        let surface = match self.surface {
+            Some(surface) => surface,
+            None => return Ok((graphics_family, None)),
+        };
+
+        let mut present_family: Option<u32> = None;
+        for (index, family) in families.iter().enumerate() {
+            let index = index as u32;
+            if family.queue_count == 0
+                || !physical_device.get_surface_support(loader, index, surface)?
+            {
+                continue;
+            }
+            if family.supports_graphics() {
+                return Ok((Some(index), Some(index)));
+            }
+            if present_family.is_none() {
+                present_family = Some(index);
+            }
+        }
+        match present_family {
+            Some(present_family) => Ok((graphics_family, Some(present_family))),
+            None => Err(Error::General(
+                "No queue family can present to the surface".to_owned())),
+        }
+    }
+
+    #[cfg(not(feature = "khr_surface"))]
+    fn graphics_and_present_families(&self, _loader: &InstanceLoader,
+                                     _physical_device: &PhysicalDevice,
+                                     families: &[QueueFamilyProperties])
+                                     -> Result<(Option<u32>, Option<u32>), Error>
+    {
+        Ok((first_graphics_family(families), None))
+    }
+}
+
+fn first_graphics_family(families: &[QueueFamilyProperties]) -> Option<u32>
+{
+    families.iter().position(|f| f.supports_graphics()).map(|i| i as u32)
+}
+
+// Finer granularities first; whole-mip-level-only families last
+fn granularity_rank(family: &QueueFamilyProperties) -> u64
+{
+    if family.transfers_whole_images_only() {
+        return ::std::u64::MAX;
+    }
+    let g = &family.min_image_transfer_granularity;
+    g.width as u64 * g.height as u64 * g.depth as u64
+}
+
+// The next unused queue of the family, or its first queue if all are in use
+fn allocate_queue(families: &[QueueFamilyProperties], used: &mut [u32], family: u32)
+                  -> QueueAssignment
+{
+    let used = &mut used[family as usize];
+    let queue_index = if *used < families[family as usize].queue_count {
+        *used += 1;
+        *used - 1
+    } else {
+        0
+    };
+    QueueAssignment {
+        family_index: family,
+        queue_index: queue_index,
+    }
+}
@@ -0,0 +1,313 @@
+use std::fmt;
+use {Error, Version, InstanceLoader, Instance};
+use error::{MissingName, NameKind};
+#[cfg(feature = "khr_surface")]
+use instance::surface::Surface;
+use super::{PhysicalDevice, PhysicalDeviceProperties, PhysicalDeviceType,
+            PhysicalDeviceFeatures, QueueFamilyProperties, QueueFlags};
+
+/// Why a physical device did not meet the requirements of a
+/// `PhysicalDeviceSelector`
+#[derive(Debug, Clone)]
+pub enum RejectionReason {
+    ApiVersion { required: Version, supported: Version },
+    MissingExtension(MissingName),
+    MissingFeatures(Vec<&'static str>),
+    /// No queue family offers all of these capabilities
+    NoQueueFamily(QueueFlags),
+    /// No queue family can present to the surface
+    NoPresentQueueFamily,
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RejectionReason::ApiVersion { ref required, ref supported } =>
+                write!(f, "supports Vulkan {}.{} but {}.{} is required",
+                       supported.0, supported.1, required.0, required.1),
+            RejectionReason::MissingExtension(ref missing) =>
+                write!(f, "missing {}", missing),
+            RejectionReason::MissingFeatures(ref names) =>
+                write!(f, "missing features {}", names.join(", ")),
+            RejectionReason::NoQueueFamily(flags) =>
+                write!(f, "no queue family supports {:?}", flags),
+            RejectionReason::NoPresentQueueFamily =>
+                write!(f, "no queue family can present to the surface"),
+        }
+    }
+}
+
+/// A physical device which was rejected, and every reason it was rejected
+#[derive(Debug, Clone)]
+pub struct DeviceRejection {
+    pub device_name: String,
+    pub device_type: PhysicalDeviceType,
+    pub reasons: Vec<RejectionReason>,
+}
+
+impl fmt::Display for DeviceRejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({:?}): ", self.device_name, self.device_type)?;
+        for (i, reason) in self.reasons.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// The physical device chosen by a `PhysicalDeviceSelector`
+pub struct SelectedPhysicalDevice {
+    pub physical_device: PhysicalDevice,
+    pub properties: PhysicalDeviceProperties,
+    pub queue_family_properties: Vec<QueueFamilyProperties>,
+    /// A queue family index for each `require_queue()` call, in order
+    pub queue_family_indices: Vec<u32>,
+    /// A queue family which can present to the surface, if one was given.
+    /// This is the same as a required queue family where possible.
+    pub present_queue_family_index: Option<u32>,
+}
+
+/// Chooses the best physical device meeting a set of requirements.  Devices
+/// are ranked by the position of their type in the preference order (types
+/// not listed come last), and then by the order the implementation enumerates
+/// them in.
+///
+/// ```ignore
+/// let selected = PhysicalDeviceSelector::new()
+///     .require_queue(QUEUE_FLAGS_GRAPHICS_BIT)
+///     .require_extension("VK_KHR_swapchain")
+///     .select(&instance, &loader)?;
+/// ```
+pub struct PhysicalDeviceSelector<'a> {
+    queues: Vec<QueueFlags>,
+    extensions: Vec<String>,
+    features: Option<PhysicalDeviceFeatures>,
+    min_api_version: Option<Version>,
+    #[cfg(feature = "khr_surface")]
+    surface: Option<&'a Surface>,
+    preference: Vec<PhysicalDeviceType>,
+    #[cfg(not(feature = "khr_surface"))]
+    _surface: ::std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> PhysicalDeviceSelector<'a> {
+    /// A selector accepting any device, preferring discrete, then
+    /// integrated, then virtual GPUs, then CPUs
+    pub fn new() -> PhysicalDeviceSelector<'a>
+    {
+        PhysicalDeviceSelector {
+            queues: Vec::new(),
+            extensions: Vec::new(),
+            features: None,
+            min_api_version: None,
+            #[cfg(feature = "khr_surface")]
+            surface: None,
+            preference: vec![PhysicalDeviceType::DiscreteGPU,
+                             PhysicalDeviceType::IntegratedGPU,
+                             PhysicalDeviceType::VirtualGPU,
+                             PhysicalDeviceType::CPU],
+            #[cfg(not(feature = "khr_surface"))]
+            _surface: ::std::marker::PhantomData,
+        }
+    }
+
+    /// Require a queue family with all of the given capabilities
+    pub fn require_queue(mut self, flags: QueueFlags) -> PhysicalDeviceSelector<'a>
+    {
+        self.queues.push(flags);
+        self
+    }
+
+    pub fn require_extension(mut self, extension_name: &str) -> PhysicalDeviceSelector<'a>
+    {
+        self.extensions.push(extension_name.to_owned());
+        self
+    }
+
+    pub fn require_features(mut self, features: PhysicalDeviceFeatures)
+                            -> PhysicalDeviceSelector<'a>
+    {
+        self.features = Some(features);
+        self
+    }
+
+    /// Require the driver to support at least this Vulkan version (ignoring
+    /// the patch version)
+    pub fn min_api_version(mut self, api_version: Version) -> PhysicalDeviceSelector<'a>
+    {
+        self.min_api_version = Some(api_version);
+        self
+    }
+
+    /// Require a queue family which can present to `surface`
+    #[cfg(feature = "khr_surface")]
+    pub fn surface(mut self, surface: &'a Surface) -> PhysicalDeviceSelector<'a>
+    {
+        self.surface = Some(surface);
+        self
+    }
+
+    /// Replace the preference order over device types, most preferred first
+    pub fn prefer(mut self, device_types: Vec<PhysicalDeviceType>)
+                  -> PhysicalDeviceSelector<'a>
+    {
+        self.preference = device_types;
+        self
+    }
+
+    /// Choose the best device, or return `Error::NoSuitablePhysicalDevice`
+    /// explaining why each device was rejected.
+    pub fn select(&self, instance: &Instance, loader: &InstanceLoader)
+                  -> Result<SelectedPhysicalDevice, Error>
+    {
+        let mut best: Option<(usize, SelectedPhysicalDevice)> = None;
+        let mut rejections: Vec<DeviceRejection> = Vec::new();
+
+        for physical_device in instance.enumerate_physical_devices(loader)? {
+            match self.check(loader, physical_device)? {
+                Ok(selected) => {
+                    let rank = self.rank(selected.properties.device_type);
+                    let better = match best {
+                        Some((best_rank, _)) => rank < best_rank,
+                        None => true,
+                    };
+                    if better {
+                        best = Some((rank, selected));
+                    }
+                },
+                Err(rejection) => rejections.push(rejection),
+            }
+        }
+
+        match best {
+            Some((_, selected)) => Ok(selected),
+            None => Err(Error::NoSuitablePhysicalDevice(rejections)),
+        }
+    }
+
+    fn rank(&self, device_type: PhysicalDeviceType) -> usize
+    {
+        self.preference.iter()
+            .position(|&t| t == device_type)
+            .unwrap_or(self.preference.len())
+    }
+
+    // Check one device, collecting every reason it fails rather than stopping
+    // at the first.
+    fn check(&self, loader: &InstanceLoader, physical_device: PhysicalDevice)
+             -> Result<Result<SelectedPhysicalDevice, DeviceRejection>, Error>
+    {
+        let properties = physical_device.get_properties(loader)?;
+        let mut reasons: Vec<RejectionReason> = Vec::new();
+
+        if let Some(ref required) = self.min_api_version {
+            if !properties.supports_api_version(required) {
+                reasons.push(RejectionReason::ApiVersion {
+                    required: *required,
+                    supported: properties.api_version,
+                });
+            }
+        }
+
+        if !self.extensions.is_empty() {
+            let available: Vec<String> = physical_device.get_extension_properties(loader, None)?
+                .into_iter()
+                .map(|ep| ep.extension_name)
+                .collect();
+            reasons.extend(MissingName::find(NameKind::DeviceExtension,
+                                             &self.extensions, &available)
+                           .into_iter()
+                           .map(RejectionReason::MissingExtension));
+        }
+
+        if let Some(ref features) = self.features {
+            let missing = features.missing_from(&physical_device.get_features(loader)?);
+            if !missing.is_empty() {
+                reasons.push(RejectionReason::MissingFeatures(missing));
+            }
+        }
+
+        let queue_family_properties = physical_device.get_queue_family_properties(loader)?;
+        let mut queue_family_indices: Vec<u32> = Vec::new();
+        for &flags in &self.queues {
+            match queue_family_properties.iter()
+                .position(|qfp| qfp.queue_count > 0 && qfp.queue_flags.contains(flags))
+            {
+                Some(index) => queue_family_indices.push(index as u32),
+                None => reasons.push(RejectionReason::NoQueueFamily(flags)),
+            }
+        }
+
+        let present_queue_family_index =
+            self.present_queue_family(loader, &physical_device, &queue_family_properties,
+                                      &queue_family_indices)?;
+        if self.has_surface() && present_queue_family_index.is_none() {
+            reasons.push(RejectionReason::NoPresentQueueFamily);
+        }
+
+        if !reasons.is_empty() {
+            return Ok(Err(DeviceRejection {
+                device_name: properties.device_name,
+                device_type: properties.device_type,
+                reasons: reasons,
+            }));
+        }
+
+        Ok(Ok(SelectedPhysicalDevice {
+            physical_device: physical_device,
+            properties: properties,
+            queue_family_properties: queue_family_properties,
+            queue_family_indices: queue_family_indices,
+            present_queue_family_index: present_queue_family_index,
+        }))
+    }
+
+    #[cfg(feature = "khr_surface")]
+    fn has_surface(&self) -> bool
+    {
+        self.surface.is_some()
+    }
+
+    #[cfg(not(feature = "khr_surface"))]
+    fn has_surface(&self) -> bool
+    {
+        false
+    }
+
+    // Prefer presenting from a family we already chose, so that fewer queues
+    // are needed.
+    #[cfg(feature = "khr_surface")]
+    fn present_queue_family(&self, loader: &InstanceLoader, physical_device: &PhysicalDevice,
+                            queue_family_properties: &[QueueFamilyProperties],
+                            chosen: &[u32])
+                            -> Result<Option<u32>, Error>
+    {
+        let surface = match self.surface {
+            Some(surface) => surface,
+            None => return Ok(None),
+        };
+        for &index in chosen {
+            if physical_device.get_surface_support(loader, index, surface)? {
+                return Ok(Some(index));
+            }
+        }
+        for index in 0..queue_family_properties.len() as u32 {
+            if physical_device.get_surface_support(loader, index, surface)? {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+
+    #[cfg(not(feature = "khr_surface"))]
+    fn present_queue_family(&self, _loader: &InstanceLoader, _physical_device: &PhysicalDevice,
+                            _queue_family_properties: &[QueueFamilyProperties],
+                            _chosen: &[u32])
+                            -> Result<Option<u32>, Error>
+    {
+        Ok(None)
+    }
+}
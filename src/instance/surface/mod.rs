@@ -16,6 +16,7 @@ use std::ptr;
 use vks::*;
 use winit;
 use super::{Instance, InstanceLoader};
+use allocator::vk_allocator;
 use Error;
 
 #[cfg(windows)]
@@ -29,11 +30,25 @@ pub type CompositeAlphaFlags = VkCompositeAlphaFlagsKHR; // u32
 
 pub struct Surface {
     surface: VkSurfaceKHR,
-    instance: VkInstance, // copy; do not drop from here.
-    loader: InstanceLoader
+    instance: Instance, // keeps the instance alive until we are destroyed
+    loader: InstanceLoader,
 }
 
 impl Surface {
+    /// Take ownership of a surface created by the mock driver.  It will be
+    /// destroyed when dropped, so it must have been created from `instance`.
+    #[cfg(feature = "mock")]
+    pub(crate) unsafe fn from_vk(instance: &Instance, loader: InstanceLoader,
+                                 surface: VkSurfaceKHR)
+                                 -> Surface
+    {
+        Surface {
+            surface: surface,
+            instance: instance.clone(),
+            loader: loader,
+        }
+    }
+
     pub fn inner(&self) -> VkSurfaceKHR
     {
         self.surface
@@ -44,9 +59,9 @@ impl Drop for Surface {
     fn drop(&mut self) {
         unsafe {
             (self.loader.0.khr_surface.vkDestroySurfaceKHR)(
-                self.instance,
+                self.instance.inner(),
                 self.surface,
-                ptr::null());
+                vk_allocator(&self.instance.allocator()));
         }
     }
 }
@@ -58,6 +73,9 @@ impl Instance {
     {
         use winit::os::unix::WindowExt;
 
+        loader.require_extension(VK_KHR_SURFACE_EXTENSION_NAME_STR)?;
+        loader.require_extension(VK_KHR_XLIB_SURFACE_EXTENSION_NAME_STR)?;
+
         let x11_display = window.get_xlib_display().unwrap();
         let x11_window = window.get_xlib_window().unwrap();
         let create_info = VkXlibSurfaceCreateInfoKHR {
@@ -70,16 +88,16 @@ impl Instance {
         let surface = unsafe {
             let mut surface: VkSurfaceKHR = mem::uninitialized();
             vk_try!((loader.0.khr_xlib_surface.vkCreateXlibSurfaceKHR)(
-                self.0,
+                self.inner(),
                 &create_info,
-                ptr::null(), // allocator
-                &mut surface));
+                vk_allocator(&self.allocator()),
+                &mut surface), "vkCreateXlibSurfaceKHR", "Instance::create_surface");
             surface
         };
         Ok(Surface {
             surface: surface,
-            instance: self.0.clone(),
-            loader: loader
+            instance: self.clone(),
+            loader: loader,
         })
     }
 
@@ -89,6 +107,9 @@ impl Instance {
     {
         use winit::os::windows::WindowExt;
 
+        loader.require_extension(VK_KHR_SURFACE_EXTENSION_NAME_STR)?;
+        loader.require_extension(VK_KHR_WIN32_SURFACE_EXTENSION_NAME_STR)?;
+
         let hwnd = window.get_hwnd() as *mut winapi::windef::HWND__;
         let hinstance = user32::GetWindow(hwnd, 0) as *const ();
         let create_info = VkWin32SurfaceCreateInfoKHR {
@@ -101,16 +122,16 @@ impl Instance {
         let surface = unsafe {
             let mut surface: VkSurfaceKHR = mem::uninitialized();
             vk_try!((loader.0.khr_win32_surface.vkCreateWin32SurfaceKHR)(
-                self.0,
+                self.inner(),
                 &create_info,
-                ptr::null(), // allocator
-                &mut surface));
+                vk_allocator(&self.allocator()),
+                &mut surface), "vkCreateWin32SurfaceKHR", "Instance::create_surface");
             surface
         };
         Ok(Surface {
             surface: surface,
-            instance: self.0.clone(),
-            loader: loader
+            instance: self.clone(),
+            loader: loader,
         })
     }
 
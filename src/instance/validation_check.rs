@@ -0,0 +1,18 @@
+#[cfg(feature = "ext_validation_flags")]
+use vks::*;
+
+/// Validation checks which can be disabled through VK_EXT_validation_flags.
+/// See vulkan specification, section 30.2.3 Validation Flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum ValidationCheck {
+    All = 0,
+    Shaders = 1,
+}
+
+#[cfg(feature = "ext_validation_flags")]
+impl From<ValidationCheck> for VkValidationCheckEXT {
+    fn from(c: ValidationCheck) -> VkValidationCheckEXT {
+        VkValidationCheckEXT::from_raw(c as u32)
+    }
+}
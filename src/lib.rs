@@ -5,7 +5,11 @@ extern crate image as imageformat;
 extern crate libc;
 #[cfg(windows)] extern crate user32;
 #[cfg(windows)] extern crate winapi;
+#[cfg(windows)] extern crate kernel32;
 #[macro_use] extern crate bitflags;
+#[cfg(feature = "profile")] #[macro_use] extern crate serde_derive;
+#[cfg(feature = "profile")] extern crate serde_json;
+#[cfg(feature = "profile")] extern crate toml;
 
 // Include our macros early
 include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/macros.rs"));
@@ -14,7 +18,10 @@ pub mod error;
 pub use error::Error;
 
 pub mod version;
-pub use version::Version;
+pub use version::{Version, HEADER_VERSION};
+
+pub mod allocator;
+pub use allocator::{HostAllocator, Allocator};
 
 pub mod instance;
 pub use instance::{InstanceLoader, Instance};
@@ -25,6 +32,11 @@ pub use format::*;
 pub mod image;
 pub use image::*;
 
+pub mod info;
+
+#[cfg(feature = "mock")]
+pub mod mock;
+
 use std::ptr;
 use std::mem;
 use std::str;
@@ -32,6 +44,7 @@ use std::ffi::{CString, CStr};
 use vks::*;
 
 /// See vulkan specification, section 30.2 Extensions
+#[derive(Debug, Clone)]
 pub struct ExtensionProperties {
     pub extension_name: String,
     pub spec_version: u32
@@ -55,7 +68,8 @@ impl ExtensionProperties {
 
 /// See vulkan specification, section 30.2 Extensions.
 /// Despite what the name implies, this returns a Vec not an Iterator.
-pub fn enumerate_instance_extension_properties(layer_name: Option<&str>)
+pub fn enumerate_instance_extension_properties(loader: &InstanceLoader,
+                                               layer_name: Option<&str>)
     -> Result<Vec<ExtensionProperties>, Error>
 {
     let layer_name_cstring: Option<CString> = match layer_name {
@@ -69,22 +83,22 @@ pub fn enumerate_instance_extension_properties(layer_name: Option<&str>)
 
     // Call once to get the property count
     let mut property_count: u32 = 0;
-    vk_try!(unsafe { vkEnumerateInstanceExtensionProperties(
+    vk_try!(unsafe { (loader.0.core_null_instance.vkEnumerateInstanceExtensionProperties)(
         p_layer_name,
         &mut property_count,
         ptr::null_mut()
-    )});
+    )}, "vkEnumerateInstanceExtensionProperties", "enumerate_instance_extension_properties");
 
     // Prepare room for the output
     let capacity: usize = property_count as usize;
     let mut properties: Vec<VkExtensionProperties> = Vec::with_capacity(capacity);
 
     // Call again to get the data
-    vk_try!(unsafe { vkEnumerateInstanceExtensionProperties(
+    vk_try_status!(unsafe { (loader.0.core_null_instance.vkEnumerateInstanceExtensionProperties)(
         p_layer_name,
         &mut property_count,
         properties.as_mut_ptr()
-    )});
+    )}, "vkEnumerateInstanceExtensionProperties", "enumerate_instance_extension_properties");
 
     // Trust the data now in the properties vector
     let properties = unsafe {
@@ -102,6 +116,7 @@ pub fn enumerate_instance_extension_properties(layer_name: Option<&str>)
 }
 
 /// See vulkan specification, section 30.1 Layers
+#[derive(Debug, Clone)]
 pub struct LayerProperties {
     pub layer_name: String,
     pub spec_version: u32,
@@ -135,24 +150,25 @@ impl LayerProperties {
 
 /// See vulkan specification, section 30.1 Layers.
 /// Despite what the name implies, this returns a Vec not an Iterator.
-pub fn enumerate_instance_layer_properties() -> Result<Vec<LayerProperties>, Error>
+pub fn enumerate_instance_layer_properties(loader: &InstanceLoader)
+                                           -> Result<Vec<LayerProperties>, Error>
 {
     // Call once to get the property count
     let mut property_count: u32 = 0;
-    vk_try!(unsafe { vkEnumerateInstanceLayerProperties(
+    vk_try!(unsafe { (loader.0.core_null_instance.vkEnumerateInstanceLayerProperties)(
         &mut property_count,
         ptr::null_mut()
-    )});
+    )}, "vkEnumerateInstanceLayerProperties", "enumerate_instance_layer_properties");
 
     // Prepare room for the output
     let capacity: usize = property_count as usize;
     let mut properties: Vec<VkLayerProperties> = Vec::with_capacity(capacity);
 
     // Call again to get the data
-    vk_try!(unsafe { vkEnumerateInstanceLayerProperties(
+    vk_try_status!(unsafe { (loader.0.core_null_instance.vkEnumerateInstanceLayerProperties)(
         &mut property_count,
         properties.as_mut_ptr()
-    )});
+    )}, "vkEnumerateInstanceLayerProperties", "enumerate_instance_layer_properties");
 
     // Trust the data now in the properties vector
     let properties = unsafe {
@@ -1,11 +1,40 @@
-
+// Return an `Error::Vulkan` unless the call returns VK_SUCCESS.  The entry
+// point name follows the call, and the sarek method may be given after it.
 macro_rules! vk_try {
-    ( $e:expr ) => {
+    ( $e:expr, $entry_point:expr ) => {
         let result = $e;
         if result != VK_SUCCESS {
-            return Err(From::from(result));
+            return Err(From::from(::error::VulkanError::new(result, $entry_point, None)));
         }
-    }
+    };
+    ( $e:expr, $entry_point:expr, $method:expr ) => {
+        let result = $e;
+        if result != VK_SUCCESS {
+            return Err(From::from(::error::VulkanError::new(
+                result, $entry_point, Some($method))));
+        }
+    };
+}
+
+// Like vk_try!, but success codes other than VK_SUCCESS (such as VK_INCOMPLETE
+// or VK_SUBOPTIMAL_KHR) are not errors; the expression evaluates to the
+// `Status` instead.
+macro_rules! vk_try_status {
+    ( $e:expr, $entry_point:expr ) => {{
+        let result = $e;
+        match ::error::Status::from_vk(result) {
+            Some(status) => status,
+            None => return Err(From::from(::error::VulkanError::new(result, $entry_point, None))),
+        }
+    }};
+    ( $e:expr, $entry_point:expr, $method:expr ) => {{
+        let result = $e;
+        match ::error::Status::from_vk(result) {
+            Some(status) => status,
+            None => return Err(From::from(::error::VulkanError::new(
+                result, $entry_point, Some($method)))),
+        }
+    }};
 }
 
 #[macro_export]
@@ -0,0 +1,1310 @@
+
+//! A pure-rust mock Vulkan driver, so that sarek can be tested without a GPU.
+//!
+//! Configure a `MockDriver`, then use `MockDriver::loader()` to get an
+//! `InstanceLoader` which dispatches into the mock instead of a real Vulkan
+//! implementation.  Physical devices, layers and extensions are whatever you
+//! configure, and any entry point can be made to fail with `MockDriver::fail()`.
+//!
+//! Only the entry points that sarek uses are implemented.  Requesting any other
+//! function returns a null function pointer, as a real driver would for an
+//! unsupported function.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::ptr;
+use std::slice;
+use std::sync::{Arc, Mutex, MutexGuard};
+use libc::{c_char, c_void};
+use vks::*;
+
+use {InstanceLoader, Version, Format, FormatProperties};
+use image::{ImageTiling, ImageFormatProperties, SparseImageFormatProperties};
+use instance::physical_device::{PhysicalDeviceProperties, PhysicalDeviceFeatures,
+                                PhysicalDeviceType, PhysicalDeviceMemoryProperties,
+                                QueueFamilyProperties, ExtensionProperties,
+                                QUEUE_FLAGS_GRAPHICS_BIT, QUEUE_FLAGS_COMPUTE_BIT,
+                                QUEUE_FLAGS_TRANSFER_BIT};
+use instance::physical_device::{MemoryType, MemoryHeap, MEMORY_HEAP_DEVICE_LOCAL_BIT,
+                                MEMORY_PROPERTY_DEVICE_LOCAL_BIT,
+                                MEMORY_PROPERTY_HOST_VISIBLE_BIT,
+                                MEMORY_PROPERTY_HOST_COHERENT_BIT};
+#[cfg(feature = "khr_surface")]
+use instance::surface::{SurfaceFormat, SurfaceCapabilities, PresentMode, COLORSPACE_SRGB_NONLINEAR};
+
+thread_local! {
+    // The driver that global (instance-less) entry points dispatch to.  Set by
+    // MockDriver::loader().
+    static CURRENT_DRIVER: RefCell<Option<MockDriver>> = RefCell::new(None);
+}
+
+/// A layer offered by the mock driver
+#[derive(Debug, Clone)]
+pub struct MockLayer {
+    pub layer_name: String,
+    pub description: String,
+    pub extensions: Vec<ExtensionProperties>,
+}
+
+/// A physical device offered by the mock driver.  Every field may be modified
+/// before the device is added to a `MockDriver`.
+#[derive(Debug, Clone)]
+pub struct MockPhysicalDevice {
+    pub properties: PhysicalDeviceProperties,
+    pub features: PhysicalDeviceFeatures,
+    pub queue_families: Vec<QueueFamilyProperties>,
+    pub memory_properties: PhysicalDeviceMemoryProperties,
+    pub extensions: Vec<ExtensionProperties>,
+    pub layers: Vec<MockLayer>,
+    /// Formats not listed here report no supported features
+    pub format_properties: Vec<(Format, FormatProperties)>,
+    /// Image formats and tilings not listed here are reported as unsupported,
+    /// whatever the usage and flags
+    pub image_format_properties: Vec<(Format, ImageTiling, ImageFormatProperties)>,
+    /// Formats not listed here do not support sparse images
+    pub sparse_image_format_properties: Vec<(Format, SparseImageFormatProperties)>,
+    /// Queue family indices which can present to any surface
+    #[cfg(feature = "khr_surface")]
+    pub present_queue_families: Vec<u32>,
+    #[cfg(feature = "khr_surface")]
+    pub surface_formats: Vec<SurfaceFormat>,
+    #[cfg(feature = "khr_surface")]
+    pub surface_capabilities: SurfaceCapabilities,
+    #[cfg(feature = "khr_surface")]
+    pub present_modes: Vec<PresentMode>,
+    #[cfg(feature = "khr_push_descriptor")]
+    pub max_push_descriptors: u32,
+}
+
+impl MockPhysicalDevice {
+    /// A device with a single graphics/compute/transfer queue family, one
+    /// device local heap with a device local and a host visible memory type, no
+    /// features, and no extensions.
+    pub fn new(device_name: &str, device_type: PhysicalDeviceType) -> MockPhysicalDevice
+    {
+        let mut properties: PhysicalDeviceProperties = From::from(
+            VkPhysicalDeviceProperties::default());
+        properties.api_version = Version(1, 0, 3);
+        properties.device_type = device_type;
+        properties.device_name = device_name.to_owned();
+
+        let mut queue_family: QueueFamilyProperties = From::from(
+            unsafe { mem::zeroed::<VkQueueFamilyProperties>() });
+        queue_family.queue_flags = QUEUE_FLAGS_GRAPHICS_BIT | QUEUE_FLAGS_COMPUTE_BIT
+            | QUEUE_FLAGS_TRANSFER_BIT;
+        queue_family.queue_count = 1;
+        queue_family.min_image_transfer_granularity = VkExtent3D {
+            width: 1, height: 1, depth: 1
+        };
+
+        MockPhysicalDevice {
+            properties: properties,
+            features: Default::default(),
+            queue_families: vec![queue_family],
+            memory_properties: PhysicalDeviceMemoryProperties {
+                memory_types: vec![
+                    MemoryType {
+                        property_flags: MEMORY_PROPERTY_DEVICE_LOCAL_BIT,
+                        heap_index: 0,
+                    },
+                    MemoryType {
+                        property_flags: MEMORY_PROPERTY_HOST_VISIBLE_BIT
+                            | MEMORY_PROPERTY_HOST_COHERENT_BIT,
+                        heap_index: 0,
+                    },
+                ],
+                memory_heaps: vec![
+                    MemoryHeap {
+                        size: 256 * 1024 * 1024,
+                        flags: MEMORY_HEAP_DEVICE_LOCAL_BIT,
+                    },
+                ],
+            },
+            extensions: Vec::new(),
+            layers: Vec::new(),
+            format_properties: Vec::new(),
+            image_format_properties: Vec::new(),
+            sparse_image_format_properties: Vec::new(),
+            #[cfg(feature = "khr_surface")]
+            present_queue_families: vec![0],
+            #[cfg(feature = "khr_surface")]
+            surface_formats: vec![SurfaceFormat {
+                format: Format::B8g8r8a8Unorm,
+                color_space: COLORSPACE_SRGB_NONLINEAR,
+            }],
+            #[cfg(feature = "khr_surface")]
+            surface_capabilities: {
+                let mut vk: VkSurfaceCapabilitiesKHR = unsafe { mem::zeroed() };
+                vk.minImageCount = 2;
+                vk.maxImageCount = 8;
+                vk.currentExtent = VkExtent2D { width: 640, height: 480 };
+                vk.minImageExtent = VkExtent2D { width: 1, height: 1 };
+                vk.maxImageExtent = VkExtent2D { width: 4096, height: 4096 };
+                vk.maxImageArrayLayers = 1;
+                From::from(vk)
+            },
+            #[cfg(feature = "khr_surface")]
+            present_modes: vec![PresentMode::Fifo],
+            #[cfg(feature = "khr_push_descriptor")]
+            max_push_descriptors: 32,
+        }
+    }
+
+    pub fn with_extension(mut self, extension_name: &str) -> MockPhysicalDevice
+    {
+        self.extensions.push(ExtensionProperties {
+            extension_name: extension_name.to_owned(),
+            spec_version: 1,
+        });
+        self
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    instance_extensions: Vec<ExtensionProperties>,
+    instance_layers: Vec<MockLayer>,
+    physical_devices: Vec<MockPhysicalDevice>,
+    failures: HashMap<String, VkResult>,
+    calls: Vec<String>,
+    submissions: Vec<MockSubmission>,
+    live_objects: usize,
+    next_handle: u64,
+    #[cfg(feature = "ext_debug_report")]
+    debug_callbacks: Vec<MockDebugCallback>,
+    #[cfg(feature = "ext_debug_marker")]
+    object_names: HashMap<u64, String>,
+    #[cfg(feature = "ext_debug_marker")]
+    object_tags: HashMap<u64, (u64, Vec<u8>)>,
+    #[cfg(feature = "ext_debug_marker")]
+    markers: Vec<String>,
+    #[cfg(feature = "ext_validation_flags")]
+    disabled_validation_checks: Vec<VkValidationCheckEXT>,
+}
+
+// A registered debug report callback.  The user data pointer is kept as an
+// integer so that the state stays Send.
+#[cfg(feature = "ext_debug_report")]
+#[derive(Clone)]
+struct MockDebugCallback {
+    handle: u64,
+    flags: VkDebugReportFlagsEXT,
+    callback: PFN_vkDebugReportCallbackEXT,
+    user_data: usize,
+}
+
+/// A batch of command buffers submitted to a queue
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockSubmission {
+    pub family_index: u32,
+    pub queue_index: u32,
+    pub command_buffer_count: u32,
+    pub wait_semaphore_count: u32,
+    pub signal_semaphore_count: u32,
+}
+
+/// A configurable mock Vulkan implementation.  Clones share the same state.
+#[derive(Clone)]
+pub struct MockDriver(Arc<Mutex<MockState>>);
+
+impl MockDriver {
+    /// A driver with no extensions, no layers and no physical devices
+    pub fn new() -> MockDriver
+    {
+        MockDriver(Arc::new(Mutex::new(Default::default())))
+    }
+
+    fn state(&self) -> MutexGuard<MockState>
+    {
+        self.0.lock().unwrap()
+    }
+
+    pub fn add_instance_extension(&self, extension_name: &str, spec_version: u32)
+    {
+        self.state().instance_extensions.push(ExtensionProperties {
+            extension_name: extension_name.to_owned(),
+            spec_version: spec_version,
+        });
+    }
+
+    pub fn add_instance_layer(&self, layer: MockLayer)
+    {
+        self.state().instance_layers.push(layer);
+    }
+
+    pub fn add_physical_device(&self, physical_device: MockPhysicalDevice)
+    {
+        self.state().physical_devices.push(physical_device);
+    }
+
+    /// Make every subsequent call to `entry_point` (e.g. "vkCreateDevice") fail
+    /// with `result`.  Entry points which return nothing cannot fail.
+    pub fn fail(&self, entry_point: &str, result: VkResult)
+    {
+        self.state().failures.insert(entry_point.to_owned(), result);
+    }
+
+    /// Stop injecting failures into `entry_point`
+    pub fn clear_failure(&self, entry_point: &str)
+    {
+        self.state().failures.remove(entry_point);
+    }
+
+    /// The names of the entry points called so far, in order
+    pub fn calls(&self) -> Vec<String>
+    {
+        self.state().calls.clone()
+    }
+
+    /// The batches submitted to any queue so far, in order
+    pub fn submissions(&self) -> Vec<MockSubmission>
+    {
+        self.state().submissions.clone()
+    }
+
+    /// The number of instances, devices and other objects which have been
+    /// created and not yet destroyed
+    pub fn live_objects(&self) -> usize
+    {
+        self.state().live_objects
+    }
+
+    /// Get a loader which dispatches into this driver.  This also makes this
+    /// driver the one used by global entry points (such as vkCreateInstance) on
+    /// the current thread.
+    pub fn loader(&self) -> InstanceLoader
+    {
+        CURRENT_DRIVER.with(|current| *current.borrow_mut() = Some(self.clone()));
+        let get_instance_proc_addr: unsafe extern "system" fn(VkInstance, *const c_char)
+                                                             -> VoidFunction
+            = get_instance_proc_addr;
+        InstanceLoader::from_get_instance_proc_addr(unsafe {
+            mem::transmute(get_instance_proc_addr)
+        })
+    }
+
+    // Record the call, and return the injected failure if there is one.
+    fn enter(&self, entry_point: &str) -> Option<VkResult>
+    {
+        let mut state = self.state();
+        state.calls.push(entry_point.to_owned());
+        state.failures.get(entry_point).cloned()
+    }
+
+    fn create_object(&self) -> u64
+    {
+        let mut state = self.state();
+        state.live_objects += 1;
+        state.next_handle += 1;
+        state.next_handle
+    }
+
+    fn destroy_object(&self)
+    {
+        self.state().live_objects -= 1;
+    }
+}
+
+fn current_driver() -> MockDriver
+{
+    CURRENT_DRIVER.with(|current| {
+        current.borrow().clone().expect("MockDriver::loader() was not called on this thread")
+    })
+}
+
+// Like a real driver, we make a host allocation for each object when given
+// allocation callbacks, so that they can be seen to be used.
+struct HostAllocation {
+    callbacks: VkAllocationCallbacks,
+    memory: *mut c_void,
+}
+
+unsafe fn host_allocate(p_allocator: *const VkAllocationCallbacks, size: usize,
+                        scope: VkSystemAllocationScope)
+                        -> Option<HostAllocation>
+{
+    if p_allocator.is_null() {
+        return None;
+    }
+    let callbacks: VkAllocationCallbacks = ptr::read(p_allocator);
+    let memory = (callbacks.pfnAllocation)(callbacks.pUserData, size, 8, scope);
+    Some(HostAllocation {
+        callbacks: callbacks,
+        memory: memory,
+    })
+}
+
+unsafe fn host_free(allocation: Option<HostAllocation>)
+{
+    if let Some(allocation) = allocation {
+        (allocation.callbacks.pfnFree)(allocation.callbacks.pUserData, allocation.memory);
+    }
+}
+
+// Dispatchable handles point at one of these
+struct MockInstance {
+    driver: MockDriver,
+    physical_devices: Vec<Box<MockPhysicalDeviceHandle>>,
+    allocation: Option<HostAllocation>,
+}
+
+struct MockPhysicalDeviceHandle {
+    driver: MockDriver,
+    index: usize,
+}
+
+struct MockDevice {
+    driver: MockDriver,
+    queues: Vec<Box<MockQueue>>,
+    allocation: Option<HostAllocation>,
+}
+
+struct MockQueue {
+    driver: MockDriver,
+    family_index: u32,
+    queue_index: u32,
+}
+
+unsafe fn instance<'a>(instance: VkInstance) -> &'a MockInstance
+{
+    &*(instance as *const MockInstance)
+}
+
+unsafe fn physical_device<'a>(physical_device: VkPhysicalDevice) -> &'a MockPhysicalDeviceHandle
+{
+    &*(physical_device as *const MockPhysicalDeviceHandle)
+}
+
+unsafe fn device<'a>(device: VkDevice) -> &'a MockDevice
+{
+    &*(device as *const MockDevice)
+}
+
+unsafe fn queue<'a>(queue: VkQueue) -> &'a MockQueue
+{
+    &*(queue as *const MockQueue)
+}
+
+impl MockPhysicalDeviceHandle {
+    fn config(&self) -> MockPhysicalDevice
+    {
+        self.driver.state().physical_devices[self.index].clone()
+    }
+}
+
+macro_rules! mock_try {
+    ($driver:expr, $name:expr) => {
+        if let Some(result) = $driver.enter($name) {
+            return result;
+        }
+    }
+}
+
+// Implements the two-call idiom: report the count if there is no output
+// array, otherwise write as many items as fit.
+unsafe fn fill<T>(items: Vec<T>, p_count: *mut u32, p_items: *mut T) -> VkResult
+{
+    if p_items.is_null() {
+        *p_count = items.len() as u32;
+        return VK_SUCCESS;
+    }
+    let total = items.len();
+    let mut written: usize = 0;
+    for item in items.into_iter().take(*p_count as usize) {
+        ptr::write(p_items.offset(written as isize), item);
+        written += 1;
+    }
+    *p_count = written as u32;
+    if written < total { VK_INCOMPLETE } else { VK_SUCCESS }
+}
+
+fn copy_str(dst: &mut [c_char], src: &str)
+{
+    let len = dst.len() - 1;
+    for (d, s) in dst.iter_mut().zip(src.bytes().take(len)) {
+        *d = s as c_char;
+    }
+}
+
+unsafe fn read_names(count: u32, names: *const *const c_char) -> Vec<String>
+{
+    (0..count as isize)
+        .map(|i| CStr::from_ptr(*names.offset(i)).to_string_lossy().into_owned())
+        .collect()
+}
+
+fn extension_to_vk(ep: &ExtensionProperties) -> VkExtensionProperties
+{
+    let mut vk: VkExtensionProperties = unsafe { mem::zeroed() };
+    copy_str(&mut vk.extensionName, &ep.extension_name);
+    vk.specVersion = ep.spec_version;
+    vk
+}
+
+fn layer_to_vk(layer: &MockLayer) -> VkLayerProperties
+{
+    let mut vk: VkLayerProperties = unsafe { mem::zeroed() };
+    copy_str(&mut vk.layerName, &layer.layer_name);
+    vk.specVersion = vk_make_version!(1, 0, 3);
+    vk.implementationVersion = 1;
+    copy_str(&mut vk.description, &layer.description);
+    vk
+}
+
+fn properties_to_vk(properties: &PhysicalDeviceProperties) -> VkPhysicalDeviceProperties
+{
+    let mut vk: VkPhysicalDeviceProperties = Default::default();
+    vk.apiVersion = properties.api_version.to_vk();
+    vk.driverVersion = properties.driver_version;
+    vk.vendorID = properties.vendor_id;
+    vk.deviceID = properties.device_id;
+    vk.deviceType = unsafe { mem::transmute(properties.device_type) };
+    copy_str(&mut vk.deviceName, &properties.device_name);
+    vk.pipelineCacheUUID = properties.pipeline_cache_uuid;
+    vk.limits = unsafe { mem::transmute(properties.limits.clone()) };
+    vk.sparseProperties = unsafe { mem::transmute(properties.sparse_properties.clone()) };
+    vk
+}
+
+fn memory_properties_to_vk(properties: &PhysicalDeviceMemoryProperties)
+                           -> VkPhysicalDeviceMemoryProperties
+{
+    let mut vk: VkPhysicalDeviceMemoryProperties = unsafe { mem::zeroed() };
+    vk.memoryTypeCount = properties.memory_types.len() as u32;
+    for (i, memory_type) in properties.memory_types.iter().enumerate() {
+        vk.memoryTypes[i] = memory_type.clone().into();
+    }
+    vk.memoryHeapCount = properties.memory_heaps.len() as u32;
+    for (i, memory_heap) in properties.memory_heaps.iter().enumerate() {
+        vk.memoryHeaps[i] = memory_heap.clone().into();
+    }
+    vk
+}
+
+fn image_format_properties_to_vk(properties: &ImageFormatProperties) -> VkImageFormatProperties
+{
+    VkImageFormatProperties {
+        maxExtent: properties.max_extent,
+        maxMipLevels: properties.max_mip_levels,
+        maxArrayLayers: properties.max_array_layers,
+        sampleCounts: properties.sample_counts,
+        maxResourceSize: properties.max_resource_size,
+    }
+}
+
+fn sparse_image_format_properties_to_vk(properties: &SparseImageFormatProperties)
+                                        -> VkSparseImageFormatProperties
+{
+    VkSparseImageFormatProperties {
+        aspectMask: properties.aspect_mask.into(),
+        imageGranularity: properties.image_granularity,
+        flags: VkSparseImageFormatFlags::from_bits(properties.flags.bits()).unwrap(),
+    }
+}
+
+// PFN_vkVoidFunction cannot be null, so the proc addr functions return this
+// instead.  It has the same representation, with `None` for a missing function.
+type VoidFunction = Option<unsafe extern "system" fn()>;
+
+fn function(f: *const c_void) -> VoidFunction
+{
+    unsafe { mem::transmute(f) }
+}
+
+unsafe extern "system" fn get_instance_proc_addr(_instance: VkInstance, p_name: *const c_char)
+                                                 -> VoidFunction
+{
+    let name = CStr::from_ptr(p_name).to_str().unwrap_or("");
+    function(match name {
+        "vkGetInstanceProcAddr" => get_instance_proc_addr as *const c_void,
+        "vkCreateInstance" => create_instance as *const c_void,
+        "vkEnumerateInstanceExtensionProperties" =>
+            enumerate_instance_extension_properties as *const c_void,
+        "vkEnumerateInstanceLayerProperties" =>
+            enumerate_instance_layer_properties as *const c_void,
+        "vkDestroyInstance" => destroy_instance as *const c_void,
+        "vkEnumeratePhysicalDevices" => enumerate_physical_devices as *const c_void,
+        "vkGetPhysicalDeviceProperties" => get_physical_device_properties as *const c_void,
+        "vkGetPhysicalDeviceFeatures" => get_physical_device_features as *const c_void,
+        "vkGetPhysicalDeviceQueueFamilyProperties" =>
+            get_physical_device_queue_family_properties as *const c_void,
+        "vkGetPhysicalDeviceMemoryProperties" =>
+            get_physical_device_memory_properties as *const c_void,
+        "vkGetPhysicalDeviceFormatProperties" =>
+            get_physical_device_format_properties as *const c_void,
+        "vkGetPhysicalDeviceImageFormatProperties" =>
+            get_physical_device_image_format_properties as *const c_void,
+        "vkGetPhysicalDeviceSparseImageFormatProperties" =>
+            get_physical_device_sparse_image_format_properties as *const c_void,
+        "vkEnumerateDeviceExtensionProperties" =>
+            enumerate_device_extension_properties as *const c_void,
+        "vkEnumerateDeviceLayerProperties" => enumerate_device_layer_properties as *const c_void,
+        "vkCreateDevice" => create_device as *const c_void,
+        "vkGetDeviceProcAddr" => get_device_proc_addr as *const c_void,
+        #[cfg(feature = "khr_get_physical_device_properties2")]
+        "vkGetPhysicalDeviceProperties2KHR" =>
+            get_physical_device_properties2_khr as *const c_void,
+        #[cfg(feature = "khr_get_physical_device_properties2")]
+        "vkGetPhysicalDeviceFeatures2KHR" => get_physical_device_features2_khr as *const c_void,
+        #[cfg(feature = "khr_get_physical_device_properties2")]
+        "vkGetPhysicalDeviceFormatProperties2KHR" =>
+            get_physical_device_format_properties2_khr as *const c_void,
+        #[cfg(feature = "khr_get_physical_device_properties2")]
+        "vkGetPhysicalDeviceImageFormatProperties2KHR" =>
+            get_physical_device_image_format_properties2_khr as *const c_void,
+        #[cfg(feature = "khr_get_physical_device_properties2")]
+        "vkGetPhysicalDeviceSparseImageFormatProperties2KHR" =>
+            get_physical_device_sparse_image_format_properties2_khr as *const c_void,
+        #[cfg(feature = "khr_get_physical_device_properties2")]
+        "vkGetPhysicalDeviceQueueFamilyProperties2KHR" =>
+            get_physical_device_queue_family_properties2_khr as *const c_void,
+        #[cfg(feature = "khr_get_physical_device_properties2")]
+        "vkGetPhysicalDeviceMemoryProperties2KHR" =>
+            get_physical_device_memory_properties2_khr as *const c_void,
+        #[cfg(feature = "khr_surface")]
+        "vkDestroySurfaceKHR" => destroy_surface_khr as *const c_void,
+        #[cfg(feature = "khr_surface")]
+        "vkGetPhysicalDeviceSurfaceSupportKHR" =>
+            get_physical_device_surface_support_khr as *const c_void,
+        #[cfg(feature = "khr_surface")]
+        "vkGetPhysicalDeviceSurfaceFormatsKHR" =>
+            get_physical_device_surface_formats_khr as *const c_void,
+        #[cfg(feature = "khr_surface")]
+        "vkGetPhysicalDeviceSurfaceCapabilitiesKHR" =>
+            get_physical_device_surface_capabilities_khr as *const c_void,
+        #[cfg(feature = "khr_surface")]
+        "vkGetPhysicalDeviceSurfacePresentModesKHR" =>
+            get_physical_device_surface_present_modes_khr as *const c_void,
+        #[cfg(feature = "ext_debug_report")]
+        "vkCreateDebugReportCallbackEXT" => create_debug_report_callback_ext as *const c_void,
+        #[cfg(feature = "ext_debug_report")]
+        "vkDestroyDebugReportCallbackEXT" => destroy_debug_report_callback_ext as *const c_void,
+        _ => return get_device_proc_addr(ptr::null_mut(), p_name),
+    })
+}
+
+unsafe extern "system" fn get_device_proc_addr(_device: VkDevice, p_name: *const c_char)
+                                               -> VoidFunction
+{
+    let name = CStr::from_ptr(p_name).to_str().unwrap_or("");
+    function(match name {
+        "vkGetDeviceProcAddr" => get_device_proc_addr as *const c_void,
+        "vkDestroyDevice" => destroy_device as *const c_void,
+        "vkGetDeviceQueue" => get_device_queue as *const c_void,
+        "vkDeviceWaitIdle" => device_wait_idle as *const c_void,
+        "vkQueueSubmit" => queue_submit as *const c_void,
+        "vkQueueWaitIdle" => queue_wait_idle as *const c_void,
+        #[cfg(feature = "khr_swapchain")]
+        "vkQueuePresentKHR" => queue_present_khr as *const c_void,
+        #[cfg(feature = "ext_debug_marker")]
+        "vkDebugMarkerSetObjectNameEXT" => debug_marker_set_object_name_ext as *const c_void,
+        #[cfg(feature = "ext_debug_marker")]
+        "vkDebugMarkerSetObjectTagEXT" => debug_marker_set_object_tag_ext as *const c_void,
+        #[cfg(feature = "ext_debug_marker")]
+        "vkCmdDebugMarkerBeginEXT" => cmd_debug_marker_begin_ext as *const c_void,
+        #[cfg(feature = "ext_debug_marker")]
+        "vkCmdDebugMarkerEndEXT" => cmd_debug_marker_end_ext as *const c_void,
+        #[cfg(feature = "ext_debug_marker")]
+        "vkCmdDebugMarkerInsertEXT" => cmd_debug_marker_insert_ext as *const c_void,
+        _ => ptr::null(),
+    })
+}
+
+unsafe extern "system" fn create_instance(p_create_info: *const VkInstanceCreateInfo,
+                                          p_allocator: *const VkAllocationCallbacks,
+                                          p_instance: *mut VkInstance)
+                                          -> VkResult
+{
+    let driver = current_driver();
+    mock_try!(driver, "vkCreateInstance");
+
+    // Refuse layers and extensions that we do not offer, like a real loader
+    let create_info = &*p_create_info;
+    let layer_names = read_names(create_info.enabledLayerCount,
+                                 create_info.ppEnabledLayerNames);
+    let extension_names = read_names(create_info.enabledExtensionCount,
+                                     create_info.ppEnabledExtensionNames);
+    let physical_device_count = {
+        let state = driver.state();
+        let mut available: Vec<&str> = state.instance_extensions.iter()
+            .map(|ep| &*ep.extension_name)
+            .collect();
+        for layer_name in &layer_names {
+            match state.instance_layers.iter().find(|l| &l.layer_name == layer_name) {
+                Some(layer) => available.extend(
+                    layer.extensions.iter().map(|ep| &*ep.extension_name)),
+                None => return VK_ERROR_LAYER_NOT_PRESENT,
+            }
+        }
+        if extension_names.iter().any(|name| !available.contains(&&**name)) {
+            return VK_ERROR_EXTENSION_NOT_PRESENT;
+        }
+        state.physical_devices.len()
+    };
+
+    #[cfg(feature = "ext_validation_flags")]
+    {
+        let vflags = create_info.pNext as *const VkValidationFlagsEXT;
+        if !vflags.is_null() && (*vflags).sType == VK_STRUCTURE_TYPE_VALIDATION_FLAGS_EXT {
+            driver.state().disabled_validation_checks = slice::from_raw_parts(
+                (*vflags).pDisabledValidationChecks,
+                (*vflags).disabledValidationCheckCount as usize).to_vec();
+        }
+    }
+
+    let allocation = host_allocate(p_allocator, mem::size_of::<MockInstance>(),
+                                   VK_SYSTEM_ALLOCATION_SCOPE_INSTANCE);
+    if allocation.as_ref().map_or(false, |a| a.memory.is_null()) {
+        return VK_ERROR_OUT_OF_HOST_MEMORY;
+    }
+    driver.create_object();
+    let mock = Box::new(MockInstance {
+        driver: driver.clone(),
+        physical_devices: (0..physical_device_count)
+            .map(|index| Box::new(MockPhysicalDeviceHandle {
+                driver: driver.clone(),
+                index: index,
+            }))
+            .collect(),
+        allocation: allocation,
+    });
+    *p_instance = Box::into_raw(mock) as VkInstance;
+    VK_SUCCESS
+}
+
+unsafe extern "system" fn enumerate_instance_extension_properties(
+    p_layer_name: *const c_char, p_property_count: *mut u32,
+    p_properties: *mut VkExtensionProperties) -> VkResult
+{
+    let driver = current_driver();
+    mock_try!(driver, "vkEnumerateInstanceExtensionProperties");
+
+    let state = driver.state();
+    let extensions: Vec<VkExtensionProperties> = if p_layer_name.is_null() {
+        state.instance_extensions.iter().map(extension_to_vk).collect()
+    } else {
+        let layer_name = CStr::from_ptr(p_layer_name).to_string_lossy();
+        match state.instance_layers.iter().find(|l| l.layer_name == layer_name) {
+            Some(layer) => layer.extensions.iter().map(extension_to_vk).collect(),
+            None => return VK_ERROR_LAYER_NOT_PRESENT,
+        }
+    };
+    fill(extensions, p_property_count, p_properties)
+}
+
+unsafe extern "system" fn enumerate_instance_layer_properties(
+    p_property_count: *mut u32, p_properties: *mut VkLayerProperties) -> VkResult
+{
+    let driver = current_driver();
+    mock_try!(driver, "vkEnumerateInstanceLayerProperties");
+
+    let layers = driver.state().instance_layers.iter().map(layer_to_vk).collect();
+    fill(layers, p_property_count, p_properties)
+}
+
+unsafe extern "system" fn destroy_instance(vkinstance: VkInstance,
+                                           _p_allocator: *const VkAllocationCallbacks)
+{
+    if vkinstance.is_null() {
+        return;
+    }
+    let mock = *Box::from_raw(vkinstance as *mut MockInstance);
+    mock.driver.enter("vkDestroyInstance");
+    mock.driver.destroy_object();
+    host_free(mock.allocation);
+}
+
+unsafe extern "system" fn enumerate_physical_devices(vkinstance: VkInstance,
+                                                     p_physical_device_count: *mut u32,
+                                                     p_physical_devices: *mut VkPhysicalDevice)
+                                                     -> VkResult
+{
+    let mock = instance(vkinstance);
+    mock_try!(mock.driver, "vkEnumeratePhysicalDevices");
+
+    let handles = mock.physical_devices.iter()
+        .map(|pd| &**pd as *const MockPhysicalDeviceHandle as VkPhysicalDevice)
+        .collect();
+    fill(handles, p_physical_device_count, p_physical_devices)
+}
+
+unsafe extern "system" fn get_physical_device_properties(
+    vkphysical_device: VkPhysicalDevice, p_properties: *mut VkPhysicalDeviceProperties)
+{
+    let mock = physical_device(vkphysical_device);
+    mock.driver.enter("vkGetPhysicalDeviceProperties");
+    *p_properties = properties_to_vk(&mock.config().properties);
+}
+
+#[cfg(feature = "khr_get_physical_device_properties2")]
+unsafe extern "system" fn get_physical_device_properties2_khr(
+    vkphysical_device: VkPhysicalDevice, p_properties: *mut VkPhysicalDeviceProperties2KHR)
+{
+    let mock = physical_device(vkphysical_device);
+    mock.driver.enter("vkGetPhysicalDeviceProperties2KHR");
+    let config = mock.config();
+    (*p_properties).properties = properties_to_vk(&config.properties);
+
+    // Fill in the extension structures we know, skipping any others
+    let mut next = (*p_properties).pNext as *mut MockChainHeader;
+    while !next.is_null() {
+        #[cfg(feature = "khr_push_descriptor")]
+        {
+            if (*next).s_type == VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_PUSH_DESCRIPTOR_PROPERTIES_KHR {
+                let push = next as *mut VkPhysicalDevicePushDescriptorPropertiesKHR;
+                (*push).maxPushDescriptors = config.max_push_descriptors;
+            }
+        }
+        next = (*next).p_next as *mut MockChainHeader;
+    }
+}
+
+// The fields common to every structure in a pNext chain
+#[cfg(feature = "khr_get_physical_device_properties2")]
+#[repr(C)]
+struct MockChainHeader {
+    s_type: VkStructureType,
+    p_next: *mut c_void,
+}
+
+#[cfg(feature = "khr_get_physical_device_properties2")]
+unsafe extern "system" fn get_physical_device_features2_khr(
+    vkphysical_device: VkPhysicalDevice, p_features: *mut VkPhysicalDeviceFeatures2KHR)
+{
+    let mock = physical_device(vkphysical_device);
+    mock.driver.enter("vkGetPhysicalDeviceFeatures2KHR");
+    (*p_features).features = mock.config().features.into_vk();
+}
+
+#[cfg(feature = "khr_get_physical_device_properties2")]
+unsafe extern "system" fn get_physical_device_format_properties2_khr(
+    vkphysical_device: VkPhysicalDevice, vkformat: VkFormat,
+    p_format_properties: *mut VkFormatProperties2KHR)
+{
+    get_physical_device_format_properties(vkphysical_device, vkformat,
+                                          &mut (*p_format_properties).formatProperties);
+}
+
+#[cfg(feature = "khr_get_physical_device_properties2")]
+unsafe extern "system" fn get_physical_device_queue_family_properties2_khr(
+    vkphysical_device: VkPhysicalDevice, p_property_count: *mut u32,
+    p_properties: *mut VkQueueFamilyProperties2KHR)
+{
+    let mock = physical_device(vkphysical_device);
+    mock.driver.enter("vkGetPhysicalDeviceQueueFamilyProperties2KHR");
+    let families = mock.config().queue_families;
+    if p_properties.is_null() {
+        *p_property_count = families.len() as u32;
+        return;
+    }
+    let count = (*p_property_count as usize).min(families.len());
+    for (i, family) in families.into_iter().take(count).enumerate() {
+        (*p_properties.offset(i as isize)).queueFamilyProperties = mem::transmute(family);
+    }
+    *p_property_count = count as u32;
+}
+
+#[cfg(feature = "khr_get_physical_device_properties2")]
+unsafe extern "system" fn get_physical_device_memory_properties2_khr(
+    vkphysical_device: VkPhysicalDevice,
+    p_memory_properties: *mut VkPhysicalDeviceMemoryProperties2KHR)
+{
+    get_physical_device_memory_properties(vkphysical_device,
+                                          &mut (*p_memory_properties).memoryProperties);
+}
+
+unsafe extern "system" fn get_physical_device_features(
+    vkphysical_device: VkPhysicalDevice, p_features: *mut VkPhysicalDeviceFeatures)
+{
+    let mock = physical_device(vkphysical_device);
+    mock.driver.enter("vkGetPhysicalDeviceFeatures");
+    *p_features = mock.config().features.into_vk();
+}
+
+unsafe extern "system" fn get_physical_device_queue_family_properties(
+    vkphysical_device: VkPhysicalDevice, p_property_count: *mut u32,
+    p_properties: *mut VkQueueFamilyProperties)
+{
+    let mock = physical_device(vkphysical_device);
+    mock.driver.enter("vkGetPhysicalDeviceQueueFamilyProperties");
+    let families: Vec<VkQueueFamilyProperties> = mock.config().queue_families.into_iter()
+        .map(|qfp| mem::transmute(qfp))
+        .collect();
+    fill(families, p_property_count, p_properties);
+}
+
+unsafe extern "system" fn get_physical_device_memory_properties(
+    vkphysical_device: VkPhysicalDevice,
+    p_memory_properties: *mut VkPhysicalDeviceMemoryProperties)
+{
+    let mock = physical_device(vkphysical_device);
+    mock.driver.enter("vkGetPhysicalDeviceMemoryProperties");
+    *p_memory_properties = memory_properties_to_vk(&mock.config().memory_properties);
+}
+
+unsafe extern "system" fn get_physical_device_format_properties(
+    vkphysical_device: VkPhysicalDevice, vkformat: VkFormat,
+    p_format_properties: *mut VkFormatProperties)
+{
+    let mock = physical_device(vkphysical_device);
+    mock.driver.enter("vkGetPhysicalDeviceFormatProperties");
+    let format: Format = From::from(vkformat);
+    *p_format_properties = match mock.config().format_properties.into_iter()
+        .find(|&(f, _)| f == format)
+    {
+        Some((_, properties)) => properties.into(),
+        None => mem::zeroed(),
+    };
+}
+
+unsafe extern "system" fn get_physical_device_image_format_properties(
+    vkphysical_device: VkPhysicalDevice, vkformat: VkFormat, _type: VkImageType,
+    vktiling: VkImageTiling, _usage: VkImageUsageFlags, _flags: VkImageCreateFlags,
+    p_image_format_properties: *mut VkImageFormatProperties) -> VkResult
+{
+    let mock = physical_device(vkphysical_device);
+    mock_try!(mock.driver, "vkGetPhysicalDeviceImageFormatProperties");
+    let format: Format = From::from(vkformat);
+    let tiling: ImageTiling = From::from(vktiling);
+    match mock.config().image_format_properties.iter()
+        .find(|&&(f, t, _)| f == format && t == tiling)
+    {
+        Some(&(_, _, ref properties)) => {
+            *p_image_format_properties = image_format_properties_to_vk(properties);
+            VK_SUCCESS
+        },
+        None => VK_ERROR_FORMAT_NOT_SUPPORTED,
+    }
+}
+
+unsafe extern "system" fn get_physical_device_sparse_image_format_properties(
+    vkphysical_device: VkPhysicalDevice, vkformat: VkFormat, _type: VkImageType,
+    _samples: VkSampleCountFlagBits, _usage: VkImageUsageFlags, _tiling: VkImageTiling,
+    p_property_count: *mut u32, p_properties: *mut VkSparseImageFormatProperties)
+{
+    let mock = physical_device(vkphysical_device);
+    mock.driver.enter("vkGetPhysicalDeviceSparseImageFormatProperties");
+    let format: Format = From::from(vkformat);
+    let properties = mock.config().sparse_image_format_properties.iter()
+        .filter(|&&(f, _)| f == format)
+        .map(|&(_, ref properties)| sparse_image_format_properties_to_vk(properties))
+        .collect();
+    fill(properties, p_property_count, p_properties);
+}
+
+#[cfg(feature = "khr_get_physical_device_properties2")]
+unsafe extern "system" fn get_physical_device_image_format_properties2_khr(
+    vkphysical_device: VkPhysicalDevice,
+    p_image_format_info: *const VkPhysicalDeviceImageFormatInfo2KHR,
+    p_image_format_properties: *mut VkImageFormatProperties2KHR) -> VkResult
+{
+    let info = &*p_image_format_info;
+    get_physical_device_image_format_properties(
+        vkphysical_device, info.format, info.type_, info.tiling, info.usage, info.flags,
+        &mut (*p_image_format_properties).imageFormatProperties)
+}
+
+#[cfg(feature = "khr_get_physical_device_properties2")]
+unsafe extern "system" fn get_physical_device_sparse_image_format_properties2_khr(
+    vkphysical_device: VkPhysicalDevice,
+    p_format_info: *const VkPhysicalDeviceSparseImageFormatInfo2KHR,
+    p_property_count: *mut u32, p_properties: *mut VkSparseImageFormatProperties2KHR)
+{
+    let info = &*p_format_info;
+    let mut properties: Vec<VkSparseImageFormatProperties> = Vec::new();
+    let mut count: u32 = 0;
+    get_physical_device_sparse_image_format_properties(
+        vkphysical_device, info.format, info.type_, info.samples, info.usage, info.tiling,
+        &mut count, ptr::null_mut());
+    properties.resize(count as usize, Default::default());
+    get_physical_device_sparse_image_format_properties(
+        vkphysical_device, info.format, info.type_, info.samples, info.usage, info.tiling,
+        &mut count, properties.as_mut_ptr());
+    if p_properties.is_null() {
+        *p_property_count = count;
+        return;
+    }
+    let count = (*p_property_count).min(count);
+    for (i, property) in properties.into_iter().take(count as usize).enumerate() {
+        (*p_properties.offset(i as isize)).properties = property;
+    }
+    *p_property_count = count;
+}
+
+unsafe extern "system" fn enumerate_device_extension_properties(
+    vkphysical_device: VkPhysicalDevice, p_layer_name: *const c_char,
+    p_property_count: *mut u32, p_properties: *mut VkExtensionProperties) -> VkResult
+{
+    let mock = physical_device(vkphysical_device);
+    mock_try!(mock.driver, "vkEnumerateDeviceExtensionProperties");
+
+    let config = mock.config();
+    let extensions: Vec<VkExtensionProperties> = if p_layer_name.is_null() {
+        config.extensions.iter().map(extension_to_vk).collect()
+    } else {
+        let layer_name = CStr::from_ptr(p_layer_name).to_string_lossy();
+        match config.layers.iter().find(|l| l.layer_name == layer_name) {
+            Some(layer) => layer.extensions.iter().map(extension_to_vk).collect(),
+            None => return VK_ERROR_LAYER_NOT_PRESENT,
+        }
+    };
+    fill(extensions, p_property_count, p_properties)
+}
+
+unsafe extern "system" fn enumerate_device_layer_properties(
+    vkphysical_device: VkPhysicalDevice, p_property_count: *mut u32,
+    p_properties: *mut VkLayerProperties) -> VkResult
+{
+    let mock = physical_device(vkphysical_device);
+    mock_try!(mock.driver, "vkEnumerateDeviceLayerProperties");
+
+    let layers = mock.config().layers.iter().map(layer_to_vk).collect();
+    fill(layers, p_property_count, p_properties)
+}
+
+unsafe extern "system" fn create_device(vkphysical_device: VkPhysicalDevice,
+                                        p_create_info: *const VkDeviceCreateInfo,
+                                        p_allocator: *const VkAllocationCallbacks,
+                                        p_device: *mut VkDevice)
+                                        -> VkResult
+{
+    let mock = physical_device(vkphysical_device);
+    mock_try!(mock.driver, "vkCreateDevice");
+
+    let config = mock.config();
+    let create_info = &*p_create_info;
+
+    // Refuse extensions and features which the device does not offer
+    let extension_names = read_names(create_info.enabledExtensionCount,
+                                     create_info.ppEnabledExtensionNames);
+    if extension_names.iter()
+        .any(|name| !config.extensions.iter().any(|ep| &ep.extension_name == name))
+    {
+        return VK_ERROR_EXTENSION_NOT_PRESENT;
+    }
+    if !create_info.pEnabledFeatures.is_null() {
+        let count = mem::size_of::<VkPhysicalDeviceFeatures>() / mem::size_of::<VkBool32>();
+        let requested = slice::from_raw_parts(
+            create_info.pEnabledFeatures as *const VkBool32, count);
+        let supported = config.features.clone().into_vk();
+        let supported = slice::from_raw_parts(
+            &supported as *const VkPhysicalDeviceFeatures as *const VkBool32, count);
+        if requested.iter().zip(supported.iter()).any(|(&r, &s)| r != 0 && s == 0) {
+            return VK_ERROR_FEATURE_NOT_PRESENT;
+        }
+    }
+
+    let mut queues: Vec<Box<MockQueue>> = Vec::new();
+    for i in 0..create_info.queueCreateInfoCount as isize {
+        let queue_info = &*create_info.pQueueCreateInfos.offset(i);
+        match config.queue_families.get(queue_info.queueFamilyIndex as usize) {
+            Some(family) if queue_info.queueCount <= family.queue_count => {},
+            _ => return VK_ERROR_INITIALIZATION_FAILED,
+        }
+        for queue_index in 0..queue_info.queueCount {
+            queues.push(Box::new(MockQueue {
+                driver: mock.driver.clone(),
+                family_index: queue_info.queueFamilyIndex,
+                queue_index: queue_index,
+            }));
+        }
+    }
+
+    let allocation = host_allocate(p_allocator, mem::size_of::<MockDevice>(),
+                                   VK_SYSTEM_ALLOCATION_SCOPE_DEVICE);
+    if allocation.as_ref().map_or(false, |a| a.memory.is_null()) {
+        return VK_ERROR_OUT_OF_HOST_MEMORY;
+    }
+    mock.driver.create_object();
+    let device = Box::new(MockDevice {
+        driver: mock.driver.clone(),
+        queues: queues,
+        allocation: allocation,
+    });
+    *p_device = Box::into_raw(device) as VkDevice;
+    VK_SUCCESS
+}
+
+unsafe extern "system" fn destroy_device(vkdevice: VkDevice,
+                                         _p_allocator: *const VkAllocationCallbacks)
+{
+    if vkdevice.is_null() {
+        return;
+    }
+    let mock = *Box::from_raw(vkdevice as *mut MockDevice);
+    mock.driver.enter("vkDestroyDevice");
+    mock.driver.destroy_object();
+    host_free(mock.allocation);
+}
+
+unsafe extern "system" fn get_device_queue(vkdevice: VkDevice, queue_family_index: u32,
+                                           queue_index: u32, p_queue: *mut VkQueue)
+{
+    let mock = device(vkdevice);
+    mock.driver.enter("vkGetDeviceQueue");
+    *p_queue = match mock.queues.iter()
+        .find(|q| q.family_index == queue_family_index && q.queue_index == queue_index)
+    {
+        Some(queue) => &**queue as *const MockQueue as VkQueue,
+        None => ptr::null_mut(),
+    };
+}
+
+unsafe extern "system" fn device_wait_idle(vkdevice: VkDevice) -> VkResult
+{
+    let mock = device(vkdevice);
+    mock_try!(mock.driver, "vkDeviceWaitIdle");
+    VK_SUCCESS
+}
+
+unsafe extern "system" fn queue_submit(vkqueue: VkQueue, submit_count: u32,
+                                       p_submits: *const VkSubmitInfo, _fence: VkFence)
+                                       -> VkResult
+{
+    let mock = queue(vkqueue);
+    mock_try!(mock.driver, "vkQueueSubmit");
+    let submits = slice::from_raw_parts(p_submits, submit_count as usize);
+    mock.driver.state().submissions.extend(submits.iter().map(|submit| MockSubmission {
+        family_index: mock.family_index,
+        queue_index: mock.queue_index,
+        command_buffer_count: submit.commandBufferCount,
+        wait_semaphore_count: submit.waitSemaphoreCount,
+        signal_semaphore_count: submit.signalSemaphoreCount,
+    }));
+    VK_SUCCESS
+}
+
+unsafe extern "system" fn queue_wait_idle(vkqueue: VkQueue) -> VkResult
+{
+    let mock = queue(vkqueue);
+    mock_try!(mock.driver, "vkQueueWaitIdle");
+    VK_SUCCESS
+}
+
+#[cfg(feature = "khr_swapchain")]
+unsafe extern "system" fn queue_present_khr(vkqueue: VkQueue,
+                                            _p_present_info: *const VkPresentInfoKHR)
+                                            -> VkResult
+{
+    let mock = queue(vkqueue);
+    mock_try!(mock.driver, "vkQueuePresentKHR");
+    VK_SUCCESS
+}
+
+/// Create a surface handle without a window, for testing the surface queries.
+#[cfg(feature = "khr_surface")]
+impl MockDriver {
+    pub fn create_surface(&self, instance: &::Instance, loader: InstanceLoader)
+                          -> Result<::instance::surface::Surface, ::Error>
+    {
+        loader.require_extension(VK_KHR_SURFACE_EXTENSION_NAME_STR)?;
+
+        self.enter("vkCreateSurfaceKHR");
+        let handle = self.create_object();
+        Ok(unsafe {
+            ::instance::surface::Surface::from_vk(instance, loader, mem::transmute(handle))
+        })
+    }
+}
+
+#[cfg(feature = "khr_surface")]
+unsafe extern "system" fn destroy_surface_khr(vkinstance: VkInstance, _surface: VkSurfaceKHR,
+                                              _p_allocator: *const VkAllocationCallbacks)
+{
+    let mock = instance(vkinstance);
+    mock.driver.enter("vkDestroySurfaceKHR");
+    mock.driver.destroy_object();
+}
+
+#[cfg(feature = "khr_surface")]
+unsafe extern "system" fn get_physical_device_surface_support_khr(
+    vkphysical_device: VkPhysicalDevice, queue_family_index: u32, _surface: VkSurfaceKHR,
+    p_supported: *mut VkBool32) -> VkResult
+{
+    let mock = physical_device(vkphysical_device);
+    mock_try!(mock.driver, "vkGetPhysicalDeviceSurfaceSupportKHR");
+    let supported = mock.config().present_queue_families.contains(&queue_family_index);
+    *p_supported = if supported { VK_TRUE } else { VK_FALSE };
+    VK_SUCCESS
+}
+
+#[cfg(feature = "khr_surface")]
+unsafe extern "system" fn get_physical_device_surface_formats_khr(
+    vkphysical_device: VkPhysicalDevice, _surface: VkSurfaceKHR,
+    p_surface_format_count: *mut u32, p_surface_formats: *mut VkSurfaceFormatKHR) -> VkResult
+{
+    let mock = physical_device(vkphysical_device);
+    mock_try!(mock.driver, "vkGetPhysicalDeviceSurfaceFormatsKHR");
+    let formats = mock.config().surface_formats.into_iter()
+        .map(|sf| mem::transmute(sf))
+        .collect();
+    fill(formats, p_surface_format_count, p_surface_formats)
+}
+
+#[cfg(feature = "khr_surface")]
+unsafe extern "system" fn get_physical_device_surface_capabilities_khr(
+    vkphysical_device: VkPhysicalDevice, _surface: VkSurfaceKHR,
+    p_surface_capabilities: *mut VkSurfaceCapabilitiesKHR) -> VkResult
+{
+    let mock = physical_device(vkphysical_device);
+    mock_try!(mock.driver, "vkGetPhysicalDeviceSurfaceCapabilitiesKHR");
+    *p_surface_capabilities = mem::transmute(mock.config().surface_capabilities);
+    VK_SUCCESS
+}
+
+#[cfg(feature = "khr_surface")]
+unsafe extern "system" fn get_physical_device_surface_present_modes_khr(
+    vkphysical_device: VkPhysicalDevice, _surface: VkSurfaceKHR,
+    p_present_mode_count: *mut u32, p_present_modes: *mut VkPresentModeKHR) -> VkResult
+{
+    let mock = physical_device(vkphysical_device);
+    mock_try!(mock.driver, "vkGetPhysicalDeviceSurfacePresentModesKHR");
+    let modes = mock.config().present_modes.into_iter()
+        .map(|pm| pm.into())
+        .collect();
+    fill(modes, p_present_mode_count, p_present_modes)
+}
+
+/// Deliver debug report messages, as a validation layer would.
+#[cfg(feature = "ext_debug_report")]
+impl MockDriver {
+    /// Send a message to every registered debug report callback whose flags
+    /// match, returning true if any of them asked for the call to be aborted.
+    pub fn debug_report(&self, flags: VkDebugReportFlagsEXT,
+                        object_type: VkDebugReportObjectTypeEXT, object: u64,
+                        message_code: i32, layer_prefix: &str, message: &str) -> bool
+    {
+        // Copy the callbacks out, as they may call back into the driver
+        let callbacks = self.state().debug_callbacks.clone();
+        let layer_prefix = CString::new(layer_prefix).unwrap();
+        let message = CString::new(message).unwrap();
+        let mut abort = false;
+        for callback in callbacks.iter().filter(|c| c.flags.intersects(flags)) {
+            let result = unsafe {
+                (callback.callback)(flags, object_type, object, 0, message_code,
+                                    layer_prefix.as_ptr(), message.as_ptr(),
+                                    callback.user_data as *mut c_void)
+            };
+            abort = abort || result != 0;
+        }
+        abort
+    }
+}
+
+#[cfg(feature = "ext_debug_report")]
+unsafe extern "system" fn create_debug_report_callback_ext(
+    vkinstance: VkInstance, p_create_info: *const VkDebugReportCallbackCreateInfoEXT,
+    _p_allocator: *const VkAllocationCallbacks, p_callback: *mut VkDebugReportCallbackEXT)
+    -> VkResult
+{
+    let mock = instance(vkinstance);
+    mock_try!(mock.driver, "vkCreateDebugReportCallbackEXT");
+    let handle = mock.driver.create_object();
+    mock.driver.state().debug_callbacks.push(MockDebugCallback {
+        handle: handle,
+        flags: (*p_create_info).flags,
+        callback: (*p_create_info).pfnCallback,
+        user_data: (*p_create_info).pUserData as usize,
+    });
+    *p_callback = mem::transmute(handle);
+    VK_SUCCESS
+}
+
+#[cfg(feature = "ext_debug_report")]
+unsafe extern "system" fn destroy_debug_report_callback_ext(
+    vkinstance: VkInstance, callback: VkDebugReportCallbackEXT,
+    _p_allocator: *const VkAllocationCallbacks)
+{
+    let mock = instance(vkinstance);
+    mock.driver.enter("vkDestroyDebugReportCallbackEXT");
+    let handle: u64 = mem::transmute(callback);
+    mock.driver.state().debug_callbacks.retain(|c| c.handle != handle);
+    mock.driver.destroy_object();
+}
+
+/// Inspect the names, tags and markers recorded through VK_EXT_debug_marker.
+/// Markers are recorded as "begin <label>", "end" and "insert <label>".
+#[cfg(feature = "ext_debug_marker")]
+impl MockDriver {
+    pub fn object_name(&self, object: u64) -> Option<String>
+    {
+        self.state().object_names.get(&object).cloned()
+    }
+
+    pub fn object_tag(&self, object: u64) -> Option<(u64, Vec<u8>)>
+    {
+        self.state().object_tags.get(&object).cloned()
+    }
+
+    pub fn markers(&self) -> Vec<String>
+    {
+        self.state().markers.clone()
+    }
+}
+
+#[cfg(feature = "ext_debug_marker")]
+unsafe extern "system" fn debug_marker_set_object_name_ext(
+    vkdevice: VkDevice, p_name_info: *const VkDebugMarkerObjectNameInfoEXT) -> VkResult
+{
+    let mock = device(vkdevice);
+    mock_try!(mock.driver, "vkDebugMarkerSetObjectNameEXT");
+    let name_info = &*p_name_info;
+    let name = CStr::from_ptr(name_info.pObjectName).to_string_lossy().into_owned();
+    mock.driver.state().object_names.insert(name_info.object, name);
+    VK_SUCCESS
+}
+
+#[cfg(feature = "ext_debug_marker")]
+unsafe extern "system" fn debug_marker_set_object_tag_ext(
+    vkdevice: VkDevice, p_tag_info: *const VkDebugMarkerObjectTagInfoEXT) -> VkResult
+{
+    let mock = device(vkdevice);
+    mock_try!(mock.driver, "vkDebugMarkerSetObjectTagEXT");
+    let tag_info = &*p_tag_info;
+    let tag = slice::from_raw_parts(tag_info.pTag as *const u8, tag_info.tagSize).to_vec();
+    mock.driver.state().object_tags.insert(tag_info.object, (tag_info.tagName, tag));
+    VK_SUCCESS
+}
+
+// We have no command buffers of our own, so command buffer entry points just
+// record what they were given with the current driver.
+#[cfg(feature = "ext_debug_marker")]
+unsafe fn record_marker(entry_point: &str, marker: String)
+{
+    let driver = current_driver();
+    driver.enter(entry_point);
+    driver.state().markers.push(marker);
+}
+
+#[cfg(feature = "ext_debug_marker")]
+unsafe extern "system" fn cmd_debug_marker_begin_ext(
+    _command_buffer: VkCommandBuffer, p_marker_info: *const VkDebugMarkerMarkerInfoEXT)
+{
+    let label = CStr::from_ptr((*p_marker_info).pMarkerName).to_string_lossy();
+    record_marker("vkCmdDebugMarkerBeginEXT", format!("begin {}", label));
+}
+
+#[cfg(feature = "ext_debug_marker")]
+unsafe extern "system" fn cmd_debug_marker_end_ext(_command_buffer: VkCommandBuffer)
+{
+    record_marker("vkCmdDebugMarkerEndEXT", "end".to_owned());
+}
+
+#[cfg(feature = "ext_debug_marker")]
+unsafe extern "system" fn cmd_debug_marker_insert_ext(
+    _command_buffer: VkCommandBuffer, p_marker_info: *const VkDebugMarkerMarkerInfoEXT)
+{
+    let label = CStr::from_ptr((*p_marker_info).pMarkerName).to_string_lossy();
+    record_marker("vkCmdDebugMarkerInsertEXT", format!("insert {}", label));
+}
+
+/// The validation checks chained onto the last vkCreateInstance call
+#[cfg(feature = "ext_validation_flags")]
+impl MockDriver {
+    pub fn disabled_validation_checks(&self) -> Vec<VkValidationCheckEXT>
+    {
+        self.state().disabled_validation_checks.clone()
+    }
+}
@@ -1,12 +1,19 @@
 
-#[derive(Debug, Clone)]
+use vks::VK_HEADER_VERSION;
+
+/// A Vulkan version number: major, minor, patch.  Versions order by major,
+/// then minor, then patch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Version(pub u32, pub u32, pub u32);
 
+/// The version of the Vulkan headers matching the enabled `vk_1_0_*` feature
+pub const HEADER_VERSION: Version = Version(1, 0, VK_HEADER_VERSION);
+
 impl Version {
     #[inline]
     pub fn to_vk(&self) -> u32
     {
-        (self.0 << 22) | (self.0 << 12) | self.2
+        (self.0 << 22) | (self.1 << 12) | self.2
     }
 
     #[inline]
@@ -17,6 +24,15 @@ impl Version {
             (vk & 0x003ff000) >> 12,
             (vk & 0x00000fff) )
     }
+
+    /// Whether an implementation of this version can run an application
+    /// targetting `target`.  The patch version is ignored, as it is by Vulkan
+    /// (see vulkan specification, section 3.2 Instances).
+    #[inline]
+    pub fn supports(&self, target: &Version) -> bool
+    {
+        (self.0, self.1) >= (target.0, target.1)
+    }
 }
 
 use std::fmt;
@@ -0,0 +1,1192 @@
+extern crate vks;
+extern crate libc;
+extern crate sarek;
+
+use libc::c_void;
+use vks::{VK_ERROR_INITIALIZATION_FAILED, VK_ERROR_OUT_OF_DEVICE_MEMORY, VK_ERROR_DEVICE_LOST,
+          VK_INCOMPLETE, VK_ERROR_LAYER_NOT_PRESENT, VK_SAMPLE_COUNT_1_BIT,
+          VK_SAMPLE_COUNT_4_BIT};
+use sarek::{Error, Version, HEADER_VERSION, InstanceLoader, Instance, Allocator, Format,
+            FormatProperties, FormatFeatureFlags, Extent3D};
+use sarek::{FORMAT_FEATURE_SAMPLED_IMAGE_BIT, FORMAT_FEATURE_COLOR_ATTACHMENT_BIT,
+            FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT};
+use sarek::image::{ImageType, ImageTiling, ImageCreateFlags, ImageFormatProperties,
+                   SparseImageFormatProperties, SparseImageFormatFlags,
+                   IMAGE_ASPECT_COLOR_BIT, IMAGE_USAGE_SAMPLED_BIT, IMAGE_USAGE_TRANSFER_DST_BIT};
+use sarek::allocator::{HostAllocator, TrackingAllocator, SystemAllocationScope};
+use sarek::error::{NameKind, Status};
+use sarek::info::Report;
+use sarek::instance::{ApplicationInfo, InstanceCreateInfo};
+use sarek::instance::device::{DeviceCreateInfo, DeviceQueueCreateInfo};
+use sarek::instance::physical_device::{PhysicalDeviceType, PhysicalDeviceFeatures, LimitValue,
+                                       ExtensionProperties,
+                                       Profile, LimitRequirement, FormatRequirement,
+                                       FormatFeatureSet, ProfileViolation};
+use sarek::mock::{MockDriver, MockLayer, MockPhysicalDevice};
+
+fn create_info(layers: Vec<&str>, required: Vec<&str>, optional: Vec<&str>)
+               -> InstanceCreateInfo
+{
+    InstanceCreateInfo {
+        application_info: ApplicationInfo {
+            application_name: "Mock Test".to_owned(),
+            application_version: Version(0,1,0),
+            engine_name: "Mock Engine".to_owned(),
+            engine_version: Version(0,1,0),
+            api_version: HEADER_VERSION,
+        },
+        enabled_layer_count: layers.len() as u32,
+        enabled_layer_names: layers.iter().map(|s| s.to_string()).collect(),
+        enabled_extension_names: required.iter().map(|s| s.to_string()).collect(),
+        optional_extension_names: optional.iter().map(|s| s.to_string()).collect(),
+        allocator: None,
+        disabled_validation_checks: vec![],
+    }
+}
+
+// One queue in the first family, and VK_KHR_swapchain
+fn device_create_info() -> DeviceCreateInfo
+{
+    DeviceCreateInfo {
+        queue_create_infos: vec![DeviceQueueCreateInfo {
+            queue_family_index: 0,
+            queue_priorities: vec![1.0],
+        }],
+        enabled_extension_names: vec!["VK_KHR_swapchain".to_owned()],
+        ..Default::default()
+    }
+}
+
+fn driver() -> MockDriver
+{
+    let driver = MockDriver::new();
+    driver.add_instance_extension("VK_KHR_surface", 25);
+    driver.add_instance_layer(MockLayer {
+        layer_name: "VK_LAYER_LUNARG_parameter_validation".to_owned(),
+        description: "Mock validation".to_owned(),
+        extensions: Vec::new(),
+    });
+    driver.add_physical_device(
+        MockPhysicalDevice::new("Mock Discrete", PhysicalDeviceType::DiscreteGPU)
+            .with_extension("VK_KHR_swapchain"));
+    driver.add_physical_device(
+        MockPhysicalDevice::new("Mock Integrated", PhysicalDeviceType::IntegratedGPU));
+    driver
+}
+
+#[test]
+fn loader_reports_vulkan_1_0() {
+    let driver = driver();
+    assert_eq!(driver.loader().enumerate_instance_version().unwrap(), Version(1,0,0));
+}
+
+fn instance(driver: &MockDriver) -> (Instance, InstanceLoader)
+{
+    Instance::new(driver.loader(), create_info(vec![], vec![], vec![])).unwrap()
+}
+
+#[test]
+#[allow(unused_variables)]
+fn enables_available_optional_extensions() {
+    let driver = driver();
+    let (instance, loader) = Instance::new(
+        driver.loader(),
+        create_info(vec![], vec!["VK_KHR_surface"], vec!["VK_EXT_debug_report"])).unwrap();
+    assert_eq!(instance.enabled_extension_names(), &["VK_KHR_surface".to_owned()]);
+    assert!(instance.is_extension_enabled("VK_KHR_surface"));
+    assert!(!instance.is_extension_enabled("VK_EXT_debug_report"));
+
+    #[cfg(feature = "khr_surface")]
+    {
+        let surface = driver.create_surface(&instance, loader.clone()).unwrap();
+        drop(surface);
+        assert!(driver.calls().iter().any(|call| call == "vkDestroySurfaceKHR"));
+    }
+}
+
+#[test]
+fn reports_missing_layers_and_extensions() {
+    let driver = driver();
+    let result = Instance::new(
+        driver.loader(),
+        create_info(vec!["VK_LAYER_LUNARG_parameter_validatoin"],
+                    vec!["VK_KHR_surface", "VK_KHR_xlib_surface"], vec![]));
+    match result {
+        Err(Error::NotPresent(missing)) => {
+            assert_eq!(missing.len(), 2);
+            assert_eq!(missing[0].kind, NameKind::Layer);
+            assert_eq!(missing[0].similar,
+                       vec!["VK_LAYER_LUNARG_parameter_validation".to_owned()]);
+            assert_eq!(missing[1].kind, NameKind::InstanceExtension);
+            assert_eq!(missing[1].name, "VK_KHR_xlib_surface");
+        },
+        _ => panic!("Expected Error::NotPresent"),
+    }
+    assert!(!driver.calls().contains(&"vkCreateInstance".to_owned()));
+}
+
+#[test]
+fn enumerates_physical_devices() {
+    let driver = driver();
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+    assert_eq!(devices.len(), 2);
+
+    let properties = devices[0].get_properties(&loader).unwrap();
+    assert_eq!(properties.device_name, "Mock Discrete");
+    assert_eq!(properties.device_type, PhysicalDeviceType::DiscreteGPU);
+    assert_eq!(properties.api_version, Version(1,0,3));
+    assert!(properties.supports_api_version(&HEADER_VERSION));
+    assert!(!properties.supports_api_version(&Version(1,1,0)));
+
+    let queue_families = devices[1].get_queue_family_properties(&loader).unwrap();
+    assert_eq!(queue_families.len(), 1);
+    assert_eq!(queue_families[0].queue_count, 1);
+
+    let memory = devices[1].get_memory_properties(&loader).unwrap();
+    assert_eq!(memory.memory_types.len(), 2);
+    assert_eq!(memory.memory_heaps.len(), 1);
+}
+
+#[test]
+fn creates_and_destroys_device() {
+    let driver = driver();
+    {
+        let (instance, loader) = instance(&driver);
+        let devices = instance.enumerate_physical_devices(&loader).unwrap();
+        let device = instance.create_device(loader.clone(), &devices[0],
+                                            device_create_info()).unwrap();
+        assert!(!device.get_queue(0, 0).unwrap().inner().is_null());
+        assert_eq!(driver.live_objects(), 2);
+
+        match instance.create_device(loader.clone(), &devices[1],
+                                     device_create_info()) {
+            Err(Error::NotPresent(missing)) => assert_eq!(missing[0].name, "VK_KHR_swapchain"),
+            _ => panic!("Expected Error::NotPresent"),
+        }
+    }
+    assert_eq!(driver.live_objects(), 0);
+}
+
+#[test]
+fn creates_device_with_several_queues() {
+    use sarek::instance::physical_device::QUEUE_FLAGS_COMPUTE_BIT;
+
+    let driver = MockDriver::new();
+    let mut physical_device = MockPhysicalDevice::new("Mock", PhysicalDeviceType::DiscreteGPU);
+    physical_device.queue_families[0].queue_count = 2;
+    let mut compute = physical_device.queue_families[0].clone();
+    compute.queue_flags = QUEUE_FLAGS_COMPUTE_BIT;
+    compute.queue_count = 1;
+    physical_device.queue_families.push(compute);
+    physical_device.features.sampler_anisotropy = 1;
+    driver.add_physical_device(physical_device);
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+
+    // Headless: no swapchain extension is needed
+    let mut info = DeviceCreateInfo {
+        queue_create_infos: vec![
+            DeviceQueueCreateInfo { queue_family_index: 0, queue_priorities: vec![1.0, 0.5] },
+            DeviceQueueCreateInfo { queue_family_index: 1, queue_priorities: vec![1.0] },
+        ],
+        ..Default::default()
+    };
+    info.enabled_features.sampler_anisotropy = 1;
+    let device = instance.create_device(loader.clone(), &devices[0], info.clone()).unwrap();
+    assert!(device.enabled_extension_names().is_empty());
+    assert!(!device.is_extension_enabled("VK_KHR_swapchain"));
+    assert_eq!(device.enabled_features().enabled_names(), vec!["sampler_anisotropy"]);
+    assert_eq!(device.queue_create_infos(), &info.queue_create_infos[..]);
+
+    assert!(!device.get_queue(0, 1).unwrap().inner().is_null());
+    assert!(!device.get_queue(1, 0).unwrap().inner().is_null());
+    assert!(device.get_queue(1, 1).is_err());
+    assert!(device.get_queue(2, 0).is_err());
+
+    let mut too_many = info.clone();
+    too_many.queue_create_infos[1].queue_priorities.push(1.0);
+    assert!(instance.create_device(loader.clone(), &devices[0], too_many).is_err());
+    let mut repeated = info.clone();
+    repeated.queue_create_infos[1].queue_family_index = 0;
+    assert!(instance.create_device(loader.clone(), &devices[0], repeated).is_err());
+    let mut no_queues = info.clone();
+    no_queues.queue_create_infos.clear();
+    assert!(instance.create_device(loader.clone(), &devices[0], no_queues).is_err());
+    // Only the first device reached the driver
+    assert_eq!(driver.calls().iter().filter(|&call| call == "vkCreateDevice").count(), 1);
+    for &priority in &[-0.5, 1.5, ::std::f32::NAN] {
+        let mut bad_priority = info.clone();
+        bad_priority.queue_create_infos[0].queue_priorities[1] = priority;
+        assert!(instance.create_device(loader.clone(), &devices[0], bad_priority).is_err());
+    }
+}
+
+#[test]
+fn injects_errors() {
+    let driver = driver();
+    let (instance, loader) = instance(&driver);
+    driver.fail("vkEnumeratePhysicalDevices", VK_ERROR_INITIALIZATION_FAILED);
+    match instance.enumerate_physical_devices(&loader) {
+        Err(Error::Vulkan(e)) => {
+            assert_eq!(e.result, VK_ERROR_INITIALIZATION_FAILED);
+            assert_eq!(e.entry_point, "vkEnumeratePhysicalDevices");
+            assert_eq!(e.method, Some("Instance::enumerate_physical_devices"));
+        },
+        _ => panic!("Expected Error::Vulkan"),
+    }
+    driver.clear_failure("vkEnumeratePhysicalDevices");
+    assert_eq!(instance.enumerate_physical_devices(&loader).unwrap().len(), 2);
+}
+
+#[test]
+fn classifies_vulkan_errors() {
+    let driver = driver();
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+
+    driver.fail("vkCreateDevice", VK_ERROR_OUT_OF_DEVICE_MEMORY);
+    let error = instance.create_device(loader.clone(), &devices[0],
+                                       device_create_info())
+        .err().unwrap();
+    assert!(error.is_out_of_memory());
+    assert!(!error.is_device_lost());
+    assert_eq!(error.vk_result(), Some(VK_ERROR_OUT_OF_DEVICE_MEMORY));
+    assert!(error.to_string().contains("vkCreateDevice"));
+    assert!(error.to_string().contains("Instance::create_device"));
+
+    assert_eq!(Status::from_vk(VK_INCOMPLETE), Some(Status::Incomplete));
+    assert_eq!(Status::from_vk(VK_ERROR_DEVICE_LOST), None);
+}
+
+#[test]
+fn uses_host_allocator() {
+    let driver = driver();
+    let tracking = TrackingAllocator::new();
+    {
+        let mut info = create_info(vec![], vec![], vec![]);
+        info.allocator = Some(Allocator::new(tracking.clone()));
+        let (instance, loader) = Instance::new(driver.loader(), info).unwrap();
+        assert!(tracking.live_bytes(SystemAllocationScope::Instance) > 0);
+        assert_eq!(tracking.live_bytes(SystemAllocationScope::Device), 0);
+
+        let devices = instance.enumerate_physical_devices(&loader).unwrap();
+        let mut info = device_create_info();
+        info.allocator = instance.allocator();
+        let _device = instance.create_device(loader.clone(), &devices[0], info).unwrap();
+        assert!(tracking.live_bytes(SystemAllocationScope::Device) > 0);
+    }
+    assert_eq!(tracking.total_live_bytes(), 0);
+    assert_eq!(tracking.live_allocations(), 0);
+}
+
+#[test]
+fn host_allocators_refuse_bad_requests_without_panicking() {
+    use sarek::allocator::{SystemAllocator, InternalAllocationType};
+
+    let scope = SystemAllocationScope::Object;
+    assert!(SystemAllocator.allocate(usize::max_value(), 16, scope).is_null());
+    assert!(SystemAllocator.allocate(64, 24, scope).is_null());
+    let memory = SystemAllocator.allocate(64, 64, scope);
+    assert!(!memory.is_null());
+    assert_eq!(memory as usize % 64, 0);
+    SystemAllocator.free(memory);
+
+    // More freed than was reported allocated
+    let tracking = TrackingAllocator::new();
+    tracking.internal_allocation(16, InternalAllocationType::Executable, scope);
+    tracking.internal_free(32, InternalAllocationType::Executable, scope);
+    assert_eq!(tracking.internal_bytes(scope), 0);
+}
+
+struct PanickingAllocator;
+
+impl HostAllocator for PanickingAllocator {
+    fn allocate(&self, _size: usize, _alignment: usize, _scope: SystemAllocationScope)
+                -> *mut c_void
+    {
+        panic!("allocate")
+    }
+
+    fn reallocate(&self, _original: *mut c_void, _size: usize, _alignment: usize,
+                  _scope: SystemAllocationScope) -> *mut c_void
+    {
+        panic!("reallocate")
+    }
+
+    fn free(&self, _memory: *mut c_void)
+    {
+        panic!("free")
+    }
+}
+
+#[test]
+fn contains_panics_in_host_allocators() {
+    let driver = driver();
+    let mut info = create_info(vec![], vec![], vec![]);
+    info.allocator = Some(Allocator::new(PanickingAllocator));
+    match Instance::new(driver.loader(), info) {
+        Err(e) => assert!(e.is_out_of_memory()),
+        Ok(_) => panic!("Expected the instance allocation to fail"),
+    }
+    assert_eq!(driver.live_objects(), 0);
+}
+
+#[test]
+fn children_keep_parents_alive() {
+    let driver = driver();
+    let device = {
+        let (instance, loader) = instance(&driver);
+        let devices = instance.enumerate_physical_devices(&loader).unwrap();
+        instance.create_device(loader.clone(), &devices[0],
+                               device_create_info()).unwrap()
+    };
+    assert_eq!(driver.live_objects(), 2);
+    assert!(!device.instance().inner().is_null());
+    drop(device);
+    assert_eq!(driver.live_objects(), 0);
+}
+
+#[cfg(feature = "ext_debug_report")]
+#[test]
+fn routes_debug_messages_to_closure() {
+    use std::sync::{Arc, Mutex};
+    use vks::{VK_DEBUG_REPORT_ERROR_BIT_EXT, VK_DEBUG_REPORT_INFORMATION_BIT_EXT,
+              VK_DEBUG_REPORT_OBJECT_TYPE_IMAGE_EXT};
+    use sarek::instance::{DebugMessage, DebugReportObjectType, DEBUG_REPORT_ERROR_BIT,
+                          DEBUG_REPORT_WARNING_BIT};
+
+    let driver = driver();
+    driver.add_instance_extension("VK_EXT_debug_report", 8);
+    let (instance, loader) = Instance::new(
+        driver.loader(),
+        create_info(vec![], vec!["VK_EXT_debug_report"], vec![])).unwrap();
+
+    let messages: Arc<Mutex<Vec<DebugMessage>>> = Arc::new(Mutex::new(Vec::new()));
+    let sink = messages.clone();
+    let callback = instance.get_debug_callback(
+        loader, DEBUG_REPORT_ERROR_BIT | DEBUG_REPORT_WARNING_BIT,
+        move |message| {
+            sink.lock().unwrap().push(message.clone());
+            message.message_code == 42
+        }).unwrap();
+
+    assert!(!driver.debug_report(VK_DEBUG_REPORT_INFORMATION_BIT_EXT,
+                                 VK_DEBUG_REPORT_OBJECT_TYPE_IMAGE_EXT, 7, 1, "Mock", "info"));
+    assert!(driver.debug_report(VK_DEBUG_REPORT_ERROR_BIT_EXT,
+                                VK_DEBUG_REPORT_OBJECT_TYPE_IMAGE_EXT, 7, 42, "Mock", "bad"));
+    {
+        let messages = messages.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].is_error());
+        assert_eq!(messages[0].object_type, DebugReportObjectType::Image);
+        assert_eq!(messages[0].object, 7);
+        assert_eq!(messages[0].layer_prefix, "Mock");
+        assert_eq!(messages[0].message, "bad");
+    }
+
+    drop(callback);
+    assert!(!driver.debug_report(VK_DEBUG_REPORT_ERROR_BIT_EXT,
+                                 VK_DEBUG_REPORT_OBJECT_TYPE_IMAGE_EXT, 7, 42, "Mock", "bad"));
+}
+
+#[cfg(feature = "ext_debug_report")]
+#[test]
+fn contains_panics_in_debug_closures() {
+    use vks::{VK_DEBUG_REPORT_ERROR_BIT_EXT, VK_DEBUG_REPORT_OBJECT_TYPE_IMAGE_EXT};
+    use sarek::instance::DEBUG_REPORT_ERROR_BIT;
+
+    let driver = driver();
+    driver.add_instance_extension("VK_EXT_debug_report", 8);
+    let (instance, loader) = Instance::new(
+        driver.loader(),
+        create_info(vec![], vec!["VK_EXT_debug_report"], vec![])).unwrap();
+    let _callback = instance.get_debug_callback(loader, DEBUG_REPORT_ERROR_BIT, |_| {
+        panic!("closure panicked");
+    }).unwrap();
+
+    // The panic does not unwind into the driver, and the call is not aborted
+    assert!(!driver.debug_report(VK_DEBUG_REPORT_ERROR_BIT_EXT,
+                                 VK_DEBUG_REPORT_OBJECT_TYPE_IMAGE_EXT, 7, 42, "Mock", "bad"));
+}
+
+#[cfg(feature = "ext_debug_report")]
+fn capturing_instance(driver: &MockDriver) -> (Instance, InstanceLoader)
+{
+    driver.add_instance_extension("VK_EXT_debug_report", 8);
+    Instance::new(driver.loader(),
+                  create_info(vec![], vec!["VK_EXT_debug_report"], vec![])).unwrap()
+}
+
+#[cfg(feature = "ext_debug_report")]
+#[test]
+fn captures_debug_messages() {
+    use vks::{VK_DEBUG_REPORT_ERROR_BIT_EXT, VK_DEBUG_REPORT_WARNING_BIT_EXT,
+              VK_DEBUG_REPORT_OBJECT_TYPE_DEVICE_EXT};
+    use sarek::instance::{DEBUG_REPORT_ERROR_BIT, DEBUG_REPORT_WARNING_BIT};
+
+    let driver = driver();
+    let (instance, loader) = capturing_instance(&driver);
+    let capture = instance.capture_debug_messages(
+        loader, DEBUG_REPORT_ERROR_BIT | DEBUG_REPORT_WARNING_BIT).unwrap();
+
+    driver.debug_report(VK_DEBUG_REPORT_WARNING_BIT_EXT,
+                        VK_DEBUG_REPORT_OBJECT_TYPE_DEVICE_EXT, 1, 2, "Mock", "careful");
+    driver.debug_report(VK_DEBUG_REPORT_ERROR_BIT_EXT,
+                        VK_DEBUG_REPORT_OBJECT_TYPE_DEVICE_EXT, 1, 3, "Mock", "wrong");
+
+    let errors = capture.take_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "wrong");
+    let messages = capture.take_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].message, "careful");
+    assert!(capture.take_messages().is_empty());
+}
+
+#[cfg(feature = "ext_debug_report")]
+#[test]
+#[should_panic(expected = "Vulkan reported errors")]
+fn panics_on_captured_errors() {
+    use vks::{VK_DEBUG_REPORT_ERROR_BIT_EXT, VK_DEBUG_REPORT_OBJECT_TYPE_DEVICE_EXT};
+    use sarek::instance::DEBUG_REPORT_ERROR_BIT;
+
+    let driver = driver();
+    let (instance, loader) = capturing_instance(&driver);
+    let _capture = instance.capture_debug_messages(loader, DEBUG_REPORT_ERROR_BIT).unwrap()
+        .panic_on_error(true);
+    driver.debug_report(VK_DEBUG_REPORT_ERROR_BIT_EXT,
+                        VK_DEBUG_REPORT_OBJECT_TYPE_DEVICE_EXT, 1, 3, "Mock", "wrong");
+}
+
+#[cfg(feature = "ext_debug_marker")]
+#[test]
+fn names_objects_and_records_markers() {
+    use std::ptr;
+
+    let driver = MockDriver::new();
+    driver.add_physical_device(
+        MockPhysicalDevice::new("Mock", PhysicalDeviceType::DiscreteGPU)
+            .with_extension("VK_EXT_debug_marker"));
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+    let mut create_info = device_create_info();
+    create_info.enabled_extension_names = vec!["VK_EXT_debug_marker".to_owned()];
+    let device = instance.create_device(loader.clone(), &devices[0], create_info).unwrap();
+    let queue = device.get_queue(0, 0).unwrap();
+
+    device.set_object_name(&queue, "Graphics queue").unwrap();
+    device.set_object_tag(&device, 9, &[1, 2, 3]).unwrap();
+    assert_eq!(driver.object_name(queue.inner() as u64), Some("Graphics queue".to_owned()));
+    assert_eq!(driver.object_tag(device.inner() as u64), Some((9, vec![1, 2, 3])));
+
+    device.cmd_debug_marker_begin(ptr::null_mut(), "Shadow pass", [1.0, 0.0, 0.0, 1.0]).unwrap();
+    device.cmd_debug_marker_insert(ptr::null_mut(), "Draw", [0.0; 4]).unwrap();
+    device.cmd_debug_marker_end(ptr::null_mut()).unwrap();
+    assert_eq!(driver.markers(), vec!["begin Shadow pass", "insert Draw", "end"]);
+
+    // Without the extension nothing reaches the driver
+    let mut create_info = device_create_info();
+    create_info.enabled_extension_names = vec![];
+    let device = instance.create_device(loader.clone(), &devices[0], create_info).unwrap();
+    match device.set_object_name(&device, "Device") {
+        Err(Error::ExtensionNotEnabled(name)) => assert_eq!(name, "VK_EXT_debug_marker"),
+        _ => panic!("Expected Error::ExtensionNotEnabled"),
+    }
+    assert!(device.cmd_debug_marker_end(ptr::null_mut()).is_err());
+    assert_eq!(driver.markers().len(), 3);
+}
+
+#[cfg(feature = "ext_validation_flags")]
+#[test]
+fn chains_disabled_validation_checks() {
+    use sarek::instance::ValidationCheck;
+    use sarek::instance::physical_device::ExtensionProperties;
+
+    let driver = driver();
+    let mut info = create_info(vec![], vec![], vec![]);
+    info.disabled_validation_checks = vec![ValidationCheck::Shaders];
+    let _ = Instance::new(driver.loader(), info).unwrap();
+    // No layer offers the extension, so nothing is chained
+    assert!(driver.disabled_validation_checks().is_empty());
+
+    driver.add_instance_layer(MockLayer {
+        layer_name: "VK_LAYER_LUNARG_core_validation".to_owned(),
+        description: "Mock validation".to_owned(),
+        extensions: vec![ExtensionProperties {
+            extension_name: "VK_EXT_validation_flags".to_owned(),
+            spec_version: 1,
+        }],
+    });
+    let mut info = create_info(vec!["VK_LAYER_LUNARG_core_validation"], vec![], vec![]);
+    info.disabled_validation_checks = vec![ValidationCheck::All, ValidationCheck::Shaders];
+    let (instance, _loader) = Instance::new(driver.loader(), info).unwrap();
+    assert!(instance.is_extension_enabled("VK_EXT_validation_flags"));
+    let checks: Vec<_> = driver.disabled_validation_checks().iter()
+        .map(|check| check.as_raw())
+        .collect();
+    assert_eq!(checks, vec![ValidationCheck::All as _, ValidationCheck::Shaders as _]);
+}
+
+#[test]
+fn selects_physical_device() {
+    use sarek::instance::physical_device::{PhysicalDeviceSelector, RejectionReason,
+                                           QUEUE_FLAGS_GRAPHICS_BIT};
+
+    let driver = driver();
+    let (instance, loader) = instance(&driver);
+
+    let selected = PhysicalDeviceSelector::new()
+        .require_queue(QUEUE_FLAGS_GRAPHICS_BIT)
+        .select(&instance, &loader).unwrap();
+    assert_eq!(selected.properties.device_name, "Mock Discrete");
+    assert_eq!(selected.queue_family_indices, vec![0]);
+
+    let selected = PhysicalDeviceSelector::new()
+        .prefer(vec![PhysicalDeviceType::IntegratedGPU])
+        .select(&instance, &loader).unwrap();
+    assert_eq!(selected.properties.device_name, "Mock Integrated");
+
+    let mut features = PhysicalDeviceFeatures::default();
+    features.geometry_shader = 1;
+    let result = PhysicalDeviceSelector::new()
+        .require_extension("VK_KHR_swapchain")
+        .require_features(features)
+        .min_api_version(Version(1,1,0))
+        .select(&instance, &loader);
+    match result {
+        Err(Error::NoSuitablePhysicalDevice(rejections)) => {
+            assert_eq!(rejections.len(), 2);
+            assert_eq!(rejections[0].device_name, "Mock Discrete");
+            assert_eq!(rejections[0].reasons.len(), 2);
+            assert_eq!(rejections[1].reasons.len(), 3);
+            match rejections[1].reasons[1] {
+                RejectionReason::MissingExtension(ref missing) =>
+                    assert_eq!(missing.name, "VK_KHR_swapchain"),
+                _ => panic!("Expected RejectionReason::MissingExtension"),
+            }
+        },
+        _ => panic!("Expected Error::NoSuitablePhysicalDevice"),
+    }
+}
+
+#[test]
+fn compares_physical_device_features() {
+    let mut a = PhysicalDeviceFeatures::default();
+    a.geometry_shader = 1;
+    a.sampler_anisotropy = 1;
+    let mut b = PhysicalDeviceFeatures::default();
+    b.sampler_anisotropy = 1;
+    b.wide_lines = 1;
+
+    assert_eq!(a.intersection(&b).enabled_names(), vec!["sampler_anisotropy"]);
+    assert_eq!(a.union(&b).enabled_names(),
+               vec!["geometry_shader", "wide_lines", "sampler_anisotropy"]);
+    assert!(a.intersection(&b).is_subset_of(&a));
+    assert!(!a.is_subset_of(&b));
+    assert_eq!(a.missing_from(&b), vec!["geometry_shader"]);
+}
+
+#[test]
+fn rejects_unsupported_device_features() {
+    let driver = MockDriver::new();
+    let mut physical_device = MockPhysicalDevice::new("Mock", PhysicalDeviceType::DiscreteGPU)
+        .with_extension("VK_KHR_swapchain");
+    physical_device.features.sampler_anisotropy = 1;
+    driver.add_physical_device(physical_device);
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+
+    let supported = devices[0].get_features(&loader).unwrap();
+    assert_eq!(supported.enabled_names(), vec!["sampler_anisotropy"]);
+
+    let mut requested = supported.clone();
+    requested.shader_float64 = 1;
+    let mut info = device_create_info();
+    info.enabled_features = requested;
+    match instance.create_device(loader.clone(), &devices[0], info) {
+        Err(Error::NotPresent(missing)) => {
+            assert_eq!(missing.len(), 1);
+            assert_eq!(missing[0].kind, NameKind::DeviceFeature);
+            assert_eq!(missing[0].name, "shader_float64");
+        },
+        _ => panic!("Expected Error::NotPresent"),
+    }
+    assert!(!driver.calls().contains(&"vkCreateDevice".to_owned()));
+}
+
+#[cfg(feature = "khr_get_physical_device_properties2")]
+#[test]
+fn queries_properties2_with_output_chains() {
+    let driver = driver();
+    driver.add_instance_extension("VK_KHR_get_physical_device_properties2", 1);
+    let (instance, loader) = Instance::new(
+        driver.loader(),
+        create_info(vec![], vec!["VK_KHR_get_physical_device_properties2"], vec![])).unwrap();
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+
+    let (properties, ()) = devices[0].get_properties2::<()>(&loader).unwrap();
+    assert_eq!(properties.device_name, "Mock Discrete");
+
+    let (features, ()) = devices[0].get_features2::<()>(&loader).unwrap();
+    assert!(features.enabled_names().is_empty());
+
+    let queue_families = devices[0].get_queue_family_properties2::<()>(&loader).unwrap();
+    assert_eq!(queue_families.len(), 1);
+    assert_eq!(queue_families[0].0.queue_count, 1);
+
+    let (memory, ()) = devices[0].get_memory_properties2::<()>(&loader).unwrap();
+    assert_eq!(memory.memory_types.len(), 2);
+}
+
+#[cfg(feature = "khr_push_descriptor")]
+#[test]
+fn fills_chained_extension_properties() {
+    use sarek::instance::physical_device::PushDescriptorProperties;
+
+    let driver = MockDriver::new();
+    driver.add_instance_extension("VK_KHR_get_physical_device_properties2", 1);
+    let mut physical_device = MockPhysicalDevice::new("Mock", PhysicalDeviceType::DiscreteGPU);
+    physical_device.max_push_descriptors = 16;
+    driver.add_physical_device(physical_device);
+    let (instance, loader) = Instance::new(
+        driver.loader(),
+        create_info(vec![], vec!["VK_KHR_get_physical_device_properties2"], vec![])).unwrap();
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+
+    let (properties, push) = devices[0].get_properties2::<PushDescriptorProperties>(&loader)
+        .unwrap();
+    assert_eq!(properties.device_name, "Mock");
+    assert_eq!(push.max_push_descriptors, 16);
+
+    // The same structure may appear with others in a tuple
+    let (_, ((), push)) = devices[0].get_properties2::<((), PushDescriptorProperties)>(&loader)
+        .unwrap();
+    assert_eq!(push.max_push_descriptors, 16);
+}
+
+#[test]
+fn queries_image_format_properties() {
+    let driver = MockDriver::new();
+    let mut physical_device = MockPhysicalDevice::new("Mock", PhysicalDeviceType::DiscreteGPU);
+    physical_device.image_format_properties.push((
+        Format::R8g8b8a8Unorm, ImageTiling::Optimal,
+        ImageFormatProperties {
+            max_extent: Extent3D { width: 4096, height: 4096, depth: 1 },
+            max_mip_levels: 13,
+            max_array_layers: 256,
+            sample_counts: VK_SAMPLE_COUNT_1_BIT | VK_SAMPLE_COUNT_4_BIT,
+            max_resource_size: 1 << 31,
+        }));
+    physical_device.sparse_image_format_properties.push((
+        Format::R8g8b8a8Unorm,
+        SparseImageFormatProperties {
+            aspect_mask: IMAGE_ASPECT_COLOR_BIT,
+            image_granularity: Extent3D { width: 128, height: 128, depth: 1 },
+            flags: SparseImageFormatFlags::empty(),
+        }));
+    driver.add_physical_device(physical_device);
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+
+    let usage = IMAGE_USAGE_SAMPLED_BIT | IMAGE_USAGE_TRANSFER_DST_BIT;
+    let properties = devices[0].get_image_format_properties(
+        &loader, Format::R8g8b8a8Unorm, ImageType::Type2D, ImageTiling::Optimal, usage,
+        ImageCreateFlags::empty()).unwrap().unwrap();
+    assert_eq!(properties.max_extent.width, 4096);
+    assert_eq!(properties.max_mip_levels, 13);
+
+    // VK_ERROR_FORMAT_NOT_SUPPORTED is not an error
+    assert!(devices[0].get_image_format_properties(
+        &loader, Format::R8g8b8a8Unorm, ImageType::Type2D, ImageTiling::Linear, usage,
+        ImageCreateFlags::empty()).unwrap().is_none());
+
+    driver.fail("vkGetPhysicalDeviceImageFormatProperties", VK_ERROR_OUT_OF_HOST_MEMORY);
+    match devices[0].get_image_format_properties(
+        &loader, Format::R8g8b8a8Unorm, ImageType::Type2D, ImageTiling::Optimal, usage,
+        ImageCreateFlags::empty())
+    {
+        Err(Error::Vulkan(e)) => {
+            assert_eq!(e.entry_point, "vkGetPhysicalDeviceImageFormatProperties");
+            assert_eq!(e.method, Some("PhysicalDevice::get_image_format_properties"));
+        },
+        _ => panic!("Expected Error::Vulkan"),
+    }
+    driver.clear_failure("vkGetPhysicalDeviceImageFormatProperties");
+
+    let sparse = devices[0].get_sparse_image_format_properties(
+        &loader, Format::R8g8b8a8Unorm, ImageType::Type2D, VK_SAMPLE_COUNT_1_BIT, usage,
+        ImageTiling::Optimal).unwrap();
+    assert_eq!(sparse.len(), 1);
+    assert_eq!(sparse[0].aspect_mask, IMAGE_ASPECT_COLOR_BIT);
+    assert_eq!(sparse[0].image_granularity.width, 128);
+    assert!(devices[0].get_sparse_image_format_properties(
+        &loader, Format::D32Sfloat, ImageType::Type2D, VK_SAMPLE_COUNT_1_BIT, usage,
+        ImageTiling::Optimal).unwrap().is_empty());
+}
+
+#[test]
+fn reports_implementation_capabilities() {
+    let driver = driver();
+    let mut physical_device = MockPhysicalDevice::new("Mock \"Quoted\"",
+                                                      PhysicalDeviceType::CPU);
+    physical_device.features.wide_lines = 1;
+    physical_device.format_properties.push((Format::R8g8b8a8Unorm, FormatProperties {
+        linear_tiling_features: FORMAT_FEATURE_SAMPLED_IMAGE_BIT,
+        optimal_tiling_features: FORMAT_FEATURE_SAMPLED_IMAGE_BIT
+            | FORMAT_FEATURE_COLOR_ATTACHMENT_BIT,
+        buffer_features: FormatFeatureFlags::empty(),
+    }));
+    driver.add_physical_device(physical_device);
+
+    let report = Report::collect(driver.loader()).unwrap();
+    assert_eq!(report.extensions.len(), 1);
+    assert_eq!(report.layers.len(), 1);
+    assert_eq!(report.devices.len(), 3);
+    assert_eq!(report.devices[2].formats.len(), 1);
+    assert_eq!(report.devices[2].formats[0].0, Format::R8g8b8a8Unorm);
+
+    let text = report.to_text();
+    assert!(text.contains("device_name = Mock Discrete\n"));
+    assert!(text.contains("wide_lines = true\n"));
+    assert!(text.contains("VK_LAYER_LUNARG_parameter_validation"));
+    assert!(text.contains("R8g8b8a8Unorm:\n"));
+
+    let json = report.to_json();
+    assert!(json.starts_with("{\n  \"instance_version\": \"1.0.0\""));
+    assert!(json.contains("\"device_name\": \"Mock \\\"Quoted\\\"\""));
+    assert!(json.contains("\"VK_KHR_surface\": 25"));
+}
+
+fn add_profile_device(driver: &MockDriver)
+{
+    let mut physical_device = MockPhysicalDevice::new("Mock", PhysicalDeviceType::DiscreteGPU)
+        .with_extension("VK_KHR_swapchain");
+    physical_device.properties.limits.max_image_dimension_2d = 4096;
+    physical_device.properties.limits.max_compute_work_group_size = [128, 128, 64];
+    physical_device.properties.limits.min_uniform_buffer_offset_alignment = 256;
+    physical_device.properties.limits.line_width_range = [1.0, 8.0];
+    physical_device.properties.limits.max_sampler_lod_bios = 16.0;
+    physical_device.properties.limits.min_memory_map_alignment = 4096;
+    physical_device.features.sampler_anisotropy = 1;
+    physical_device.format_properties.push((Format::D24UnormS8Uint, FormatProperties {
+        linear_tiling_features: FormatFeatureFlags::empty(),
+        optimal_tiling_features: FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT,
+        buffer_features: FormatFeatureFlags::empty(),
+    }));
+    driver.add_physical_device(physical_device);
+}
+
+#[test]
+fn checks_capability_profiles() {
+    let driver = MockDriver::new();
+    add_profile_device(&driver);
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+
+    let mut profile = Profile::default();
+    profile.limits.insert("max_image_dimension_2d".to_owned(), LimitRequirement::Scalar(4096.0));
+    profile.limits.insert("max_compute_work_group_size".to_owned(),
+                          LimitRequirement::Array(vec![64.0, 64.0, 64.0]));
+    profile.limits.insert("min_uniform_buffer_offset_alignment".to_owned(),
+                          LimitRequirement::Scalar(256.0));
+    profile.limits.insert("line_width_range".to_owned(),
+                          LimitRequirement::Array(vec![1.0, 4.0]));
+    profile.limits.insert("max_sampler_lod_bias".to_owned(), LimitRequirement::Scalar(16.0));
+    profile.limits.insert("min_memory_map_alignment".to_owned(), LimitRequirement::Scalar(64.0));
+    profile.features.push("sampler_anisotropy".to_owned());
+    profile.extensions.push("VK_KHR_swapchain".to_owned());
+    profile.formats.insert("D24UnormS8Uint".to_owned(), FormatRequirement {
+        optimal_tiling_features: vec!["FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT".to_owned()],
+        ..Default::default()
+    });
+    assert_eq!(devices[0].check_profile(&loader, &profile).unwrap(), vec![]);
+
+    profile.limits.insert("max_image_dimension_2d".to_owned(), LimitRequirement::Scalar(8192.0));
+    profile.limits.insert("min_uniform_buffer_offset_alignment".to_owned(),
+                          LimitRequirement::Scalar(64.0));
+    profile.features.push("geometry_shader".to_owned());
+    profile.features.push("geometry_shadr".to_owned());
+    profile.extensions.push("VK_KHR_push_descriptor".to_owned());
+    profile.formats.insert("D24UnormS8Uint".to_owned(), FormatRequirement {
+        optimal_tiling_features: vec!["FORMAT_FEATURE_SAMPLED_IMAGE_BIT".to_owned()],
+        ..Default::default()
+    });
+
+    let violations = devices[0].check_profile(&loader, &profile).unwrap();
+    assert_eq!(violations.len(), 6);
+    match violations[0] {
+        ProfileViolation::Limit { ref name, ref actual, .. } => {
+            assert_eq!(name, "max_image_dimension_2d");
+            assert_eq!(*actual, LimitValue::U32(4096));
+        },
+        ref other => panic!("Unexpected {:?}", other),
+    }
+    match violations[1] {
+        ProfileViolation::Limit { ref name, .. } =>
+            assert_eq!(name, "min_uniform_buffer_offset_alignment"),
+        ref other => panic!("Unexpected {:?}", other),
+    }
+    assert_eq!(violations[2], ProfileViolation::MissingFeature("geometry_shader".to_owned()));
+    assert_eq!(violations[3],
+               ProfileViolation::UnknownName("feature geometry_shadr".to_owned()));
+    match violations[4] {
+        ProfileViolation::MissingExtension(ref missing) =>
+            assert_eq!(missing.name, "VK_KHR_push_descriptor"),
+        ref other => panic!("Unexpected {:?}", other),
+    }
+    assert_eq!(violations[5], ProfileViolation::MissingFormatFeatures {
+        format: Format::D24UnormS8Uint,
+        set: FormatFeatureSet::OptimalTiling,
+        missing: FORMAT_FEATURE_SAMPLED_IMAGE_BIT,
+    });
+}
+
+#[cfg(feature = "profile")]
+#[test]
+fn reads_capability_profiles() {
+    let driver = MockDriver::new();
+    add_profile_device(&driver);
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+
+    let toml = Profile::from_toml(r#"
+extensions = ["VK_KHR_swapchain"]
+features = ["sampler_anisotropy"]
+
+[limits]
+max_image_dimension_2d = 4096
+max_compute_work_group_size = [128, 128, 64]
+
+[formats.D24UnormS8Uint]
+optimal_tiling_features = ["FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT"]
+"#).unwrap();
+    assert_eq!(toml.limits["max_compute_work_group_size"],
+               LimitRequirement::Array(vec![128.0, 128.0, 64.0]));
+    assert_eq!(devices[0].check_profile(&loader, &toml).unwrap(), vec![]);
+
+    let json = Profile::from_json(r#"{
+        "limits": { "max_image_dimension_2d": 16384 },
+        "features": ["sampler_anisotropy"]
+    }"#).unwrap();
+    assert_eq!(devices[0].check_profile(&loader, &json).unwrap().len(), 1);
+
+    assert!(Profile::from_json(r#"{ "limitz": {} }"#).is_err());
+}
+
+#[test]
+fn enumerates_device_layers_and_their_extensions() {
+    let driver = MockDriver::new();
+    let mut physical_device = MockPhysicalDevice::new("Mock", PhysicalDeviceType::DiscreteGPU)
+        .with_extension("VK_KHR_swapchain");
+    physical_device.layers.push(MockLayer {
+        layer_name: "VK_LAYER_LUNARG_device_limits".to_owned(),
+        description: "Mock device layer".to_owned(),
+        extensions: vec![ExtensionProperties {
+            extension_name: "VK_EXT_debug_marker".to_owned(),
+            spec_version: 4,
+        }],
+    });
+    driver.add_physical_device(physical_device);
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+
+    let layers = devices[0].enumerate_layer_properties(&loader).unwrap();
+    assert_eq!(layers.len(), 1);
+    assert_eq!(layers[0].layer_name, "VK_LAYER_LUNARG_device_limits");
+    assert_eq!(layers[0].description, "Mock device layer");
+
+    let extensions = devices[0].get_extension_properties(&loader, None).unwrap();
+    assert_eq!(extensions.len(), 1);
+    assert_eq!(extensions[0].extension_name, "VK_KHR_swapchain");
+
+    let extensions = devices[0].get_extension_properties(
+        &loader, Some("VK_LAYER_LUNARG_device_limits")).unwrap();
+    assert_eq!(extensions.len(), 1);
+    assert_eq!(extensions[0].extension_name, "VK_EXT_debug_marker");
+    assert_eq!(extensions[0].spec_version, 4);
+
+    match devices[0].get_extension_properties(&loader, Some("VK_LAYER_missing")) {
+        Err(e) => assert_eq!(e.vk_result(), Some(VK_ERROR_LAYER_NOT_PRESENT)),
+        Ok(_) => panic!("Expected an error"),
+    }
+}
+
+#[test]
+fn describes_formats() {
+    use sarek::{Component, NumericType};
+    use sarek::image::{IMAGE_ASPECT_DEPTH_BIT, IMAGE_ASPECT_STENCIL_BIT};
+
+    let rgba = Format::R8g8b8a8Unorm;
+    assert_eq!(rgba.block_size(), 4);
+    assert_eq!(rgba.component_count(), 4);
+    assert_eq!(rgba.component_bits(Component::G), Some(8));
+    assert_eq!(rgba.component_bits(Component::D), None);
+    assert_eq!(rgba.numeric_type(), Some(NumericType::Unorm));
+    assert_eq!(rgba.aspect_mask(), IMAGE_ASPECT_COLOR_BIT);
+    assert!(!rgba.is_compressed() && !rgba.is_srgb());
+    assert_eq!(rgba.to_srgb(), Some(Format::R8g8b8a8Srgb));
+    assert_eq!(Format::R8g8b8a8Srgb.to_unorm(), Some(rgba));
+    assert_eq!(Format::R16Unorm.to_srgb(), None);
+
+    let depth = Format::D24UnormS8Uint;
+    assert!(depth.is_depth() && depth.is_stencil());
+    assert_eq!(depth.components(), vec![(Component::D, 24), (Component::S, 8)]);
+    assert_eq!(depth.aspect_mask(), IMAGE_ASPECT_DEPTH_BIT | IMAGE_ASPECT_STENCIL_BIT);
+    assert_eq!(Format::D32Sfloat.aspect_mask(), IMAGE_ASPECT_DEPTH_BIT);
+    assert_eq!(Format::D32SfloatS8Uint.numeric_type(), Some(NumericType::Sfloat));
+
+    let bc1 = Format::Bc1RgbaSrgbBlock;
+    assert!(bc1.is_compressed() && bc1.is_srgb());
+    assert_eq!(bc1.block_size(), 8);
+    let extent = bc1.block_extent();
+    assert_eq!((extent.width, extent.height, extent.depth), (4, 4, 1));
+    let extent = Format::Astc8x6UnormBlock.block_extent();
+    assert_eq!((extent.width, extent.height, extent.depth), (8, 6, 1));
+
+    assert_eq!(Format::Undefined.block_size(), 0);
+    assert_eq!(Format::Undefined.numeric_type(), None);
+    assert!(Format::Undefined.aspect_mask().is_empty());
+    assert!(Format::all().iter().all(|f| f.block_size() > 0 && f.component_count() > 0));
+}
+
+#[test]
+fn finds_supported_formats() {
+    let driver = MockDriver::new();
+    let mut physical_device = MockPhysicalDevice::new("Mock", PhysicalDeviceType::DiscreteGPU);
+    for &format in &[Format::D24UnormS8Uint, Format::D16Unorm] {
+        physical_device.format_properties.push((format, FormatProperties {
+            linear_tiling_features: FormatFeatureFlags::empty(),
+            optimal_tiling_features: FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT,
+            buffer_features: FormatFeatureFlags::empty(),
+        }));
+    }
+    physical_device.format_properties.push((Format::R16g16b16a16Sfloat, FormatProperties {
+        linear_tiling_features: FORMAT_FEATURE_SAMPLED_IMAGE_BIT,
+        optimal_tiling_features: FORMAT_FEATURE_SAMPLED_IMAGE_BIT,
+        buffer_features: FormatFeatureFlags::empty(),
+    }));
+    physical_device.format_properties.push((Format::B10g11r11UfloatPack32, FormatProperties {
+        linear_tiling_features: FormatFeatureFlags::empty(),
+        optimal_tiling_features: FORMAT_FEATURE_SAMPLED_IMAGE_BIT
+            | FORMAT_FEATURE_COLOR_ATTACHMENT_BIT,
+        buffer_features: FormatFeatureFlags::empty(),
+    }));
+    driver.add_physical_device(physical_device);
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+
+    let found = devices[0].find_supported_format(
+        &loader, &[Format::R8g8b8a8Unorm, Format::R16g16b16a16Sfloat], ImageTiling::Linear,
+        FORMAT_FEATURE_SAMPLED_IMAGE_BIT).unwrap();
+    assert_eq!(found, Some(Format::R16g16b16a16Sfloat));
+    assert_eq!(devices[0].find_supported_format(
+        &loader, &[Format::D16Unorm], ImageTiling::Linear,
+        FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT).unwrap(), None);
+
+    assert_eq!(devices[0].find_depth_format(&loader).unwrap(), Some(Format::D16Unorm));
+    assert_eq!(devices[0].find_depth_stencil_format(&loader).unwrap(),
+               Some(Format::D24UnormS8Uint));
+    assert_eq!(devices[0].find_hdr_format(&loader).unwrap(),
+               Some(Format::B10g11r11UfloatPack32));
+
+    let table = devices[0].get_format_table(&loader).unwrap();
+    assert_eq!(table.find_depth_format(), Some(Format::D16Unorm));
+    assert_eq!(table.find_depth_stencil_format(), Some(Format::D24UnormS8Uint));
+    assert_eq!(table.find_hdr_format(), Some(Format::B10g11r11UfloatPack32));
+    assert_eq!(table.formats_supporting(ImageTiling::Optimal,
+                                        FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT),
+               vec![Format::D16Unorm, Format::D24UnormS8Uint]);
+    assert_eq!(table.supported_formats().len(), 4);
+    assert!(table.get(Format::R8g8b8a8Unorm).optimal_tiling_features.is_empty());
+}
+
+#[test]
+fn plans_queues() {
+    use sarek::instance::physical_device::{QueuePlanner, QueueAssignment,
+                                           QUEUE_FLAGS_COMPUTE_BIT, QUEUE_FLAGS_TRANSFER_BIT};
+
+    let driver = MockDriver::new();
+    let mut physical_device = MockPhysicalDevice::new("Mock", PhysicalDeviceType::DiscreteGPU);
+    physical_device.queue_families[0].queue_count = 3;
+    let mut compute = physical_device.queue_families[0].clone();
+    compute.queue_flags = QUEUE_FLAGS_COMPUTE_BIT | QUEUE_FLAGS_TRANSFER_BIT;
+    compute.queue_count = 1;
+    let mut whole_image_transfer = compute.clone();
+    whole_image_transfer.queue_flags = QUEUE_FLAGS_TRANSFER_BIT;
+    whole_image_transfer.min_image_transfer_granularity =
+        Extent3D { width: 0, height: 0, depth: 0 };
+    let mut coarse_transfer = whole_image_transfer.clone();
+    coarse_transfer.min_image_transfer_granularity = Extent3D { width: 8, height: 8, depth: 1 };
+    physical_device.queue_families.extend(vec![compute, whole_image_transfer, coarse_transfer]);
+    driver.add_physical_device(physical_device);
+    driver.add_physical_device(MockPhysicalDevice::new("Mock", PhysicalDeviceType::CPU));
+    let mut compute_only = MockPhysicalDevice::new("Mock", PhysicalDeviceType::Other);
+    compute_only.queue_families[0].queue_flags = QUEUE_FLAGS_COMPUTE_BIT;
+    driver.add_physical_device(compute_only);
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+
+    let plan = QueuePlanner::new().plan(&loader, &devices[0]).unwrap();
+    assert_eq!(plan.graphics, Some(QueueAssignment { family_index: 0, queue_index: 0 }));
+    assert_eq!(plan.present, None);
+    assert_eq!(plan.compute, QueueAssignment { family_index: 1, queue_index: 0 });
+    assert_eq!(plan.transfer, QueueAssignment { family_index: 3, queue_index: 0 });
+    assert_eq!(plan.transfer_granularity.width, 8);
+    assert!(plan.has_async_compute() && plan.has_dedicated_transfer());
+    let families: Vec<u32> = plan.queue_create_infos.iter()
+        .map(|info| info.queue_family_index)
+        .collect();
+    assert_eq!(families, vec![0, 1, 3]);
+
+    // Too coarse for the transfer-only families, so share the compute queue
+    let plan = QueuePlanner::new()
+        .max_transfer_granularity(Extent3D { width: 4, height: 4, depth: 1 })
+        .plan(&loader, &devices[0]).unwrap();
+    assert_eq!(plan.transfer, plan.compute);
+    assert!(!plan.has_dedicated_transfer());
+    assert_eq!(plan.queue_create_infos.len(), 2);
+
+    // A single family with a single queue does everything
+    let plan = QueuePlanner::new().plan(&loader, &devices[1]).unwrap();
+    assert_eq!(Some(plan.compute), plan.graphics);
+    assert_eq!(Some(plan.transfer), plan.graphics);
+    assert_eq!(plan.queue_create_infos.len(), 1);
+    assert_eq!(plan.queue_create_infos[0].queue_priorities, vec![1.0]);
+
+    // Compute and transfers without graphics
+    let plan = QueuePlanner::new().plan(&loader, &devices[2]).unwrap();
+    assert_eq!(plan.graphics, None);
+    assert_eq!(plan.compute, QueueAssignment { family_index: 0, queue_index: 0 });
+    assert_eq!(plan.transfer, plan.compute);
+    assert!(!plan.has_async_compute() && !plan.has_dedicated_transfer());
+    assert_eq!(plan.queue_create_infos.len(), 1);
+}
+
+#[test]
+fn submits_to_queues_from_several_threads() {
+    use std::ptr;
+    use std::thread;
+    use sarek::instance::device::SubmitInfo;
+
+    let driver = driver();
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+    let device = instance.create_device(loader.clone(), &devices[0],
+                                        device_create_info()).unwrap();
+    let queue = device.get_queue(0, 0).unwrap();
+    drop(device);
+
+    let threads: Vec<thread::JoinHandle<()>> = (0..4)
+        .map(|_| {
+            let queue = queue.clone();
+            thread::spawn(move || {
+                for _ in 0..10 {
+                    let submit = SubmitInfo {
+                        command_buffers: vec![ptr::null_mut(); 2],
+                        ..Default::default()
+                    };
+                    queue.submit(&[submit], None).unwrap();
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    let submissions = driver.submissions();
+    assert_eq!(submissions.len(), 40);
+    assert!(submissions.iter().all(|s| s.family_index == 0 && s.command_buffer_count == 2));
+
+    queue.wait_idle().unwrap();
+    queue.device().wait_idle().unwrap();
+    let calls = driver.calls();
+    assert!(calls.contains(&"vkQueueWaitIdle".to_owned()));
+    assert!(calls.contains(&"vkDeviceWaitIdle".to_owned()));
+
+    driver.fail("vkQueueSubmit", VK_ERROR_DEVICE_LOST);
+    let error = queue.submit(&[SubmitInfo::default()], None).err().unwrap();
+    assert!(error.is_device_lost());
+    assert!(error.to_string().contains("Queue::submit"));
+}
+
+#[cfg(feature = "khr_swapchain")]
+#[test]
+fn presents_and_reports_suboptimal_swapchains() {
+    use vks::{VK_SUBOPTIMAL_KHR, VK_ERROR_OUT_OF_DATE_KHR};
+    use sarek::instance::device::PresentInfo;
+
+    let driver = driver();
+    let (instance, loader) = instance(&driver);
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+    let device = instance.create_device(loader.clone(), &devices[0],
+                                        device_create_info()).unwrap();
+    let queue = device.get_queue(0, 0).unwrap();
+    let present_info = PresentInfo::default();
+
+    assert_eq!(queue.present(&present_info).unwrap(), Status::Success);
+    driver.fail("vkQueuePresentKHR", VK_SUBOPTIMAL_KHR);
+    assert!(queue.present(&present_info).unwrap().is_suboptimal());
+    driver.fail("vkQueuePresentKHR", VK_ERROR_OUT_OF_DATE_KHR);
+    assert!(queue.present(&present_info).err().unwrap().is_out_of_date());
+}
+
+fn assert_extension_not_enabled<T>(result: Result<T, Error>, extension_name: &str)
+{
+    match result {
+        Err(Error::ExtensionNotEnabled(name)) => assert_eq!(name, extension_name),
+        Err(e) => panic!("Expected Error::ExtensionNotEnabled, got {}", e),
+        Ok(_) => panic!("Expected Error::ExtensionNotEnabled"),
+    }
+}
+
+#[test]
+#[allow(unused_variables)]
+fn refuses_functions_of_extensions_not_enabled() {
+    let driver = driver();
+    driver.add_instance_extension("VK_EXT_debug_report", 8);
+    driver.add_instance_extension("VK_KHR_get_physical_device_properties2", 1);
+    let (instance, loader) = instance(&driver);
+    assert!(loader.enabled_extension_names().is_empty());
+    assert!(!loader.is_extension_enabled("VK_KHR_surface"));
+    let devices = instance.enumerate_physical_devices(&loader).unwrap();
+    let mut create_info = device_create_info();
+    create_info.enabled_extension_names = vec![];
+    let device = instance.create_device(loader.clone(), &devices[0], create_info).unwrap();
+
+    // The core query still works without the extension
+    assert_eq!(devices[0].get_properties(&loader).unwrap().device_name, "Mock Discrete");
+    #[cfg(feature = "khr_get_physical_device_properties2")]
+    assert_extension_not_enabled(devices[0].get_properties2::<()>(&loader),
+                                 "VK_KHR_get_physical_device_properties2");
+
+    #[cfg(feature = "ext_debug_report")]
+    {
+        use sarek::instance::{DebugCallback, DEBUG_REPORT_ERROR_BIT};
+        assert_extension_not_enabled(
+            DebugCallback::new(&instance, loader.clone(), DEBUG_REPORT_ERROR_BIT, |_| false),
+            "VK_EXT_debug_report");
+    }
+
+    #[cfg(feature = "khr_swapchain")]
+    {
+        use sarek::instance::device::PresentInfo;
+        let queue = device.get_queue(0, 0).unwrap();
+        assert_extension_not_enabled(queue.present(&PresentInfo::default()),
+                                     "VK_KHR_swapchain");
+    }
+
+    #[cfg(feature = "ext_debug_marker")]
+    assert_extension_not_enabled(device.set_object_name(&device, "Device"),
+                                 "VK_EXT_debug_marker");
+
+    #[cfg(feature = "khr_surface")]
+    assert_extension_not_enabled(driver.create_surface(&instance, loader.clone()),
+                                 "VK_KHR_surface");
+
+    assert!(!driver.calls().iter().any(|call| {
+        call == "vkGetPhysicalDeviceProperties2KHR" || call == "vkCreateDebugReportCallbackEXT"
+            || call == "vkQueuePresentKHR" || call == "vkDebugMarkerSetObjectNameEXT"
+            || call == "vkCreateSurfaceKHR"
+    }));
+}
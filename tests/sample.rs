@@ -3,26 +3,30 @@ extern crate winit;
 extern crate sarek;
 
 use sarek::{enumerate_instance_extension_properties, enumerate_instance_layer_properties};
-use sarek::{Version, InstanceLoader, Instance};
+use sarek::{Version, HEADER_VERSION, InstanceLoader, Instance};
 use sarek::instance::{ApplicationInfo, InstanceCreateInfo};
+use sarek::info::DeviceReport;
 
 #[test]
 pub fn main() {
+    // Initialize an instance loader
+    let loader = InstanceLoader::load_system().unwrap();
+
     // Print all available extensions (except layer extensions)
-    let eps = enumerate_instance_extension_properties(None).unwrap();
+    let eps = enumerate_instance_extension_properties(&loader, None).unwrap();
     for ep in &eps {
         println!("{} (version {})", ep.extension_name, ep.spec_version);
     }
 
     // Print extensions to a particular layer
     let eps = enumerate_instance_extension_properties(
-        Some("VK_LAYER_LUNARG_parameter_validation")).unwrap();
+        &loader, Some("VK_LAYER_LUNARG_parameter_validation")).unwrap();
     for ep in &eps {
         println!("{} (version {})", ep.extension_name, ep.spec_version);
     }
 
     // Print available layers
-    let lps = enumerate_instance_layer_properties().unwrap();
+    let lps = enumerate_instance_layer_properties(&loader).unwrap();
     for lp in &lps {
         println!("{} (version {}, impl version {}): {}",
                  lp.layer_name, lp.spec_version, lp.implementation_version,
@@ -38,9 +42,6 @@ pub fn main() {
         .with_decorations(true)
         .build(&window_events_loop).unwrap();
 
-    // Initialize an instance loader
-    let loader = InstanceLoader::new();
-
     // Create an instance
     let (instance, loader) = Instance::new(
         loader,
@@ -53,9 +54,16 @@ pub fn main() {
             },
             enabled_layer_count: 1,
             enabled_layer_names: vec!["VK_LAYER_LUNARG_parameter_validation".to_owned()],
+            enabled_extension_names: vec!["VK_KHR_surface".to_owned(),
+                                          "VK_KHR_xlib_surface".to_owned()],
+            optional_extension_names: vec!["VK_EXT_debug_report".to_owned()],
+            allocator: None,
+            disabled_validation_checks: vec![],
         },
     ).unwrap();
 
+    println!("Enabled instance extensions: {:?}", instance.enabled_extension_names());
+
     // Create surface (tie instance to window)
     let _surface = instance.create_surface(loader.clone(), &window).unwrap();
 
@@ -63,10 +71,7 @@ pub fn main() {
     let devices = instance.enumerate_physical_devices(&loader).unwrap();
 
     for device in &devices {
-        let properties = device.get_properties(&loader);
-        println!("Device: {:?}", properties);
-
-        let queue_family_properties =  device.get_queue_family_properties(&loader);
-        println!("Queue Family Properties: {:?}", queue_family_properties);
+        let report = DeviceReport::collect(&loader, device).unwrap();
+        print!("{}", report.to_value().to_text());
     }
 }